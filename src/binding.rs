@@ -0,0 +1,386 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use Event;
+use Button;
+use InputState;
+use WindowId;
+use Key;
+use KeyCode;
+use MouseButton;
+use Modifiers;
+use GamepadButton;
+use GamepadAxis;
+use GamepadState;
+
+/// A single input that can be part of a [`Binding`].
+///
+/// In text, keys are written by their [`KeyCode`] name or as `Scancode:<n>` for unnamed keys,
+/// and other inputs with a prefix: `Mouse:Left`, `Gamepad:A`, `Axis:LeftY>0.5` or
+/// `Axis:LeftY<-0.5`.
+///
+/// [`Binding`]: struct.Binding.html
+/// [`KeyCode`]: enum.KeyCode.html
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BindingInput {
+    Key(Key),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButton),
+    /// Active while the axis is at least as far from the center as `threshold`, on the same side.
+    /// Its value is the distance of the axis from the center.
+    GamepadAxis {
+        axis: GamepadAxis,
+        threshold: f32
+    }
+}
+
+impl BindingInput {
+    /// Returns the input pressed in a key or mouse button press event, for binding whatever the
+    /// user presses next.
+    pub fn from_event(event: &Event) -> Option<BindingInput> {
+        match *event {
+            Event::KeyDown { key, .. } => Some(BindingInput::Key(key)),
+            Event::MouseButtonDown { button, .. } => Some(BindingInput::Mouse(button)),
+            _ => None
+        }
+    }
+
+    fn value<F: Fn(Button) -> bool>(&self, button: &F, gamepad: Option<&GamepadState>) -> f32 {
+        let digital = |b: bool| if b { 1.0 } else { 0.0 };
+        match *self {
+            BindingInput::Key(key) => digital(button(Button::Key(key))),
+            BindingInput::Mouse(b) => digital(button(Button::Mouse(b))),
+            BindingInput::GamepadButton(b) => digital(gamepad.is_some_and(|g| g.button(b))),
+            BindingInput::GamepadAxis { axis, threshold } => {
+                let value = gamepad.map_or(0.0, |g| g.axis(axis));
+                let value = if threshold < 0.0 { -value } else { value };
+                if value > 0.0 && value >= threshold.abs() { value } else { 0.0 }
+            }
+        }
+    }
+}
+
+impl fmt::Display for BindingInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BindingInput::Key(Key::Named(code)) => write!(f, "{}", code),
+            BindingInput::Key(Key::Unnamed(scancode)) => write!(f, "Scancode:{}", scancode),
+            BindingInput::Mouse(button) => write!(f, "Mouse:{}", button),
+            BindingInput::GamepadButton(button) => write!(f, "Gamepad:{}", button),
+            BindingInput::GamepadAxis { axis, threshold } if threshold < 0.0 =>
+                    write!(f, "Axis:{}<{}", axis, threshold),
+            BindingInput::GamepadAxis { axis, threshold } =>
+                    write!(f, "Axis:{}>{}", axis, threshold)
+        }
+    }
+}
+
+impl FromStr for BindingInput {
+    type Err = ParseBindingError;
+
+    fn from_str(s: &str) -> Result<BindingInput, ParseBindingError> {
+        let mut parts = s.splitn(2, ':');
+        let (prefix, name) = match (parts.next(), parts.next()) {
+            (Some(prefix), Some(name)) => (prefix.trim(), name.trim()),
+            _ => return s.trim().parse()
+                    .map(|code| BindingInput::Key(Key::Named(code)))
+                    .map_err(ParseBindingError::new)
+        };
+        if prefix.eq_ignore_ascii_case("scancode") {
+            name.parse()
+                    .map(|scancode| BindingInput::Key(Key::Unnamed(scancode)))
+                    .map_err(|_| ParseBindingError::new(format!("invalid scancode `{}`", name)))
+        } else if prefix.eq_ignore_ascii_case("mouse") {
+            name.parse().map(BindingInput::Mouse).map_err(ParseBindingError::new)
+        } else if prefix.eq_ignore_ascii_case("gamepad") {
+            name.parse().map(BindingInput::GamepadButton).map_err(ParseBindingError::new)
+        } else if prefix.eq_ignore_ascii_case("axis") {
+            let split = match name.find(['<', '>']) {
+                Some(split) => split,
+                None => return Err(ParseBindingError::new(
+                    format!("missing threshold in `{}`", s.trim())
+                ))
+            };
+            let axis = name[..split].trim().parse().map_err(ParseBindingError::new)?;
+            let threshold: f32 = name[split + 1..].trim().parse().map_err(|_| {
+                ParseBindingError::new(format!("invalid threshold in `{}`", s.trim()))
+            })?;
+            // The comparison must agree with the side of the center the threshold is on
+            if (&name[split..split + 1] == "<") != (threshold < 0.0) {
+                return Err(ParseBindingError::new(
+                    format!("threshold in `{}` is on the wrong side of the center", s.trim())
+                ));
+            }
+            Ok(BindingInput::GamepadAxis { axis, threshold })
+        } else {
+            Err(ParseBindingError::new(format!("unknown input type `{}`", prefix)))
+        }
+    }
+}
+
+/// A combination of inputs that triggers an action, like `Ctrl+Shift+S` or `Gamepad:A`.
+///
+/// The binding is active while all of its inputs are, and the modifier keys are held. Unlike with
+/// [`Shortcut::matches()`], other modifiers may be held as well, so a movement key keeps working
+/// while Shift is held for sprinting. This also means `Ctrl+S` is active while Ctrl+Shift+S is
+/// held. In text, modifiers and inputs are separated by `+`, with the modifiers written as
+/// [`Modifiers`] writes them.
+///
+/// [`Shortcut::matches()`]: struct.Shortcut.html#method.matches
+/// [`Modifiers`]: struct.Modifiers.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct Binding {
+    /// Modifier keys that must be held, in addition to any others. Only `SHIFT`, `CONTROL`, `ALT`
+    /// and `SUPER` are supported.
+    pub modifiers: Modifiers,
+    pub inputs: Vec<BindingInput>
+}
+
+const MODIFIER_KEYS: [(Modifiers, KeyCode, KeyCode); 4] = [
+    (Modifiers::SHIFT, KeyCode::LeftShift, KeyCode::RightShift),
+    (Modifiers::CONTROL, KeyCode::LeftControl, KeyCode::RightControl),
+    (Modifiers::ALT, KeyCode::LeftAlt, KeyCode::RightAlt),
+    (Modifiers::SUPER, KeyCode::LeftSuper, KeyCode::RightSuper)
+];
+
+impl Binding {
+    /// Creates a binding to a single input without modifiers.
+    pub fn new(input: BindingInput) -> Binding {
+        Binding {
+            modifiers: Modifiers::empty(),
+            inputs: vec![input]
+        }
+    }
+
+    /// Returns how strongly the binding is activated, as the weakest of its inputs: 1.0 for held
+    /// buttons and the value of axes. `button` tells whether a key or mouse button counts as held.
+    fn value<F: Fn(Button) -> bool>(&self, button: &F, gamepad: Option<&GamepadState>) -> f32 {
+        for &(modifier, left, right) in &MODIFIER_KEYS {
+            if self.modifiers.contains(modifier) && !button(left.into()) && !button(right.into()) {
+                return 0.0;
+            }
+        }
+        self.inputs.iter()
+                .map(|input| input.value(button, gamepad))
+                .fold(None, |min: Option<f32>, v| Some(min.map_or(v, |min| min.min(v))))
+                .unwrap_or(0.0)
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let modifiers = self.modifiers - (Modifiers::CAPSLOCK | Modifiers::NUMLOCK);
+        write!(f, "{}", modifiers)?;
+        let mut first = modifiers.is_empty();
+        for input in &self.inputs {
+            write!(f, "{}{}", if first { "" } else { "+" }, input)?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Binding {
+    type Err = ParseBindingError;
+
+    fn from_str(s: &str) -> Result<Binding, ParseBindingError> {
+        let mut binding = Binding {
+            modifiers: Modifiers::empty(),
+            inputs: vec![]
+        };
+        for part in s.split('+').map(str::trim) {
+            let modifier: Modifiers = part.parse().unwrap_or_else(|_| Modifiers::empty());
+            let lock = modifier.intersects(Modifiers::CAPSLOCK | Modifiers::NUMLOCK);
+            if modifier.is_empty() || lock {
+                binding.inputs.push(part.parse()?);
+            } else {
+                binding.modifiers |= modifier;
+            }
+        }
+        if binding.inputs.is_empty() {
+            return Err(ParseBindingError::new(format!("no inputs in binding `{}`", s.trim())));
+        }
+        Ok(binding)
+    }
+}
+
+/// Maps named actions, like `"jump"` or `"fire"`, to the bindings that trigger them.
+///
+/// Actions are checked against the keyboard and mouse state of a window from an [`InputState`],
+/// and optionally the state of a gamepad. Bindings can be changed at any time, and saved and
+/// loaded as text with `Display` and `FromStr`, one action per line:
+///
+/// ```text
+/// # Comments start with '#'
+/// jump = Space | Gamepad:A
+/// fire = Mouse:Left | Axis:RightTrigger>0.5
+/// save = Ctrl+S
+/// walk_left = A | Left | Axis:LeftX<-0.25
+/// ```
+///
+/// [`InputState`]: struct.InputState.html
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bindings {
+    actions: BTreeMap<String, Vec<Binding>>
+}
+
+impl Bindings {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a binding to an action, keeping its existing bindings.
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        self.actions.entry(action.to_owned()).or_default().push(binding);
+    }
+
+    /// Replaces all bindings of an action.
+    pub fn rebind(&mut self, action: &str, bindings: Vec<Binding>) {
+        self.actions.insert(action.to_owned(), bindings);
+    }
+
+    /// Removes an action and its bindings.
+    pub fn unbind(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    /// Returns the bindings of an action, which are empty for unknown actions.
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map_or(&[], |b| &b[..])
+    }
+
+    /// Returns the names of all actions, in alphabetical order.
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(|a| &a[..])
+    }
+
+    /// Returns whether any binding of the action is active in the window.
+    pub fn is_active(
+        &self, action: &str, input: &InputState, win: WindowId, gamepad: Option<&GamepadState>
+    ) -> bool {
+        self.value(action, input, win, gamepad) > 0.0
+    }
+
+    /// Returns how strongly the action is activated in the window, from 0.0 to 1.0.
+    ///
+    /// Bindings made of buttons count as 1.0 when active, and bindings with axes as the value of
+    /// the axis, so an action bound to both a key and a stick can drive analog movement.
+    pub fn value(
+        &self, action: &str, input: &InputState, win: WindowId, gamepad: Option<&GamepadState>
+    ) -> f32 {
+        let held = |b: Button| input.is_down(win, b);
+        self.strongest(action, &held, gamepad)
+    }
+
+    /// Returns whether the action became active this frame: it is active, or was pressed and
+    /// released again since the last [`InputState::end_frame()`], and was not active at the end
+    /// of the previous frame.
+    ///
+    /// `previous_gamepad` is the gamepad state of the previous frame, as gamepads are polled
+    /// rather than reported through events.
+    ///
+    /// [`InputState::end_frame()`]: struct.InputState.html#method.end_frame
+    pub fn was_triggered(
+        &self, action: &str, input: &InputState, win: WindowId,
+        gamepad: Option<&GamepadState>, previous_gamepad: Option<&GamepadState>
+    ) -> bool {
+        let now = |b: Button| input.is_down(win, b) || input.was_pressed_this_frame(win, b);
+        // Whether a button was held at the end of the previous frame. Presses and releases are
+        // not ordered, so a button both pressed and released this frame is taken as a tap.
+        let before = |b: Button| if input.was_pressed_this_frame(win, b) {
+            false
+        } else {
+            input.is_down(win, b) || input.was_released_this_frame(win, b)
+        };
+        self.strongest(action, &now, gamepad) > 0.0 &&
+                self.strongest(action, &before, previous_gamepad) == 0.0
+    }
+
+    fn strongest<F: Fn(Button) -> bool>(
+        &self, action: &str, button: &F, gamepad: Option<&GamepadState>
+    ) -> f32 {
+        self.bindings(action).iter()
+                .map(|binding| binding.value(button, gamepad))
+                .fold(0.0, f32::max)
+    }
+}
+
+impl fmt::Display for Bindings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (action, bindings) in &self.actions {
+            write!(f, "{} =", action)?;
+            for (i, binding) in bindings.iter().enumerate() {
+                write!(f, "{} {}", if i == 0 { "" } else { " |" }, binding)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Bindings {
+    type Err = ParseBindingError;
+
+    fn from_str(s: &str) -> Result<Bindings, ParseBindingError> {
+        let mut bindings = Bindings::new();
+        for (i, line) in s.lines().enumerate() {
+            let at_line = |mut e: ParseBindingError| {
+                e.line = Some(i + 1);
+                e
+            };
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut parts = line.splitn(2, '=');
+            let action = parts.next().unwrap().trim();
+            let list = match parts.next() {
+                Some(list) if !action.is_empty() => list.trim(),
+                _ => return Err(at_line(ParseBindingError::new("expected `action = bindings`")))
+            };
+            let mut action_bindings = vec![];
+            if !list.is_empty() {
+                for binding in list.split('|') {
+                    action_bindings.push(binding.parse().map_err(&at_line)?);
+                }
+            }
+            bindings.rebind(action, action_bindings);
+        }
+        Ok(bindings)
+    }
+}
+
+/// Error returned when parsing bindings from text fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseBindingError {
+    line: Option<usize>,
+    message: String
+}
+
+impl ParseBindingError {
+    fn new<T: fmt::Display>(message: T) -> ParseBindingError {
+        ParseBindingError {
+            line: None,
+            message: message.to_string()
+        }
+    }
+
+    /// Returns the line the error is on, when parsing [`Bindings`].
+    ///
+    /// [`Bindings`]: struct.Bindings.html
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+}
+
+impl fmt::Display for ParseBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => f.write_str(&self.message)
+        }
+    }
+}
+
+impl ::std::error::Error for ParseBindingError {}
@@ -0,0 +1,315 @@
+use ClientApi;
+use ContextCreationApi;
+use ContextReleaseBehavior;
+use ContextRobustness;
+use CursorMode;
+use Error;
+use ErrorKind;
+use Glfw;
+use Image;
+use Monitor;
+use OpenGlProfile;
+use Result;
+use SetInputMode;
+use Window;
+use WindowHints;
+
+/// Generates a setter for each window hint.
+macro_rules! hint_setters {
+    ($($(#[$attr:meta])* $name:ident: $ty:ty;)*) => {$(
+        $(#[$attr])*
+        pub fn $name(mut self, $name: $ty) -> Self {
+            self.hints.$name = $name;
+            self
+        }
+    )*};
+}
+
+/// The limits passed to [`WindowBuilder::size_limits()`].
+///
+/// [`WindowBuilder::size_limits()`]: struct.WindowBuilder.html#method.size_limits
+#[derive(Copy, Clone)]
+struct SizeLimits {
+    min_width: Option<i32>,
+    min_height: Option<i32>,
+    max_width: Option<i32>,
+    max_height: Option<i32>
+}
+
+/// Creates windows, as an alternative to [`Glfw::create_window()`] that also sets up the window
+/// before it is shown.
+///
+/// Every [`WindowHints`] field has a setter of the same name. The settings that are not hints,
+/// like the position and icon, are applied after the window is created but before it is shown,
+/// so it never appears in an intermediate state, and [`build()`] fails without leaving a window
+/// behind if any of them fails.
+///
+/// ```no_run
+/// use glfw_wrapper::{ CursorMode, WindowBuilder };
+///
+/// let glfw = glfw_wrapper::init(Default::default()).unwrap();
+/// let window = WindowBuilder::new(1280, 720, "Game")
+///         .context_version(3, 3)
+///         .opengl_profile(glfw_wrapper::OpenGlProfile::Core)
+///         .position(100, 100)
+///         .size_limits(Some(640), Some(360), None, None)
+///         .cursor_mode(CursorMode::Disabled)
+///         .build(&glfw)
+///         .unwrap();
+/// ```
+///
+/// [`Glfw::create_window()`]: struct.Glfw.html#method.create_window
+/// [`WindowHints`]: struct.WindowHints.html
+/// [`build()`]: #method.build
+pub struct WindowBuilder<'a> {
+    hints: WindowHints<'a>,
+    width: i32,
+    height: i32,
+    title: &'a str,
+    monitor: Option<Monitor>,
+    share: Option<&'a Window<'a>>,
+    position: Option<(i32, i32)>,
+    icon: Option<&'a [Image]>,
+    size_limits: Option<SizeLimits>,
+    aspect_ratio: Option<(i32, i32)>,
+    cursor_mode: Option<CursorMode>,
+    opacity: Option<f32>
+}
+
+impl<'a> WindowBuilder<'a> {
+    /// Starts building a windowed mode window with the default hints.
+    pub fn new(width: i32, height: i32, title: &'a str) -> Self {
+        WindowBuilder {
+            hints: WindowHints::default(),
+            width,
+            height,
+            title,
+            monitor: None,
+            share: None,
+            position: None,
+            icon: None,
+            size_limits: None,
+            aspect_ratio: None,
+            cursor_mode: None,
+            opacity: None
+        }
+    }
+
+    /// Replaces all of the hints at once.
+    pub fn hints(mut self, hints: WindowHints<'a>) -> Self {
+        self.hints = hints;
+        self
+    }
+
+    hint_setters! {
+        resizable: bool;
+        visible: bool;
+        decorated: bool;
+        focused: bool;
+        auto_iconify: bool;
+        floating: bool;
+        maximized: bool;
+        center_cursor: bool;
+        transparent_framebuffer: bool;
+        red_bits: Option<i32>;
+        green_bits: Option<i32>;
+        blue_bits: Option<i32>;
+        alpha_bits: Option<i32>;
+        depth_bits: Option<i32>;
+        stencil_bits: Option<i32>;
+        accum_red_bits: Option<i32>;
+        accum_green_bits: Option<i32>;
+        accum_blue_bits: Option<i32>;
+        accum_alpha_bits: Option<i32>;
+        auxiliary_buffers: Option<i32>;
+        samples: Option<i32>;
+        refresh_rate: Option<i32>;
+        stereo: bool;
+        srgb_capable: bool;
+        double_buffer: bool;
+        client_api: ClientApi;
+        context_creation_api: ContextCreationApi;
+        context_robustness: ContextRobustness;
+        context_release_behavior: ContextReleaseBehavior;
+        opengl_forward_compatible: bool;
+        opengl_debug_context: bool;
+        opengl_profile: OpenGlProfile;
+        context_no_error: bool;
+        cocoa_retina_framebuffer: bool;
+        cocoa_frame_name: &'a str;
+        cocoa_graphics_switching: bool;
+        x11_class_name: &'a str;
+        x11_instance_name: &'a str;
+    }
+
+    pub fn context_version(mut self, major: i32, minor: i32) -> Self {
+        self.hints.context_version = (major, minor);
+        self
+    }
+
+    /// Makes the window fullscreen on `monitor`, with its size as the resolution.
+    pub fn fullscreen(mut self, monitor: Monitor) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// Shares the context of `window` with the created window.
+    pub fn share(mut self, window: &'a Window<'a>) -> Self {
+        self.share = Some(window);
+        self
+    }
+
+    /// Sets the position of the client area. Only valid for windowed mode windows.
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Sets the icon, as with [`Window::set_icon()`].
+    ///
+    /// [`Window::set_icon()`]: struct.Window.html#method.set_icon
+    pub fn icon(mut self, icons: &'a [Image]) -> Self {
+        self.icon = Some(icons);
+        self
+    }
+
+    /// Sets the size limits, as with [`Window::set_size_limits()`].
+    ///
+    /// [`Window::set_size_limits()`]: struct.Window.html#method.set_size_limits
+    pub fn size_limits(
+        mut self,
+        min_width: Option<i32>,
+        min_height: Option<i32>,
+        max_width: Option<i32>,
+        max_height: Option<i32>
+    ) -> Self {
+        self.size_limits = Some(SizeLimits { min_width, min_height, max_width, max_height });
+        self
+    }
+
+    /// Locks the aspect ratio of the client area, as with [`Window::set_aspect_ratio()`].
+    ///
+    /// [`Window::set_aspect_ratio()`]: struct.Window.html#method.set_aspect_ratio
+    pub fn aspect_ratio(mut self, numerator: i32, denominator: i32) -> Self {
+        self.aspect_ratio = Some((numerator, denominator));
+        self
+    }
+
+    pub fn cursor_mode(mut self, mode: CursorMode) -> Self {
+        self.cursor_mode = Some(mode);
+        self
+    }
+
+    /// Sets the opacity of the whole window, from 0.0 to 1.0.
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = Some(opacity);
+        self
+    }
+
+    /// Checks the settings for combinations GLFW would reject or that cannot be applied,
+    /// returning an `InvalidValue` error describing the first problem found.
+    pub fn validate(&self) -> Result<()> {
+        let hints = &self.hints;
+        let (major, minor) = hints.context_version;
+        if self.width <= 0 || self.height <= 0 {
+            return invalid(format!("Invalid window size {}x{}", self.width, self.height));
+        }
+        if self.title.contains('\0') {
+            return invalid("Window title contains a nul character".to_owned());
+        }
+        if hints.client_api == ClientApi::NoApi {
+            if hints.context_version != (1, 0) {
+                return invalid(format!("Context version {}.{} requested without a client API",
+                        major, minor));
+            }
+            if hints.opengl_profile != OpenGlProfile::Any || hints.opengl_forward_compatible ||
+                    hints.opengl_debug_context {
+                return invalid("OpenGL context hints set without a client API".to_owned());
+            }
+            if self.share.is_some() {
+                return invalid("Cannot share a context without a client API".to_owned());
+            }
+        }
+        if hints.client_api == ClientApi::OpenGl {
+            if hints.opengl_profile != OpenGlProfile::Any && (major, minor) < (3, 2) {
+                return invalid(format!("OpenGL profiles require version 3.2, not {}.{}",
+                        major, minor));
+            }
+            if hints.opengl_forward_compatible && major < 3 {
+                return invalid(format!("Forward-compatibility requires OpenGL 3.0, not {}.{}",
+                        major, minor));
+            }
+        }
+        if self.monitor.is_some() && self.position.is_some() {
+            return invalid("Fullscreen windows cannot be positioned".to_owned());
+        }
+        if let Some(limits) = self.size_limits {
+            let SizeLimits { min_width, min_height, max_width, max_height } = limits;
+            let negative = [min_width, min_height, max_width, max_height].iter().flatten()
+                    .any(|&l| l < 0);
+            let crossed = |min: Option<i32>, max: Option<i32>| match (min, max) {
+                (Some(min), Some(max)) => min > max,
+                _ => false
+            };
+            if negative || crossed(min_width, max_width) || crossed(min_height, max_height) {
+                return invalid("Invalid window size limits".to_owned());
+            }
+        }
+        if let Some((numerator, denominator)) = self.aspect_ratio {
+            if numerator <= 0 || denominator <= 0 {
+                return invalid(format!("Invalid aspect ratio {}:{}", numerator, denominator));
+            }
+        }
+        if let Some(opacity) = self.opacity {
+            if !(0.0..=1.0).contains(&opacity) {
+                return invalid(format!("Invalid opacity {}", opacity));
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the settings, then creates the window and applies them.
+    pub fn build<'g>(self, glfw: &'g Glfw) -> Result<Window<'g>> {
+        self.validate()?;
+        let deferred = self.position.is_some() || self.icon.is_some() ||
+                self.size_limits.is_some() || self.aspect_ratio.is_some() ||
+                self.cursor_mode.is_some() || self.opacity.is_some();
+        let show = deferred && self.hints.visible;
+        let hints = WindowHints { visible: self.hints.visible && !show, ..self.hints };
+        let window = glfw.create_window(&hints, self.width, self.height, self.title, self.monitor,
+                self.share)?;
+
+        // The window is destroyed when dropped on failure
+        if let Some((x, y)) = self.position {
+            window.set_pos(x, y)?;
+        }
+        if let Some(icons) = self.icon {
+            window.set_icon(icons)?;
+        }
+        if let Some(limits) = self.size_limits {
+            window.set_size_limits(limits.min_width, limits.min_height, limits.max_width,
+                    limits.max_height)?;
+        }
+        if let Some((numerator, denominator)) = self.aspect_ratio {
+            window.set_aspect_ratio(Some(numerator), Some(denominator))?;
+        }
+        if let Some(mode) = self.cursor_mode {
+            window.set_input_mode(SetInputMode::Cursor(mode))?;
+        }
+        if let Some(opacity) = self.opacity {
+            window.set_opacity(opacity)?;
+        }
+        if show {
+            window.show()?;
+        }
+        Ok(window)
+    }
+}
+
+fn invalid(description: String) -> Result<()> {
+    Err(Error {
+        kind: ErrorKind::InvalidValue,
+        description,
+        function: "WindowBuilder::validate"
+    })
+}
@@ -0,0 +1,139 @@
+use std::any::Any;
+use std::cell::{ Cell, RefCell };
+use std::fmt;
+use std::panic;
+use std::process;
+use std::sync::{ Arc, Mutex };
+
+/// What happens when an event handler panics while called from a GLFW callback.
+///
+/// Panics cannot unwind through GLFW, so they are always caught at the callback.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PanicBehavior {
+    /// Reports the panic as a [`Diagnostic`], then aborts the process. This is the default.
+    ///
+    /// [`Diagnostic`]: enum.Diagnostic.html
+    Abort,
+    /// Stops delivering events and resumes the panic with its original payload once the event
+    /// processing function returns to Rust code, as if the handler had been called from there.
+    Propagate
+}
+
+/// A problem the wrapper cannot handle by returning an error, passed to the hook set with
+/// [`set_diagnostic_hook()`] right before the process aborts.
+///
+/// [`set_diagnostic_hook()`]: fn.set_diagnostic_hook.html
+#[derive(Debug)]
+pub enum Diagnostic<'a> {
+    /// GLFW reported an error the wrapper should have made impossible, like `GLFW_INVALID_ENUM`,
+    /// which means there is a bug in the wrapper.
+    UnexpectedError {
+        code: i32,
+        description: &'a str
+    },
+    /// An event handler panicked with [`PanicBehavior::Abort`] in effect.
+    ///
+    /// [`PanicBehavior::Abort`]: enum.PanicBehavior.html#variant.Abort
+    CallbackPanic {
+        /// The GLFW callback the handler was called from, like `"key"`.
+        callback: &'static str,
+        payload: &'a (dyn Any + Send)
+    }
+}
+
+impl<'a> fmt::Display for Diagnostic<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Diagnostic::UnexpectedError { code, description } => write!(f,
+                    "unexpected GLFW error 0x{:X}: {}", code, description),
+            Diagnostic::CallbackPanic { callback, payload } => {
+                let message = payload.downcast_ref::<&str>().cloned()
+                        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                        .unwrap_or("Box<Any>");
+                write!(f, "panic in GLFW {} callback: {}", callback, message)
+            }
+        }
+    }
+}
+
+type Hook = Arc<dyn Fn(&Diagnostic) + Send + Sync>;
+
+static HOOK: Mutex<Option<Hook>> = Mutex::new(None);
+
+thread_local! {
+    // Callbacks only run on the main thread, during event processing
+    static PANIC_BEHAVIOR: Cell<PanicBehavior> = const { Cell::new(PanicBehavior::Abort) };
+    static PENDING_PANIC: RefCell<Option<Box<dyn Any + Send>>> = const { RefCell::new(None) };
+}
+
+/// Sets the function that receives [`Diagnostic`]s, replacing the previous one. This can be
+/// called before [`init()`], and stays in effect across initializations.
+///
+/// Without a hook, diagnostics are written to the standard error stream, or logged with the
+/// `log` crate at the error level if the `log` feature is enabled.
+///
+/// [`Diagnostic`]: enum.Diagnostic.html
+/// [`init()`]: fn.init.html
+pub fn set_diagnostic_hook<F: Fn(&Diagnostic) + Send + Sync + 'static>(hook: F) {
+    *HOOK.lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Removes the hook set with [`set_diagnostic_hook()`].
+///
+/// [`set_diagnostic_hook()`]: fn.set_diagnostic_hook.html
+pub fn clear_diagnostic_hook() {
+    *HOOK.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Reports a diagnostic to the hook, or the default sink, then aborts.
+pub(crate) fn fatal(diagnostic: Diagnostic) -> ! {
+    // Cloned out so that the hook can replace itself without deadlocking
+    let hook = HOOK.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    match hook {
+        Some(hook) => hook(&diagnostic),
+        None => report(&diagnostic)
+    }
+    process::abort()
+}
+
+#[cfg(feature = "log")]
+fn report(diagnostic: &Diagnostic) {
+    error!("{}", diagnostic);
+}
+
+#[cfg(not(feature = "log"))]
+fn report(diagnostic: &Diagnostic) {
+    eprintln!("{}", diagnostic);
+}
+
+pub(crate) fn set_panic_behavior(behavior: PanicBehavior) {
+    PANIC_BEHAVIOR.with(|b| b.set(behavior));
+}
+
+/// Runs the body of a GLFW callback, handling a panic according to the panic behavior. The body
+/// still runs while a panic is waiting to be resumed, so that it can keep the wrapper's state up to
+/// date, but must not call the event handler then. Only the first panic is resumed.
+pub(crate) fn run_callback<F: FnOnce() + panic::UnwindSafe>(callback: &'static str, f: F) {
+    if let Err(payload) = panic::catch_unwind(f) {
+        match PANIC_BEHAVIOR.with(Cell::get) {
+            PanicBehavior::Abort => fatal(Diagnostic::CallbackPanic {
+                callback,
+                payload: &*payload
+            }),
+            PanicBehavior::Propagate => PENDING_PANIC.with(|p| {
+                p.borrow_mut().get_or_insert(payload);
+            })
+        }
+    }
+}
+
+pub(crate) fn panic_pending() -> bool {
+    PENDING_PANIC.with(|p| p.borrow().is_some())
+}
+
+/// Resumes the panic caught by a callback, if any.
+pub(crate) fn resume_pending_panic() {
+    if let Some(payload) = PENDING_PANIC.with(|p| p.borrow_mut().take()) {
+        panic::resume_unwind(payload);
+    }
+}
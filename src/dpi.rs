@@ -0,0 +1,309 @@
+use Event;
+use Monitor;
+use Result;
+use Window;
+
+/// A size in logical pixels, which are physical pixels divided by the content scale. User
+/// interfaces laid out in logical pixels look the same size on every monitor.
+///
+/// GLFW reports window sizes and cursor positions in screen coordinates, which are physical
+/// pixels on some platforms, like Windows and X11, and logical pixels on others, like macOS.
+/// The typed accessors, like [`Window::get_logical_size()`], convert through the ratio between
+/// the framebuffer size and the window size, so they give the same result everywhere.
+///
+/// Monitor positions and work areas have no typed accessors, as GLFW gives no framebuffer to
+/// find the ratio for a monitor from. They stay in screen coordinates, like [`Window::get_pos()`].
+///
+/// [`Window::get_logical_size()`]: struct.Window.html#method.get_logical_size
+/// [`Window::get_pos()`]: struct.Window.html#method.get_pos
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct LogicalSize {
+    pub width: f64,
+    pub height: f64
+}
+
+/// A size in physical pixels.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct PhysicalSize {
+    pub width: i32,
+    pub height: i32
+}
+
+/// A position in logical pixels.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct LogicalPosition {
+    pub x: f64,
+    pub y: f64
+}
+
+/// A position in physical pixels.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct PhysicalPosition {
+    pub x: f64,
+    pub y: f64
+}
+
+impl LogicalSize {
+    pub fn new(width: f64, height: f64) -> Self {
+        LogicalSize { width, height }
+    }
+
+    /// Converts to physical pixels with a content scale, as returned by
+    /// [`Window::get_content_scale()`], rounding to the nearest pixel.
+    ///
+    /// [`Window::get_content_scale()`]: struct.Window.html#method.get_content_scale
+    pub fn to_physical(self, scale: (f32, f32)) -> PhysicalSize {
+        PhysicalSize {
+            width: (self.width * scale.0 as f64).round() as i32,
+            height: (self.height * scale.1 as f64).round() as i32
+        }
+    }
+}
+
+impl PhysicalSize {
+    pub fn new(width: i32, height: i32) -> Self {
+        PhysicalSize { width, height }
+    }
+
+    /// Converts to logical pixels with a content scale, as returned by
+    /// [`Window::get_content_scale()`]. Scales that are not positive are taken as 1.0.
+    ///
+    /// [`Window::get_content_scale()`]: struct.Window.html#method.get_content_scale
+    pub fn to_logical(self, scale: (f32, f32)) -> LogicalSize {
+        LogicalSize {
+            width: self.width as f64 / divisor(scale.0),
+            height: self.height as f64 / divisor(scale.1)
+        }
+    }
+}
+
+impl LogicalPosition {
+    pub fn new(x: f64, y: f64) -> Self {
+        LogicalPosition { x, y }
+    }
+
+    /// Converts to physical pixels with a content scale, as returned by
+    /// [`Window::get_content_scale()`].
+    ///
+    /// [`Window::get_content_scale()`]: struct.Window.html#method.get_content_scale
+    pub fn to_physical(self, scale: (f32, f32)) -> PhysicalPosition {
+        PhysicalPosition { x: self.x * scale.0 as f64, y: self.y * scale.1 as f64 }
+    }
+}
+
+impl PhysicalPosition {
+    pub fn new(x: f64, y: f64) -> Self {
+        PhysicalPosition { x, y }
+    }
+
+    /// Converts to logical pixels with a content scale, as returned by
+    /// [`Window::get_content_scale()`]. Scales that are not positive are taken as 1.0.
+    ///
+    /// [`Window::get_content_scale()`]: struct.Window.html#method.get_content_scale
+    pub fn to_logical(self, scale: (f32, f32)) -> LogicalPosition {
+        LogicalPosition { x: self.x / divisor(scale.0), y: self.y / divisor(scale.1) }
+    }
+}
+
+/// Guards conversions to logical pixels against dividing by a zero scale, like `pixel_ratio`
+/// does for iconified windows.
+fn divisor(scale: f32) -> f64 {
+    if scale > 0.0 { scale as f64 } else { 1.0 }
+}
+
+impl From<(f64, f64)> for LogicalSize {
+    fn from((width, height): (f64, f64)) -> Self {
+        LogicalSize { width, height }
+    }
+}
+
+impl From<LogicalSize> for (f64, f64) {
+    fn from(size: LogicalSize) -> Self {
+        (size.width, size.height)
+    }
+}
+
+impl From<(i32, i32)> for PhysicalSize {
+    fn from((width, height): (i32, i32)) -> Self {
+        PhysicalSize { width, height }
+    }
+}
+
+impl From<PhysicalSize> for (i32, i32) {
+    fn from(size: PhysicalSize) -> Self {
+        (size.width, size.height)
+    }
+}
+
+impl From<(f64, f64)> for LogicalPosition {
+    fn from((x, y): (f64, f64)) -> Self {
+        LogicalPosition { x, y }
+    }
+}
+
+impl From<LogicalPosition> for (f64, f64) {
+    fn from(pos: LogicalPosition) -> Self {
+        (pos.x, pos.y)
+    }
+}
+
+impl From<(f64, f64)> for PhysicalPosition {
+    fn from((x, y): (f64, f64)) -> Self {
+        PhysicalPosition { x, y }
+    }
+}
+
+impl From<PhysicalPosition> for (f64, f64) {
+    fn from(pos: PhysicalPosition) -> Self {
+        (pos.x, pos.y)
+    }
+}
+
+impl<'a> Window<'a> {
+    /// Returns the number of physical pixels per screen coordinate, as the ratio between the
+    /// framebuffer size and the window size.
+    fn pixel_ratio(&self) -> Result<(f64, f64)> {
+        let (width, height) = self.get_window_size()?;
+        let (fb_width, fb_height) = self.get_framebuffer_size()?;
+        // Iconified windows have a size of zero on some platforms
+        let ratio = |fb: i32, size: i32| if size > 0 { fb as f64 / size as f64 } else { 1.0 };
+        Ok((ratio(fb_width, width), ratio(fb_height, height)))
+    }
+
+    /// Returns the size of the client area in physical pixels, which is the framebuffer size.
+    pub fn get_physical_size(&self) -> Result<PhysicalSize> {
+        self.get_framebuffer_size().map(PhysicalSize::from)
+    }
+
+    /// Returns the size of the client area in logical pixels.
+    pub fn get_logical_size(&self) -> Result<LogicalSize> {
+        Ok(self.get_physical_size()?.to_logical(self.get_content_scale()?))
+    }
+
+    /// Resizes the client area to a size in logical pixels, rounded to the nearest screen
+    /// coordinate.
+    pub fn set_logical_size(&self, size: LogicalSize) -> Result<()> {
+        let physical = size.to_physical(self.get_content_scale()?);
+        let (x_ratio, y_ratio) = self.pixel_ratio()?;
+        self.set_size((physical.width as f64 / x_ratio).round() as i32,
+                (physical.height as f64 / y_ratio).round() as i32)
+    }
+
+    /// Returns the position of the client area in physical pixels.
+    pub fn get_physical_pos(&self) -> Result<PhysicalPosition> {
+        let (x, y) = self.get_pos()?;
+        self.screen_to_physical(x as f64, y as f64)
+    }
+
+    /// Returns the position of the client area in logical pixels.
+    pub fn get_logical_pos(&self) -> Result<LogicalPosition> {
+        Ok(self.get_physical_pos()?.to_logical(self.get_content_scale()?))
+    }
+
+    /// Moves the client area to a position in physical pixels, rounded to the nearest screen
+    /// coordinate.
+    pub fn set_physical_pos(&self, pos: PhysicalPosition) -> Result<()> {
+        let (x_ratio, y_ratio) = self.pixel_ratio()?;
+        self.set_pos((pos.x / x_ratio).round() as i32, (pos.y / y_ratio).round() as i32)
+    }
+
+    /// Moves the client area to a position in logical pixels, rounded to the nearest screen
+    /// coordinate.
+    pub fn set_logical_pos(&self, pos: LogicalPosition) -> Result<()> {
+        self.set_physical_pos(pos.to_physical(self.get_content_scale()?))
+    }
+
+    /// Returns the position of the cursor relative to the client area in physical pixels.
+    pub fn get_physical_cursor_pos(&self) -> Result<PhysicalPosition> {
+        let (x, y) = self.get_cursor_pos();
+        self.screen_to_physical(x, y)
+    }
+
+    /// Returns the position of the cursor relative to the client area in logical pixels.
+    pub fn get_logical_cursor_pos(&self) -> Result<LogicalPosition> {
+        Ok(self.get_physical_cursor_pos()?.to_logical(self.get_content_scale()?))
+    }
+
+    fn screen_to_physical(&self, x: f64, y: f64) -> Result<PhysicalPosition> {
+        let (x_ratio, y_ratio) = self.pixel_ratio()?;
+        Ok(PhysicalPosition { x: x * x_ratio, y: y * y_ratio })
+    }
+}
+
+impl Monitor {
+    /// Returns the resolution of the current video mode in physical pixels.
+    pub fn get_physical_resolution(&self) -> Result<PhysicalSize> {
+        let mode = self.get_video_mode()?;
+        Ok(PhysicalSize { width: mode.width, height: mode.height })
+    }
+
+    /// Returns the resolution of the current video mode in logical pixels.
+    pub fn get_logical_resolution(&self) -> Result<LogicalSize> {
+        Ok(self.get_physical_resolution()?.to_logical(self.get_content_scale()?))
+    }
+}
+
+impl<'a> Event<'a> {
+    /// Returns the new size of the client area of a `WindowResize` or `FramebufferSizeChanged`
+    /// event in physical pixels, or `None` for other events.
+    pub fn physical_size(&self) -> Result<Option<PhysicalSize>> {
+        match *self {
+            Event::WindowResize { win, width, height } => {
+                let (x_ratio, y_ratio) = win.pixel_ratio()?;
+                Ok(Some(PhysicalSize {
+                    width: (width as f64 * x_ratio).round() as i32,
+                    height: (height as f64 * y_ratio).round() as i32
+                }))
+            }
+            Event::FramebufferSizeChanged { width, height, .. } =>
+                    Ok(Some(PhysicalSize { width, height })),
+            _ => Ok(None)
+        }
+    }
+
+    /// Returns the new size of the client area of a `WindowResize` or `FramebufferSizeChanged`
+    /// event in logical pixels, or `None` for other events.
+    pub fn logical_size(&self) -> Result<Option<LogicalSize>> {
+        match (self.physical_size()?, self.window()) {
+            (Some(size), Some(win)) => Ok(Some(size.to_logical(win.get_content_scale()?))),
+            _ => Ok(None)
+        }
+    }
+
+    /// Returns the new position of the client area of a `WindowPosition` event in physical pixels,
+    /// or `None` for other events.
+    pub fn physical_pos(&self) -> Result<Option<PhysicalPosition>> {
+        match *self {
+            Event::WindowPosition { win, x, y } =>
+                    win.screen_to_physical(x as f64, y as f64).map(Some),
+            _ => Ok(None)
+        }
+    }
+
+    /// Returns the new position of the client area of a `WindowPosition` event in logical pixels,
+    /// or `None` for other events.
+    pub fn logical_pos(&self) -> Result<Option<LogicalPosition>> {
+        match (self.physical_pos()?, self.window()) {
+            (Some(pos), Some(win)) => Ok(Some(pos.to_logical(win.get_content_scale()?))),
+            _ => Ok(None)
+        }
+    }
+
+    /// Returns the position of a `CursorMoved` event in physical pixels, or `None` for other
+    /// events.
+    pub fn physical_cursor_pos(&self) -> Result<Option<PhysicalPosition>> {
+        match *self {
+            Event::CursorMoved { win, x, y } => win.screen_to_physical(x, y).map(Some),
+            _ => Ok(None)
+        }
+    }
+
+    /// Returns the position of a `CursorMoved` event in logical pixels, or `None` for other
+    /// events.
+    pub fn logical_cursor_pos(&self) -> Result<Option<LogicalPosition>> {
+        match (self.physical_cursor_pos()?, self.window()) {
+            (Some(pos), Some(win)) => Ok(Some(pos.to_logical(win.get_content_scale()?))),
+            _ => Ok(None)
+        }
+    }
+}
@@ -1,4 +1,7 @@
 use std::os::raw::{ c_int, c_uchar };
+use std::fmt;
+use std::str::FromStr;
+use enum_primitive::FromPrimitive;
 use ffi;
 
 enum_from_primitive! {
@@ -190,6 +193,124 @@ enum_from_primitive! {
     }
 }
 
+enum_from_primitive! {
+    /// Gamepad buttons, named after their Xbox controller equivalent.
+    /// 
+    /// [GLFW Reference][glfw]
+    /// 
+    /// [glfw]: http://www.glfw.org/docs/3.3/group__gamepad__buttons.html
+    #[repr(i32)]
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+    pub enum GamepadButton {
+        A = ffi::GLFW_GAMEPAD_BUTTON_A,
+        B = ffi::GLFW_GAMEPAD_BUTTON_B,
+        X = ffi::GLFW_GAMEPAD_BUTTON_X,
+        Y = ffi::GLFW_GAMEPAD_BUTTON_Y,
+        LeftBumper = ffi::GLFW_GAMEPAD_BUTTON_LEFT_BUMPER,
+        RightBumper = ffi::GLFW_GAMEPAD_BUTTON_RIGHT_BUMPER,
+        Back = ffi::GLFW_GAMEPAD_BUTTON_BACK,
+        Start = ffi::GLFW_GAMEPAD_BUTTON_START,
+        Guide = ffi::GLFW_GAMEPAD_BUTTON_GUIDE,
+        LeftThumb = ffi::GLFW_GAMEPAD_BUTTON_LEFT_THUMB,
+        RightThumb = ffi::GLFW_GAMEPAD_BUTTON_RIGHT_THUMB,
+        DpadUp = ffi::GLFW_GAMEPAD_BUTTON_DPAD_UP,
+        DpadRight = ffi::GLFW_GAMEPAD_BUTTON_DPAD_RIGHT,
+        DpadDown = ffi::GLFW_GAMEPAD_BUTTON_DPAD_DOWN,
+        DpadLeft = ffi::GLFW_GAMEPAD_BUTTON_DPAD_LEFT
+    }
+}
+
+enum_from_primitive! {
+    /// [GLFW Reference][glfw]
+    /// 
+    /// [glfw]: http://www.glfw.org/docs/3.3/group__gamepad__axes.html
+    #[repr(i32)]
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+    pub enum GamepadAxis {
+        LeftX = ffi::GLFW_GAMEPAD_AXIS_LEFT_X,
+        LeftY = ffi::GLFW_GAMEPAD_AXIS_LEFT_Y,
+        RightX = ffi::GLFW_GAMEPAD_AXIS_RIGHT_X,
+        RightY = ffi::GLFW_GAMEPAD_AXIS_RIGHT_Y,
+        LeftTrigger = ffi::GLFW_GAMEPAD_AXIS_LEFT_TRIGGER,
+        RightTrigger = ffi::GLFW_GAMEPAD_AXIS_RIGHT_TRIGGER
+    }
+}
+
+/// The button labels of a family of gamepads, for prompts that match the controller in use.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum GamepadLayout {
+    Xbox,
+    PlayStation
+}
+
+impl GamepadButton {
+    /// Every button, ordered by their GLFW value.
+    pub const ALL: [GamepadButton; 15] = [
+        GamepadButton::A, GamepadButton::B, GamepadButton::X, GamepadButton::Y,
+        GamepadButton::LeftBumper, GamepadButton::RightBumper,
+        GamepadButton::Back, GamepadButton::Start, GamepadButton::Guide,
+        GamepadButton::LeftThumb, GamepadButton::RightThumb,
+        GamepadButton::DpadUp, GamepadButton::DpadRight,
+        GamepadButton::DpadDown, GamepadButton::DpadLeft
+    ];
+
+    /// Returns the name of the button on controllers of the given layout.
+    pub fn label(self, layout: GamepadLayout) -> &'static str {
+        use self::GamepadButton::*;
+        match (self, layout) {
+            (A, GamepadLayout::Xbox) => "A",
+            (A, GamepadLayout::PlayStation) => "Cross",
+            (B, GamepadLayout::Xbox) => "B",
+            (B, GamepadLayout::PlayStation) => "Circle",
+            (X, GamepadLayout::Xbox) => "X",
+            (X, GamepadLayout::PlayStation) => "Square",
+            (Y, GamepadLayout::Xbox) => "Y",
+            (Y, GamepadLayout::PlayStation) => "Triangle",
+            (LeftBumper, GamepadLayout::Xbox) => "LB",
+            (LeftBumper, GamepadLayout::PlayStation) => "L1",
+            (RightBumper, GamepadLayout::Xbox) => "RB",
+            (RightBumper, GamepadLayout::PlayStation) => "R1",
+            (Back, GamepadLayout::Xbox) => "View",
+            (Back, GamepadLayout::PlayStation) => "Share",
+            (Start, GamepadLayout::Xbox) => "Menu",
+            (Start, GamepadLayout::PlayStation) => "Options",
+            (Guide, GamepadLayout::Xbox) => "Xbox",
+            (Guide, GamepadLayout::PlayStation) => "PS",
+            (LeftThumb, GamepadLayout::Xbox) => "LS",
+            (LeftThumb, GamepadLayout::PlayStation) => "L3",
+            (RightThumb, GamepadLayout::Xbox) => "RS",
+            (RightThumb, GamepadLayout::PlayStation) => "R3",
+            (DpadUp, _) => "D-pad Up",
+            (DpadRight, _) => "D-pad Right",
+            (DpadDown, _) => "D-pad Down",
+            (DpadLeft, _) => "D-pad Left"
+        }
+    }
+}
+
+impl GamepadAxis {
+    /// Every axis, ordered by their GLFW value.
+    pub const ALL: [GamepadAxis; 6] = [
+        GamepadAxis::LeftX, GamepadAxis::LeftY, GamepadAxis::RightX, GamepadAxis::RightY,
+        GamepadAxis::LeftTrigger, GamepadAxis::RightTrigger
+    ];
+
+    /// Returns the name of the axis on controllers of the given layout.
+    pub fn label(self, layout: GamepadLayout) -> &'static str {
+        use self::GamepadAxis::*;
+        match (self, layout) {
+            (LeftX, _) => "Left Stick X",
+            (LeftY, _) => "Left Stick Y",
+            (RightX, _) => "Right Stick X",
+            (RightY, _) => "Right Stick Y",
+            (LeftTrigger, GamepadLayout::Xbox) => "LT",
+            (LeftTrigger, GamepadLayout::PlayStation) => "L2",
+            (RightTrigger, GamepadLayout::Xbox) => "RT",
+            (RightTrigger, GamepadLayout::PlayStation) => "R2"
+        }
+    }
+}
+
 enum_from_primitive! {
     /// [GLFW Reference][glfw]
     /// 
@@ -226,7 +347,7 @@ enum_from_primitive! {
 
 enum_from_primitive! {
     #[repr(i32)]
-    #[derive(Copy, Clone, Hash, Debug)]
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
     pub enum ClientApi {
         OpenGl = ffi::GLFW_OPENGL_API,
         OpenGlEs = ffi::GLFW_OPENGL_ES_API,
@@ -236,7 +357,7 @@ enum_from_primitive! {
 
 enum_from_primitive! {
     #[repr(i32)]
-    #[derive(Copy, Clone, Hash, Debug)]
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
     pub enum ContextRobustness {
         NoRobustness = ffi::GLFW_NO_ROBUSTNESS,
         NoResetNotification = ffi::GLFW_NO_RESET_NOTIFICATION,
@@ -246,7 +367,7 @@ enum_from_primitive! {
 
 enum_from_primitive! {
     #[repr(i32)]
-    #[derive(Copy, Clone, Hash, Debug)]
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
     pub enum OpenGlProfile {
         Any = ffi::GLFW_OPENGL_ANY_PROFILE,
         Core = ffi::GLFW_OPENGL_CORE_PROFILE,
@@ -256,7 +377,7 @@ enum_from_primitive! {
 
 enum_from_primitive! {
     #[repr(i32)]
-    #[derive(Copy, Clone, Hash, Debug)]
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
     pub enum ContextReleaseBehavior {
         Any = ffi::GLFW_ANY_RELEASE_BEHAVIOR,
         Flush = ffi::GLFW_RELEASE_BEHAVIOR_FLUSH,
@@ -266,7 +387,7 @@ enum_from_primitive! {
 
 enum_from_primitive! {
     #[repr(i32)]
-    #[derive(Copy, Clone, Hash, Debug)]
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
     pub enum ContextCreationApi {
         Native = ffi::GLFW_NATIVE_CONTEXT_API,
         Egl = ffi::GLFW_EGL_CONTEXT_API,
@@ -658,3 +779,191 @@ impl Default for InitHints {
         }
     }
 }
+
+/// Error returned when parsing a name that does not match any variant of an enum.
+/// 
+/// Names are the variant names, matched case-insensitively, along with the other forms listed by
+/// each `FromStr` implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseNameError {
+    what: &'static str,
+    name: String
+}
+
+impl ParseNameError {
+    pub(crate) fn new(what: &'static str, name: &str) -> ParseNameError {
+        ParseNameError {
+            what,
+            name: name.to_owned()
+        }
+    }
+}
+
+impl fmt::Display for ParseNameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown {} name `{}`", self.what, self.name)
+    }
+}
+
+impl ::std::error::Error for ParseNameError {}
+
+/// Finds the variant whose name is `name`, ignoring case.
+fn find_variant<T: fmt::Debug, I: Iterator<Item = T>>(
+    mut variants: I, what: &'static str, name: &str
+) -> Result<T, ParseNameError> {
+    variants.find(|v| format!("{:?}", v).eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParseNameError::new(what, name))
+}
+
+/// Implements `Display` and `FromStr` using the variant names, given the range of values the
+/// variants lie in.
+macro_rules! variant_names {
+    ($($ty:ident, $what:expr, $range:expr;)*) => {$(
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Debug::fmt(self, f)
+            }
+        }
+
+        impl FromStr for $ty {
+            type Err = ParseNameError;
+
+            fn from_str(s: &str) -> Result<$ty, ParseNameError> {
+                find_variant($range.filter_map($ty::from_i32), $what, s)
+            }
+        }
+    )*};
+}
+
+variant_names! {
+    MouseButton, "mouse button", (ffi::GLFW_MOUSE_BUTTON_1..=ffi::GLFW_MOUSE_BUTTON_LAST);
+    GamepadButton, "gamepad button", (0..=ffi::GLFW_GAMEPAD_BUTTON_LAST);
+    GamepadAxis, "gamepad axis", (0..=ffi::GLFW_GAMEPAD_AXIS_LAST);
+}
+
+/// Keys written as the character they produce on a US keyboard rather than by name.
+const KEY_SYMBOLS: [(KeyCode, &str); 21] = [
+    (KeyCode::Zero, "0"),
+    (KeyCode::One, "1"),
+    (KeyCode::Two, "2"),
+    (KeyCode::Three, "3"),
+    (KeyCode::Four, "4"),
+    (KeyCode::Five, "5"),
+    (KeyCode::Six, "6"),
+    (KeyCode::Seven, "7"),
+    (KeyCode::Eight, "8"),
+    (KeyCode::Nine, "9"),
+    (KeyCode::Apostrophe, "'"),
+    (KeyCode::Comma, ","),
+    (KeyCode::Minus, "-"),
+    (KeyCode::Period, "."),
+    (KeyCode::Slash, "/"),
+    (KeyCode::Semicolon, ";"),
+    (KeyCode::Equal, "="),
+    (KeyCode::LeftBracket, "["),
+    (KeyCode::Backslash, "\\"),
+    (KeyCode::RightBracket, "]"),
+    (KeyCode::GraveAccent, "`")
+];
+
+impl fmt::Display for KeyCode {
+    /// Writes digits and punctuation as the character they produce on a US keyboard, like `1` or
+    /// `=`, and other keys by their variant name, like `Space`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match KEY_SYMBOLS.iter().find(|&&(code, _)| code == *self) {
+            Some(&(_, symbol)) => f.write_str(symbol),
+            None => fmt::Debug::fmt(self, f)
+        }
+    }
+}
+
+impl FromStr for KeyCode {
+    type Err = ParseNameError;
+
+    /// Parses the form written by `Display`, or the variant name of any key, like `Zero`.
+    fn from_str(s: &str) -> Result<KeyCode, ParseNameError> {
+        match KEY_SYMBOLS.iter().find(|&&(_, symbol)| symbol == s) {
+            Some(&(code, _)) => Ok(code),
+            None => find_variant(
+                (ffi::GLFW_KEY_SPACE..=ffi::GLFW_KEY_LAST).filter_map(KeyCode::from_i32), "key", s
+            )
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    /// Writes the key code as `KeyCode` does, or `Scancode:<n>` for unnamed keys.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Key::Named(code) => write!(f, "{}", code),
+            Key::Unnamed(scancode) => write!(f, "Scancode:{}", scancode)
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Key, ParseNameError> {
+        let unnamed = if s.get(..9).is_some_and(|p| p.eq_ignore_ascii_case("scancode:")) {
+            s[9..].trim().parse().ok()
+        } else {
+            None
+        };
+        match unnamed {
+            Some(scancode) => Ok(Key::Unnamed(scancode)),
+            None => s.parse().map(Key::Named)
+        }
+    }
+}
+
+/// Names of the modifiers, in the order they are written in. The first name of each is the one
+/// written, the others are accepted when parsing.
+const MODIFIER_NAMES: [(Modifiers, &[&str]); 6] = [
+    (Modifiers::CONTROL, &["Ctrl", "Control"]),
+    (Modifiers::ALT, &["Alt", "Option", "Opt"]),
+    (Modifiers::SHIFT, &["Shift"]),
+    (Modifiers::SUPER, &["Super", "Cmd", "Command", "Win", "Meta"]),
+    (Modifiers::CAPSLOCK, &["CapsLock"]),
+    (Modifiers::NUMLOCK, &["NumLock"])
+];
+
+/// Returns the modifier with the given name, if it is one.
+fn parse_modifier(name: &str) -> Option<Modifiers> {
+    MODIFIER_NAMES.iter()
+            .find(|&&(_, names)| names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+            .map(|&(modifier, _)| modifier)
+}
+
+impl fmt::Display for Modifiers {
+    /// Writes the modifiers separated by `+`, like `Ctrl+Shift`. Nothing is written for no
+    /// modifiers.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+        for &(modifier, names) in &MODIFIER_NAMES {
+            if self.contains(modifier) {
+                write!(f, "{}{}", if first { "" } else { "+" }, names[0])?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Modifiers {
+    type Err = ParseNameError;
+
+    /// Parses modifiers separated by `+`. Along with the names written by `Display`, `Control`,
+    /// `Option`, `Opt`, `Cmd`, `Command`, `Win` and `Meta` are accepted, and case is ignored.
+    fn from_str(s: &str) -> Result<Modifiers, ParseNameError> {
+        let mut modifiers = Modifiers::empty();
+        if s.trim().is_empty() {
+            return Ok(modifiers);
+        }
+        for name in s.split('+').map(str::trim) {
+            modifiers |= parse_modifier(name)
+                    .ok_or_else(|| ParseNameError::new("modifier", name))?;
+        }
+        Ok(modifiers)
+    }
+}
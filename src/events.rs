@@ -1,8 +1,6 @@
 use std::os::raw::{ c_char, c_int, c_uint, c_float, c_double };
 use std::path::PathBuf;
 use std::char::from_u32;
-use std::panic;
-use std::process::abort;
 use std::slice;
 use std::ffi::CStr;
 use enum_primitive::FromPrimitive;
@@ -12,12 +10,18 @@ use KeyCode;
 use MouseButton;
 use Modifiers;
 use Window;
+use WindowId;
 use ffi;
 use cint_to_bool;
 use Monitor;
 use DisconnectedMonitor;
 use Joystick;
+use JoystickHatState;
+use GamepadButton;
+use GamepadAxis;
 use invalidate_monitor;
+use take_joystick_user_data;
+use diagnostics;
 
 pub(crate) static mut EVENT_PROCESSOR: Option<*mut FnMut(Event) -> ()> = None;
 
@@ -80,46 +84,342 @@ pub enum Event<'a> {
     KeyDown {
         win: &'a Window<'a>,
         key: Key,
+        scancode: i32,
         modifiers: Modifiers
     },
     KeyUp {
         win: &'a Window<'a>,
         key: Key,
+        scancode: i32,
         modifiers: Modifiers
     },
     KeyRepeat {
         win: &'a Window<'a>,
         key: Key,
+        scancode: i32,
         modifiers: Modifiers
     },
     CharTyped(&'a Window<'a>, char),
-    FileDrop(&'a Window<'a>, Vec<PathBuf>)
+    /// Delivered alongside [`CharTyped`] with the modifier keys held when the character was
+    /// input. Useful for filtering out characters typed as part of a shortcut.
+    /// 
+    /// [`CharTyped`]: #variant.CharTyped
+    CharTypedWithModifiers {
+        win: &'a Window<'a>,
+        character: char,
+        modifiers: Modifiers
+    },
+    FileDrop(&'a Window<'a>, Vec<PathBuf>),
+    /// The joystick and gamepad events below are only delivered once enabled with
+    /// [`Glfw::enable_gamepad_events()`], as GLFW does not report them; they are found by
+    /// comparing the state of each joystick with the previous event processing call.
+    /// 
+    /// [`Glfw::enable_gamepad_events()`]: struct.Glfw.html#method.enable_gamepad_events
+    GamepadButtonDown {
+        joystick: Joystick,
+        button: GamepadButton
+    },
+    GamepadButtonUp {
+        joystick: Joystick,
+        button: GamepadButton
+    },
+    GamepadAxisMoved {
+        joystick: Joystick,
+        axis: GamepadAxis,
+        value: f32
+    },
+    /// A button of a joystick without a gamepad mapping was pressed.
+    JoystickButtonDown {
+        joystick: Joystick,
+        button: usize
+    },
+    JoystickButtonUp {
+        joystick: Joystick,
+        button: usize
+    },
+    JoystickAxisMoved {
+        joystick: Joystick,
+        axis: usize,
+        value: f32
+    },
+    JoystickHatChanged {
+        joystick: Joystick,
+        hat: usize,
+        state: JoystickHatState
+    }
 }
 
+/// An owned version of [`Event`] that refers to windows by [`WindowId`].
+/// 
+/// Unlike `Event`, this can be stored, sent to other threads, and processed after the call that
+/// produced it returns. Monitors are identified by name, as a `GLFWmonitor` does not outlive its
+/// disconnection.
+/// 
+/// [`Event`]: enum.Event.html
+/// [`WindowId`]: struct.WindowId.html
+#[derive(Clone, Debug, PartialEq)]
+pub enum OwnedEvent {
+    MonitorConnected(String),
+    MonitorDisconnected(String),
+    JoystickConnected(Joystick),
+    JoystickDisconnected(Joystick),
+    WindowPosition {
+        win: WindowId,
+        x: i32,
+        y: i32
+    },
+    WindowResize {
+        win: WindowId,
+        width: i32,
+        height: i32
+    },
+    WindowCloseRequested(WindowId),
+    WindowRefresh(WindowId),
+    WindowFocused(WindowId),
+    WindowDefocused(WindowId),
+    WindowIconified(WindowId),
+    WindowDeiconified(WindowId),
+    WindowMaximized(WindowId),
+    WindowDemaximized(WindowId),
+    FramebufferSizeChanged {
+        win: WindowId,
+        width: i32,
+        height: i32
+    },
+    ContentScaleChanged {
+        win: WindowId,
+        x_scale: f32,
+        y_scale: f32
+    },
+    MouseButtonDown {
+        win: WindowId,
+        button: MouseButton,
+        modifiers: Modifiers
+    },
+    MouseButtonUp {
+        win: WindowId,
+        button: MouseButton,
+        modifiers: Modifiers
+    },
+    CursorMoved {
+        win: WindowId,
+        x: f64,
+        y: f64
+    },
+    CursorEntered(WindowId),
+    CursorExited(WindowId),
+    Scroll {
+        win: WindowId,
+        x_offset: f64,
+        y_offset: f64
+    },
+    KeyDown {
+        win: WindowId,
+        key: Key,
+        scancode: i32,
+        modifiers: Modifiers
+    },
+    KeyUp {
+        win: WindowId,
+        key: Key,
+        scancode: i32,
+        modifiers: Modifiers
+    },
+    KeyRepeat {
+        win: WindowId,
+        key: Key,
+        scancode: i32,
+        modifiers: Modifiers
+    },
+    CharTyped(WindowId, char),
+    CharTypedWithModifiers {
+        win: WindowId,
+        character: char,
+        modifiers: Modifiers
+    },
+    FileDrop(WindowId, Vec<PathBuf>),
+    GamepadButtonDown {
+        joystick: Joystick,
+        button: GamepadButton
+    },
+    GamepadButtonUp {
+        joystick: Joystick,
+        button: GamepadButton
+    },
+    GamepadAxisMoved {
+        joystick: Joystick,
+        axis: GamepadAxis,
+        value: f32
+    },
+    JoystickButtonDown {
+        joystick: Joystick,
+        button: usize
+    },
+    JoystickButtonUp {
+        joystick: Joystick,
+        button: usize
+    },
+    JoystickAxisMoved {
+        joystick: Joystick,
+        axis: usize,
+        value: f32
+    },
+    JoystickHatChanged {
+        joystick: Joystick,
+        hat: usize,
+        state: JoystickHatState
+    }
+}
+
+impl<'a, 'b> From<&'b Event<'a>> for OwnedEvent {
+    fn from(event: &'b Event<'a>) -> OwnedEvent {
+        use self::Event::*;
+        match *event {
+            MonitorConnected(ref m) => OwnedEvent::MonitorConnected(m.get_name()),
+            MonitorDisconnected(m) => OwnedEvent::MonitorDisconnected(m.get_name()),
+            JoystickConnected(j) => OwnedEvent::JoystickConnected(j),
+            JoystickDisconnected(j) => OwnedEvent::JoystickDisconnected(j),
+            WindowPosition { win, x, y } => OwnedEvent::WindowPosition { win: win.id(), x, y },
+            WindowResize { win, width, height } =>
+                    OwnedEvent::WindowResize { win: win.id(), width, height },
+            WindowCloseRequested(win) => OwnedEvent::WindowCloseRequested(win.id()),
+            WindowRefresh(win) => OwnedEvent::WindowRefresh(win.id()),
+            WindowFocused(win) => OwnedEvent::WindowFocused(win.id()),
+            WindowDefocused(win) => OwnedEvent::WindowDefocused(win.id()),
+            WindowIconified(win) => OwnedEvent::WindowIconified(win.id()),
+            WindowDeiconified(win) => OwnedEvent::WindowDeiconified(win.id()),
+            WindowMaximized(win) => OwnedEvent::WindowMaximized(win.id()),
+            WindowDemaximized(win) => OwnedEvent::WindowDemaximized(win.id()),
+            FramebufferSizeChanged { win, width, height } =>
+                    OwnedEvent::FramebufferSizeChanged { win: win.id(), width, height },
+            ContentScaleChanged { win, x_scale, y_scale } =>
+                    OwnedEvent::ContentScaleChanged { win: win.id(), x_scale, y_scale },
+            MouseButtonDown { win, button, modifiers } =>
+                    OwnedEvent::MouseButtonDown { win: win.id(), button, modifiers },
+            MouseButtonUp { win, button, modifiers } =>
+                    OwnedEvent::MouseButtonUp { win: win.id(), button, modifiers },
+            CursorMoved { win, x, y } => OwnedEvent::CursorMoved { win: win.id(), x, y },
+            CursorEntered(win) => OwnedEvent::CursorEntered(win.id()),
+            CursorExited(win) => OwnedEvent::CursorExited(win.id()),
+            Scroll { win, x_offset, y_offset } =>
+                    OwnedEvent::Scroll { win: win.id(), x_offset, y_offset },
+            KeyDown { win, key, scancode, modifiers } =>
+                    OwnedEvent::KeyDown { win: win.id(), key, scancode, modifiers },
+            KeyUp { win, key, scancode, modifiers } =>
+                    OwnedEvent::KeyUp { win: win.id(), key, scancode, modifiers },
+            KeyRepeat { win, key, scancode, modifiers } =>
+                    OwnedEvent::KeyRepeat { win: win.id(), key, scancode, modifiers },
+            CharTyped(win, c) => OwnedEvent::CharTyped(win.id(), c),
+            CharTypedWithModifiers { win, character, modifiers } =>
+                    OwnedEvent::CharTypedWithModifiers { win: win.id(), character, modifiers },
+            FileDrop(win, ref paths) => OwnedEvent::FileDrop(win.id(), paths.clone()),
+            GamepadButtonDown { joystick, button } =>
+                    OwnedEvent::GamepadButtonDown { joystick, button },
+            GamepadButtonUp { joystick, button } =>
+                    OwnedEvent::GamepadButtonUp { joystick, button },
+            GamepadAxisMoved { joystick, axis, value } =>
+                    OwnedEvent::GamepadAxisMoved { joystick, axis, value },
+            JoystickButtonDown { joystick, button } =>
+                    OwnedEvent::JoystickButtonDown { joystick, button },
+            JoystickButtonUp { joystick, button } =>
+                    OwnedEvent::JoystickButtonUp { joystick, button },
+            JoystickAxisMoved { joystick, axis, value } =>
+                    OwnedEvent::JoystickAxisMoved { joystick, axis, value },
+            JoystickHatChanged { joystick, hat, state } =>
+                    OwnedEvent::JoystickHatChanged { joystick, hat, state }
+        }
+    }
+}
+
+impl<'a> Event<'a> {
+    /// Returns the window this event is about, or `None` for monitor, joystick and gamepad
+    /// events.
+    pub fn window(&self) -> Option<&'a Window<'a>> {
+        use self::Event::*;
+        match *self {
+            MonitorConnected(_) | MonitorDisconnected(_) |
+            JoystickConnected(_) | JoystickDisconnected(_) |
+            GamepadButtonDown { .. } | GamepadButtonUp { .. } | GamepadAxisMoved { .. } |
+            JoystickButtonDown { .. } | JoystickButtonUp { .. } | JoystickAxisMoved { .. } |
+            JoystickHatChanged { .. } => None,
+            WindowPosition { win, .. } | WindowResize { win, .. } |
+            FramebufferSizeChanged { win, .. } | ContentScaleChanged { win, .. } |
+            MouseButtonDown { win, .. } | MouseButtonUp { win, .. } | CursorMoved { win, .. } |
+            Scroll { win, .. } | KeyDown { win, .. } | KeyUp { win, .. } |
+            KeyRepeat { win, .. } | CharTypedWithModifiers { win, .. } => Some(win),
+            WindowCloseRequested(win) | WindowRefresh(win) | WindowFocused(win) |
+            WindowDefocused(win) | WindowIconified(win) | WindowDeiconified(win) |
+            WindowMaximized(win) | WindowDemaximized(win) | CursorEntered(win) |
+            CursorExited(win) | CharTyped(win, _) | FileDrop(win, _) => Some(win)
+        }
+    }
+
+    /// Returns the ID of the window this event is about, or `None` for monitor, joystick and
+    /// gamepad events.
+    pub fn window_id(&self) -> Option<WindowId> {
+        self.window().map(|w| w.id())
+    }
+}
+
+impl OwnedEvent {
+    /// Returns the ID of the window this event is about, or `None` for monitor, joystick and
+    /// gamepad events.
+    pub fn window_id(&self) -> Option<WindowId> {
+        use self::OwnedEvent::*;
+        match *self {
+            MonitorConnected(_) | MonitorDisconnected(_) |
+            JoystickConnected(_) | JoystickDisconnected(_) |
+            GamepadButtonDown { .. } | GamepadButtonUp { .. } | GamepadAxisMoved { .. } |
+            JoystickButtonDown { .. } | JoystickButtonUp { .. } | JoystickAxisMoved { .. } |
+            JoystickHatChanged { .. } => None,
+            WindowPosition { win, .. } | WindowResize { win, .. } |
+            FramebufferSizeChanged { win, .. } | ContentScaleChanged { win, .. } |
+            MouseButtonDown { win, .. } | MouseButtonUp { win, .. } | CursorMoved { win, .. } |
+            Scroll { win, .. } | KeyDown { win, .. } | KeyUp { win, .. } |
+            KeyRepeat { win, .. } | CharTypedWithModifiers { win, .. } => Some(win),
+            WindowCloseRequested(win) | WindowRefresh(win) | WindowFocused(win) |
+            WindowDefocused(win) | WindowIconified(win) | WindowDeiconified(win) |
+            WindowMaximized(win) | WindowDemaximized(win) | CursorEntered(win) |
+            CursorExited(win) | CharTyped(win, _) | FileDrop(win, _) => Some(win)
+        }
+    }
+}
+
+/// Defines a GLFW callback passing events to the event processor. The optional first block runs
+/// before the event processor is called, and also when there is none, as GLFW calls monitor and
+/// joystick callbacks from outside event processing too.
 macro_rules! callback {
-    ($name:ident, $cb:ident; $($p_n:ident: $p_t:ty),* => $code:block) => {
+    ($name:ident, $cb:ident; $($p_n:ident: $p_t:ty),* => $always:block $code:block) => {
         extern "C" fn $name($($p_n: $p_t),*) {
-            if let Err(_) = panic::catch_unwind(|| unsafe {
+            diagnostics::run_callback(stringify!($name), || unsafe {
+                $always
+                // The rest of the events are dropped once the handler panics
+                if diagnostics::panic_pending() {
+                    return;
+                }
                 if let Some(cbptr) = EVENT_PROCESSOR {
                     let $cb = &mut *cbptr;
                     $code
                 }
-            }) {
-                eprintln!(concat!("Panic in GLFW ", stringify!($name), " callback"));
-                abort();
-            }
+            });
         }
     };
+    ($name:ident, $cb:ident; $($p_n:ident: $p_t:ty),* => $code:block) => {
+        callback! { $name, $cb; $($p_n: $p_t),* => {} $code }
+    };
 }
 
+// The user data of a disconnected monitor or joystick is dropped before the handler receives the
+// disconnection event
 callback! { monitor, cb;
     monitor: *mut ffi::GLFWmonitor, event: c_int => {
+        if event == ffi::GLFW_DISCONNECTED {
+            invalidate_monitor(monitor);
+        }
+    } {
         match event {
             ffi::GLFW_CONNECTED => cb(Event::MonitorConnected(Monitor::create_from(monitor))),
-            ffi::GLFW_DISCONNECTED => {
-                invalidate_monitor(monitor);
-                cb(Event::MonitorDisconnected(&DisconnectedMonitor(monitor)));
-            }
+            ffi::GLFW_DISCONNECTED => cb(Event::MonitorDisconnected(&DisconnectedMonitor(monitor))),
             _ => unreachable!()
         }
     }
@@ -127,6 +427,10 @@ callback! { monitor, cb;
 
 callback! { joystick, cb;
     joystick: c_int, event: c_int => {
+        if event == ffi::GLFW_DISCONNECTED {
+            take_joystick_user_data(joystick);
+        }
+    } {
         match event {
             ffi::GLFW_CONNECTED =>
                     cb(Event::JoystickConnected(Joystick::from_i32(joystick).unwrap())),
@@ -137,6 +441,15 @@ callback! { joystick, cb;
     }
 }
 
+/// Passes an event GLFW does not report through a callback to the event processor.
+pub(crate) fn emit(event: Event) {
+    unsafe {
+        if let Some(cbptr) = EVENT_PROCESSOR {
+            (&mut *cbptr)(event);
+        }
+    }
+}
+
 pub(crate) fn initialize_callbacks() {
     unsafe {
         ffi::glfwSetMonitorCallback(Some(monitor));
@@ -270,16 +583,19 @@ window_callbacks! {
             ffi::GLFW_PRESS => Event::KeyDown {
                 win: &win,
                 key: KeyCode::from_i32(key).map_or(Key::Unnamed(scancode), |c| Key::Named(c)),
+                scancode,
                 modifiers: Modifiers::from_bits(mods).unwrap()
             },
             ffi::GLFW_RELEASE => Event::KeyUp {
                 win: &win,
                 key: KeyCode::from_i32(key).map_or(Key::Unnamed(scancode), |c| Key::Named(c)),
+                scancode,
                 modifiers: Modifiers::from_bits(mods).unwrap()
             },
             ffi::GLFW_REPEAT => Event::KeyRepeat {
                 win: &win,
                 key: KeyCode::from_i32(key).map_or(Key::Unnamed(scancode), |c| Key::Named(c)),
+                scancode,
                 modifiers: Modifiers::from_bits(mods).unwrap()
             },
             _ => unreachable!()
@@ -291,6 +607,15 @@ window_callbacks! {
         transform win = char: c_uint => Event::CharTyped(&win, from_u32(char).unwrap());
     }
 
+    char_mods {
+        glfw = glfwSetCharModsCallback;
+        transform win = char: c_uint, mods: c_int => Event::CharTypedWithModifiers {
+            win: &win,
+            character: from_u32(char).unwrap(),
+            modifiers: Modifiers::from_bits(mods).unwrap()
+        };
+    }
+
     file_drop {
         glfw = glfwSetDropCallback;
         transform win = count: c_int, paths: *const *const c_char => {
@@ -300,4 +625,75 @@ window_callbacks! {
             ).collect())
         };
     }
+}
+
+/// Invokes the callback GLFW would invoke for `event`, or passes it to the event processor directly
+/// for events not reported by GLFW and joystick connections. `window` is the window the event is
+/// about, or null for joystick events.
+#[cfg(feature = "event-injection")]
+pub(crate) fn inject(event: &OwnedEvent, window: *mut ffi::GLFWwindow) {
+    use self::OwnedEvent::*;
+    fn key_code(key: Key) -> c_int {
+        match key {
+            Key::Named(code) => code as c_int,
+            Key::Unnamed(_) => ffi::GLFW_KEY_UNKNOWN
+        }
+    }
+    let w = window;
+    match *event {
+        MonitorConnected(_) | MonitorDisconnected(_) => unreachable!(),
+        // Not through the joystick callback, which would drop the user data of the real joystick
+        JoystickConnected(j) => emit(Event::JoystickConnected(j)),
+        JoystickDisconnected(j) => emit(Event::JoystickDisconnected(j)),
+        WindowPosition { x, y, .. } => window_pos(w, x, y),
+        WindowResize { width, height, .. } => window_size(w, width, height),
+        WindowCloseRequested(_) => window_close(w),
+        WindowRefresh(_) => window_refresh(w),
+        WindowFocused(_) => window_focus(w, ffi::GLFW_TRUE),
+        WindowDefocused(_) => window_focus(w, ffi::GLFW_FALSE),
+        WindowIconified(_) => window_iconify(w, ffi::GLFW_TRUE),
+        WindowDeiconified(_) => window_iconify(w, ffi::GLFW_FALSE),
+        WindowMaximized(_) => window_maximize(w, ffi::GLFW_TRUE),
+        WindowDemaximized(_) => window_maximize(w, ffi::GLFW_FALSE),
+        FramebufferSizeChanged { width, height, .. } => framebuffer_size(w, width, height),
+        ContentScaleChanged { x_scale, y_scale, .. } => content_scale(w, x_scale, y_scale),
+        MouseButtonDown { button, modifiers, .. } =>
+                mouse_button(w, button as c_int, ffi::GLFW_PRESS, modifiers.bits()),
+        MouseButtonUp { button, modifiers, .. } =>
+                mouse_button(w, button as c_int, ffi::GLFW_RELEASE, modifiers.bits()),
+        CursorMoved { x, y, .. } => cursor_pos(w, x, y),
+        CursorEntered(_) => cursor_enter(w, ffi::GLFW_TRUE),
+        CursorExited(_) => cursor_enter(w, ffi::GLFW_FALSE),
+        Scroll { x_offset, y_offset, .. } => scroll(w, x_offset, y_offset),
+        KeyDown { key: k, scancode, modifiers, .. } =>
+                key(w, key_code(k), scancode, ffi::GLFW_PRESS, modifiers.bits()),
+        KeyUp { key: k, scancode, modifiers, .. } =>
+                key(w, key_code(k), scancode, ffi::GLFW_RELEASE, modifiers.bits()),
+        KeyRepeat { key: k, scancode, modifiers, .. } =>
+                key(w, key_code(k), scancode, ffi::GLFW_REPEAT, modifiers.bits()),
+        CharTyped(_, c) => char(w, c as c_uint),
+        CharTypedWithModifiers { character, modifiers, .. } =>
+                char_mods(w, character as c_uint, modifiers.bits()),
+        FileDrop(_, ref paths) => {
+            // `Glfw::inject_event` rejects paths containing NUL bytes
+            let paths: Vec<_> = paths.iter()
+                    .map(|p| ::std::ffi::CString::new(p.to_string_lossy().into_owned()).unwrap())
+                    .collect();
+            let ptrs: Vec<_> = paths.iter().map(|p| p.as_ptr()).collect();
+            file_drop(w, ptrs.len() as c_int, ptrs.as_ptr());
+        }
+        GamepadButtonDown { joystick, button } =>
+                emit(Event::GamepadButtonDown { joystick, button }),
+        GamepadButtonUp { joystick, button } => emit(Event::GamepadButtonUp { joystick, button }),
+        GamepadAxisMoved { joystick, axis, value } =>
+                emit(Event::GamepadAxisMoved { joystick, axis, value }),
+        JoystickButtonDown { joystick, button } =>
+                emit(Event::JoystickButtonDown { joystick, button }),
+        JoystickButtonUp { joystick, button } =>
+                emit(Event::JoystickButtonUp { joystick, button }),
+        JoystickAxisMoved { joystick, axis, value } =>
+                emit(Event::JoystickAxisMoved { joystick, axis, value }),
+        JoystickHatChanged { joystick, hat, state } =>
+                emit(Event::JoystickHatChanged { joystick, hat, state })
+    }
 }
\ No newline at end of file
@@ -6,6 +6,9 @@ pub const GLFW_VERSION_MAJOR: c_int = 3;
 pub const GLFW_VERSION_MINOR: c_int = 3;
 pub const GLFW_VERSION_REVISION: c_int = 0;
 
+#[allow(unused)] pub const GLFW_TRUE: c_int = 1;
+#[allow(unused)] pub const GLFW_FALSE: c_int = 0;
+
 pub const GLFW_RELEASE: c_int = 0;
 pub const GLFW_PRESS: c_int = 1;
 pub const GLFW_REPEAT: c_int = 2;
@@ -182,7 +185,7 @@ pub const GLFW_JOYSTICK_13: c_int = 12;
 pub const GLFW_JOYSTICK_14: c_int = 13;
 pub const GLFW_JOYSTICK_15: c_int = 14;
 pub const GLFW_JOYSTICK_16: c_int = 15;
-#[allow(unused)] pub const GLFW_JOYSTICK_LAST: c_int = GLFW_JOYSTICK_16;
+pub const GLFW_JOYSTICK_LAST: c_int = GLFW_JOYSTICK_16;
 
 pub const GLFW_GAMEPAD_BUTTON_A: c_int = 0;
 pub const GLFW_GAMEPAD_BUTTON_B: c_int = 1;
@@ -216,7 +219,7 @@ pub const GLFW_GAMEPAD_AXIS_RIGHT_TRIGGER: c_int = 5;
 #[allow(unused)] pub const GLFW_GAMEPAD_AXIS_LAST: c_int = GLFW_GAMEPAD_AXIS_RIGHT_TRIGGER;
 
 pub const GLFW_NO_ERROR: c_int = 0;
-pub const GLFW_NOT_INITIALIZED: c_int = 0x00010001;
+#[allow(unused)] pub const GLFW_NOT_INITIALIZED: c_int = 0x00010001;
 pub const GLFW_NO_CURRENT_CONTEXT: c_int = 0x00010002;
 pub const GLFW_INVALID_ENUM: c_int = 0x00010003;
 pub const GLFW_INVALID_VALUE: c_int = 0x00010004;
@@ -343,7 +346,7 @@ pub type GLFWcursorenterfun = extern "C" fn(*mut GLFWwindow,c_int);
 pub type GLFWscrollfun = extern "C" fn(*mut GLFWwindow,c_double,c_double);
 pub type GLFWkeyfun = extern "C" fn(*mut GLFWwindow,c_int,c_int,c_int,c_int);
 pub type GLFWcharfun = extern "C" fn(*mut GLFWwindow,c_uint);
-#[allow(unused)] pub type GLFWcharmodsfun = extern "C" fn(*mut GLFWwindow,c_uint,c_int);
+pub type GLFWcharmodsfun = extern "C" fn(*mut GLFWwindow,c_uint,c_int);
 pub type GLFWdropfun = extern "C" fn(*mut GLFWwindow,c_int,*const *const c_char);
 pub type GLFWmonitorfun = extern "C" fn(*mut GLFWmonitor,c_int);
 pub type GLFWjoystickfun = extern "C" fn(c_int,c_int);
@@ -385,6 +388,10 @@ pub struct GLFWgamepadstate {
     pub(crate) axes: [c_float; 6]
 }
 
+#[cfg(feature = "mock")]
+pub use mock::glfw::*;
+
+#[cfg(not(feature = "mock"))]
 #[link(name = "glfw3", kind = "static")]
 extern "C" {
     pub fn glfwInit() -> c_int;
@@ -401,9 +408,9 @@ extern "C" {
     pub fn glfwGetMonitorPhysicalSize(monitor: *mut GLFWmonitor, widthMM: *mut c_int, heightMM: *mut c_int);
     pub fn glfwGetMonitorContentScale(monitor: *mut GLFWmonitor, xscale: *mut c_float, yscale: *mut c_float);
     pub fn glfwGetMonitorName(monitor: *mut GLFWmonitor) -> *const c_char;
-    // We don't expose monitor user pointers
-    #[allow(unused)] pub fn glfwSetMonitorUserPointer(monitor: *mut GLFWmonitor, pointer: *mut c_void);
-    #[allow(unused)] pub fn glfwGetMonitorUserPointer(monitor: *mut GLFWmonitor) -> *mut c_void;
+    // The monitor user pointer is owned by the wrapper; see monitor.rs Monitor::set_user_data
+    pub fn glfwSetMonitorUserPointer(monitor: *mut GLFWmonitor, pointer: *mut c_void);
+    pub fn glfwGetMonitorUserPointer(monitor: *mut GLFWmonitor) -> *mut c_void;
     pub fn glfwSetMonitorCallback(cbfun: Option<GLFWmonitorfun>) -> Option<GLFWmonitorfun>;
     pub fn glfwGetVideoModes(monitor: *mut GLFWmonitor, count: *mut c_int) -> *const GLFWvidmode;
     pub fn glfwGetVideoMode(monitor: *mut GLFWmonitor) -> *const GLFWvidmode;
@@ -442,8 +449,9 @@ extern "C" {
     pub fn glfwSetWindowMonitor(window: *mut GLFWwindow, monitor: *mut GLFWmonitor, xpos: c_int, ypos: c_int, width: c_int, height: c_int, refreshRate: c_int);
     pub fn glfwGetWindowAttrib(window: *mut GLFWwindow, attrib: c_int) -> c_int;
     pub fn glfwSetWindowAttrib(window: *mut GLFWwindow, attrib: c_int, value: c_int);
-    #[allow(unused)] pub fn glfwSetWindowUserPointer(window: *mut GLFWwindow, pointer: *mut c_void);
-    #[allow(unused)] pub fn glfwGetWindowUserPointer(window: *mut GLFWwindow) -> *mut c_void;
+    // The window user pointer is owned by the wrapper; see window.rs WindowData
+    pub fn glfwSetWindowUserPointer(window: *mut GLFWwindow, pointer: *mut c_void);
+    pub fn glfwGetWindowUserPointer(window: *mut GLFWwindow) -> *mut c_void;
     pub fn glfwSetWindowPosCallback(window: *mut GLFWwindow, cbfun: Option<GLFWwindowposfun>) -> Option<GLFWwindowposfun>;
     pub fn glfwSetWindowSizeCallback(window: *mut GLFWwindow, cbfun: Option<GLFWwindowsizefun>) -> Option<GLFWwindowsizefun>;
     pub fn glfwSetWindowCloseCallback(window: *mut GLFWwindow, cbfun: Option<GLFWwindowclosefun>) -> Option<GLFWwindowclosefun>;
@@ -472,7 +480,7 @@ extern "C" {
     pub fn glfwSetCursor(window: *mut GLFWwindow, cursor: *mut GLFWcursor);
     pub fn glfwSetKeyCallback(window: *mut GLFWwindow, cbfun: Option<GLFWkeyfun>) -> Option<GLFWkeyfun>;
     pub fn glfwSetCharCallback(window: *mut GLFWwindow, cbfun: Option<GLFWcharfun>) -> Option<GLFWcharfun>;
-    #[allow(unused)] pub fn glfwSetCharModsCallback(window: *mut GLFWwindow, cbfun: Option<GLFWcharmodsfun>) -> Option<GLFWcharmodsfun>;
+    pub fn glfwSetCharModsCallback(window: *mut GLFWwindow, cbfun: Option<GLFWcharmodsfun>) -> Option<GLFWcharmodsfun>;
     pub fn glfwSetMouseButtonCallback(window: *mut GLFWwindow, cbfun: Option<GLFWmousebuttonfun>) -> Option<GLFWmousebuttonfun>;
     pub fn glfwSetCursorPosCallback(window: *mut GLFWwindow, cbfun: Option<GLFWcursorposfun>) -> Option<GLFWcursorposfun>;
     pub fn glfwSetCursorEnterCallback(window: *mut GLFWwindow, cbfun: Option<GLFWcursorenterfun>) -> Option<GLFWcursorenterfun>;
@@ -484,9 +492,9 @@ extern "C" {
     pub fn glfwGetJoystickHats(jid: c_int, count: *mut c_int) -> *const c_uchar;
     pub fn glfwGetJoystickName(jid: c_int) -> *const c_char;
     pub fn glfwGetJoystickGUID(jid: c_int) -> *const c_char;
-    // We don't expose joystick user pointers
-    #[allow(unused)] pub fn glfwSetJoystickUserPointer(jid: c_int, pointer: *mut c_void);
-    #[allow(unused)] pub fn glfwGetJoystickUserPointer(jid: c_int) -> *mut c_void;
+    // The joystick user pointer is owned by the wrapper; see Glfw::set_joystick_user_data
+    pub fn glfwSetJoystickUserPointer(jid: c_int, pointer: *mut c_void);
+    pub fn glfwGetJoystickUserPointer(jid: c_int) -> *mut c_void;
     pub fn glfwJoystickIsGamepad(jid: c_int) -> c_int;
     pub fn glfwSetJoystickCallback(cbfun: Option<GLFWjoystickfun>) -> Option<GLFWjoystickfun>;
     pub fn glfwUpdateGamepadMappings(string: *const c_char) -> c_int;
@@ -508,13 +516,16 @@ extern "C" {
     pub fn glfwGetProcAddress(procname: *const c_char) -> GLFWglproc;
 }
 
-#[cfg(target_os="windows")]
+#[cfg(all(not(feature = "mock"), target_os="windows"))]
 #[link(name = "opengl32")]
 #[link(name = "gdi32")]
 #[link(name = "user32")]
 extern {}
 
-#[cfg(any(target_os="linux", target_os="freebsd", target_os="dragonfly"))]
+#[cfg(all(
+    not(feature = "mock"),
+    any(target_os="linux", target_os="freebsd", target_os="dragonfly")
+))]
 #[link(name = "X11")]
 #[link(name = "GL")]
 #[link(name = "Xxf86vm")]
@@ -524,7 +535,7 @@ extern {}
 #[link(name = "Xinerama")]
 extern {}
 
-#[cfg(target_os="macos")]
+#[cfg(all(not(feature = "mock"), target_os="macos"))]
 #[link(name = "Cocoa", kind = "framework")]
 #[link(name = "OpenGL", kind = "framework")]
 #[link(name = "IOKit", kind = "framework")]
@@ -598,6 +609,46 @@ pub mod glx {
     }
 }
 
+#[cfg(feature = "vulkan")]
+pub mod vulkan {
+    use std::os::raw::c_void;
+    #[cfg(not(feature = "mock"))]
+    use super::GLFWwindow;
+    #[cfg(not(feature = "mock"))]
+    use std::os::raw::{ c_char, c_int };
+
+    // Vulkan handle types as defined by vulkan_core.h. Dispatchable handles are pointers to opaque
+    // structs, non-dispatchable handles are 64-bit integers on every platform we care about.
+    pub type VkInstance = *mut c_void;
+    pub type VkPhysicalDevice = *mut c_void;
+    pub type VkSurfaceKHR = u64;
+    pub type VkResult = i32;
+    pub enum VkAllocationCallbacks {}
+
+    pub const VK_SUCCESS: VkResult = 0;
+    pub const VK_ERROR_OUT_OF_HOST_MEMORY: VkResult = -1;
+    pub const VK_ERROR_OUT_OF_DEVICE_MEMORY: VkResult = -2;
+    pub const VK_ERROR_EXTENSION_NOT_PRESENT: VkResult = -7;
+
+    pub type GLFWvkproc = *const c_void;
+
+    pub type PFN_vkDestroySurfaceKHR = extern "system" fn(
+        VkInstance, VkSurfaceKHR, *const VkAllocationCallbacks
+    );
+
+    #[cfg(feature = "mock")]
+    pub use mock::glfw::vulkan::*;
+
+    #[cfg(not(feature = "mock"))]
+    extern {
+        pub fn glfwVulkanSupported() -> c_int;
+        pub fn glfwGetRequiredInstanceExtensions(count: *mut u32) -> *const *const c_char;
+        pub fn glfwGetInstanceProcAddress(instance: VkInstance, procname: *const c_char) -> GLFWvkproc;
+        pub fn glfwGetPhysicalDevicePresentationSupport(instance: VkInstance, device: VkPhysicalDevice, queuefamily: u32) -> c_int;
+        pub fn glfwCreateWindowSurface(instance: VkInstance, window: *mut GLFWwindow, allocator: *const VkAllocationCallbacks, surface: *mut VkSurfaceKHR) -> VkResult;
+    }
+}
+
 // TODO: Expose Wayland, Mir
 
 // TODO: Expose EGL, OSMesa
\ No newline at end of file
@@ -0,0 +1,152 @@
+use ffi;
+use Error;
+use ErrorKind;
+use Monitor;
+use Result;
+use SetWindowAttribute;
+use VideoMode;
+use Window;
+use WindowAttribute;
+use monitor::connected_monitors;
+use placement::{ overlap, workarea };
+
+/// A display mode for [`Window::set_fullscreen()`].
+///
+/// [`Window::set_fullscreen()`]: struct.Window.html#method.set_fullscreen
+#[derive(Debug)]
+pub enum FullscreenMode {
+    /// A regular window, at the position and size it had before it was made fullscreen. A window
+    /// created fullscreen is centered on the work area of its monitor instead, keeping its size
+    /// if it fits.
+    Windowed,
+    /// An undecorated window covering a monitor, without changing its video mode. Switching to
+    /// and from other windows is instant, and the window is not iconified when it loses focus.
+    Borderless(Monitor),
+    /// A fullscreen window with exclusive use of a monitor, changing its video mode to the
+    /// closest match GLFW finds for the given one.
+    Exclusive(Monitor, VideoMode)
+}
+
+/// The windowed state saved while a window is fullscreen.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct WindowedState {
+    pub(crate) pos: (i32, i32),
+    pub(crate) size: (i32, i32),
+    pub(crate) decorated: bool,
+    pub(crate) maximized: bool
+}
+
+impl<'a> Window<'a> {
+    /// Switches the window between windowed, borderless and exclusive fullscreen modes.
+    ///
+    /// The position, size, decoration and maximization of the window are saved when it leaves
+    /// windowed mode, and restored when it returns to it, even after switching between
+    /// fullscreen modes in between.
+    pub fn set_fullscreen(&self, mode: FullscreenMode) -> Result<()> {
+        if let FullscreenMode::Windowed = mode {
+            return self.leave_fullscreen();
+        }
+        if self.data().windowed.get().is_none() {
+            let maximized = self.get_attribute(WindowAttribute::Maximized)?;
+            if maximized {
+                // The saved rect is the one the window returns to when restored
+                self.restore()?;
+            }
+            let (x, y) = self.get_pos()?;
+            let (width, height) = self.get_window_size()?;
+            self.data().windowed.set(Some(WindowedState {
+                pos: (x, y),
+                size: (width, height),
+                decorated: self.get_attribute(WindowAttribute::Decorated)?,
+                maximized
+            }));
+        }
+        match mode {
+            FullscreenMode::Borderless(ref monitor) => {
+                let (x, y) = monitor.get_pos()?;
+                let video_mode = monitor.get_video_mode()?;
+                self.set_attribute(SetWindowAttribute::Decorated(false))?;
+                self.set_monitor(None, x, y, video_mode.width, video_mode.height, None)
+            }
+            FullscreenMode::Exclusive(ref monitor, video_mode) => self.set_monitor(Some(monitor),
+                    0, 0, video_mode.width, video_mode.height, Some(video_mode.refresh_rate)),
+            FullscreenMode::Windowed => unreachable!()
+        }
+    }
+
+    fn leave_fullscreen(&self) -> Result<()> {
+        let state = match self.data().windowed.get() {
+            Some(state) => state,
+            None => return match self.get_monitor() {
+                // Created fullscreen, so there is no windowed state to return to
+                Some(monitor) => {
+                    let (x, y, area_width, area_height) = workarea(&monitor)?;
+                    let (width, height) = self.get_window_size()?;
+                    let (width, height) = (width.min(area_width), height.min(area_height));
+                    self.set_monitor(None, x + (area_width - width) / 2,
+                            y + (area_height - height) / 2, width, height, None)
+                }
+                None => Ok(())
+            }
+        };
+        let (x, y) = state.pos;
+        let (width, height) = state.size;
+        self.set_attribute(SetWindowAttribute::Decorated(state.decorated))?;
+        self.set_monitor(None, x, y, width, height, None)?;
+        if state.maximized {
+            self.maximize()?;
+        }
+        self.data().windowed.set(None);
+        Ok(())
+    }
+
+    /// Returns whether the window was made fullscreen, either with [`set_fullscreen()`] or by
+    /// being given a monitor.
+    ///
+    /// [`set_fullscreen()`]: #method.set_fullscreen
+    pub fn is_fullscreen(&self) -> bool {
+        self.data().windowed.get().is_some() || self.get_monitor().is_some()
+    }
+
+    /// Switches the window to borderless fullscreen on [`current_monitor()`] if it is windowed,
+    /// and back to windowed mode otherwise, as usually done with F11.
+    ///
+    /// [`current_monitor()`]: #method.current_monitor
+    pub fn toggle_fullscreen(&self) -> Result<()> {
+        if self.is_fullscreen() {
+            return self.set_fullscreen(FullscreenMode::Windowed);
+        }
+        match self.current_monitor()? {
+            Some(monitor) => self.set_fullscreen(FullscreenMode::Borderless(monitor)),
+            None => Err(Error {
+                kind: ErrorKind::PlatformError,
+                description: "No monitor is connected".to_owned(),
+                function: "Window::toggle_fullscreen"
+            })
+        }
+    }
+
+    /// Returns the monitor the window is fullscreen on, or else the one containing the largest
+    /// part of the window, or the primary monitor if the window is not on any monitor.
+    pub fn current_monitor(&self) -> Result<Option<Monitor>> {
+        if let Some(monitor) = self.get_monitor() {
+            return Ok(Some(monitor));
+        }
+        let (x, y) = self.get_pos()?;
+        let (width, height) = self.get_window_size()?;
+        let mut best = None;
+        let mut best_area = 0;
+        for monitor in connected_monitors() {
+            let (mx, my) = monitor.get_pos()?;
+            let mode = monitor.get_video_mode()?;
+            let area = overlap((x, y, width, height), (mx, my, mode.width, mode.height));
+            if area > best_area {
+                best_area = area;
+                best = Some(monitor);
+            }
+        }
+        Ok(best.or_else(|| unsafe {
+            ffi::glfwGetPrimaryMonitor().as_mut().map(|p| Monitor::create_from(p))
+        }))
+    }
+}
@@ -0,0 +1,185 @@
+use GamepadState;
+
+/// How small stick movements are filtered out by [`StickSettings`].
+///
+/// [`StickSettings`]: struct.StickSettings.html
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Deadzone {
+    /// No filtering.
+    None,
+    /// Each axis is zeroed separately while within the given distance of the center. Keeps
+    /// movements along an axis from drifting on the other, but makes diagonals sticky.
+    Axial(f32),
+    /// The stick is zeroed while within the given distance of the center, and reported unchanged
+    /// outside of it, so values jump from zero to the size of the deadzone.
+    Radial(f32),
+    /// Like `Radial`, but the remaining range is scaled to start from zero at the edge of the
+    /// deadzone, so values change smoothly.
+    ScaledRadial(f32)
+}
+
+/// Processing applied to an analog stick by [`GamepadSettings`].
+///
+/// The steps are applied in order: the deadzone, Y axis inversion, the outer deadzone, then the
+/// response curve, which keeps the direction of the stick and only changes its distance from the
+/// center.
+///
+/// [`GamepadSettings`]: struct.GamepadSettings.html
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StickSettings {
+    pub deadzone: Deadzone,
+    /// Distance from the edge of the range where the stick is reported as fully pushed, for
+    /// sticks that never quite reach it. The rest of the range is scaled to fill it.
+    pub outer_deadzone: f32,
+    /// Exponent applied to the distance from the center. Values above 1 give finer control of
+    /// small movements, and values below 1 a faster response.
+    pub exponent: f32,
+    /// Flips the Y axis, which GLFW reports as negative when the stick is pushed up.
+    pub invert_y: bool
+}
+
+impl Default for StickSettings {
+    /// Returns settings that leave the stick unchanged.
+    fn default() -> Self {
+        StickSettings {
+            deadzone: Deadzone::None,
+            outer_deadzone: 0.0,
+            exponent: 1.0,
+            invert_y: false
+        }
+    }
+}
+
+impl StickSettings {
+    /// Returns the processed position of a stick.
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let (mut x, mut y) = (x, y);
+        let mut inner = 0.0;
+        match self.deadzone {
+            Deadzone::None => {}
+            Deadzone::Axial(size) => {
+                if x.abs() < size {
+                    x = 0.0;
+                }
+                if y.abs() < size {
+                    y = 0.0;
+                }
+            }
+            Deadzone::Radial(size) | Deadzone::ScaledRadial(size) => {
+                if x.hypot(y) < size {
+                    return (0.0, 0.0);
+                }
+                if let Deadzone::ScaledRadial(_) = self.deadzone {
+                    inner = size;
+                }
+            }
+        }
+        if self.invert_y {
+            y = -y;
+        }
+
+        let distance = x.hypot(y);
+        if distance == 0.0 {
+            return (0.0, 0.0);
+        }
+        let scaled = response(distance, inner, self.outer_deadzone, self.exponent);
+        (x / distance * scaled, y / distance * scaled)
+    }
+}
+
+/// Processing applied to a trigger by [`GamepadSettings`].
+///
+/// Triggers are processed over the distance they are pressed, from 0 to 1, then reported in the
+/// range GLFW uses, from -1 when released to 1 when fully pressed.
+///
+/// [`GamepadSettings`]: struct.GamepadSettings.html
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TriggerSettings {
+    /// How far the trigger must be pressed, from 0 to 1, before it is reported as pressed at all.
+    pub threshold: f32,
+    /// Distance from the end of the range where the trigger is reported as fully pressed.
+    pub outer_deadzone: f32,
+    /// Exponent applied to the distance pressed, as for [`StickSettings::exponent`].
+    ///
+    /// [`StickSettings::exponent`]: struct.StickSettings.html#structfield.exponent
+    pub exponent: f32
+}
+
+impl Default for TriggerSettings {
+    /// Returns settings that leave the trigger unchanged.
+    fn default() -> Self {
+        TriggerSettings {
+            threshold: 0.0,
+            outer_deadzone: 0.0,
+            exponent: 1.0
+        }
+    }
+}
+
+impl TriggerSettings {
+    /// Returns the processed value of a trigger, given and returned in the range GLFW uses.
+    pub fn apply(&self, value: f32) -> f32 {
+        let pressed = ((value + 1.0) / 2.0).max(0.0);
+        if pressed < self.threshold || pressed == 0.0 {
+            return -1.0;
+        }
+        response(pressed, self.threshold, self.outer_deadzone, self.exponent) * 2.0 - 1.0
+    }
+}
+
+/// Deadzones and response curves for the analog inputs of a gamepad, so they are handled the same
+/// way everywhere. The default settings leave the state unchanged.
+///
+/// ```
+/// use glfw_wrapper::{ Deadzone, GamepadSettings, StickSettings };
+///
+/// let stick = StickSettings {
+///     deadzone: Deadzone::ScaledRadial(0.2),
+///     exponent: 2.0,
+///     ..Default::default()
+/// };
+/// let settings = GamepadSettings { left_stick: stick, right_stick: stick, ..Default::default() };
+/// assert_eq!(settings.left_stick.apply(0.1, 0.1), (0.0, 0.0));
+/// assert_eq!(settings.left_stick.apply(0.0, -1.0), (0.0, -1.0));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct GamepadSettings {
+    pub left_stick: StickSettings,
+    pub right_stick: StickSettings,
+    pub left_trigger: TriggerSettings,
+    pub right_trigger: TriggerSettings
+}
+
+impl GamepadSettings {
+    /// Returns `state` with the settings applied to its sticks and triggers. Buttons are copied
+    /// unchanged.
+    pub fn apply(&self, state: &GamepadState) -> GamepadState {
+        let (left_stick_x, left_stick_y) =
+                self.left_stick.apply(state.left_stick_x, state.left_stick_y);
+        let (right_stick_x, right_stick_y) =
+                self.right_stick.apply(state.right_stick_x, state.right_stick_y);
+        GamepadState {
+            left_stick_x,
+            left_stick_y,
+            right_stick_x,
+            right_stick_y,
+            left_trigger: self.left_trigger.apply(state.left_trigger),
+            right_trigger: self.right_trigger.apply(state.right_trigger),
+            ..*state
+        }
+    }
+}
+
+/// Maps `value` from the range between `inner` and `1 - outer` to the range from 0 to 1, then
+/// applies the response curve.
+fn response(value: f32, inner: f32, outer: f32, exponent: f32) -> f32 {
+    let range = 1.0 - outer - inner;
+    let scaled = if range > 0.0 {
+        ((value - inner) / range).max(0.0)
+    } else {
+        1.0
+    };
+    // Sticks can go past 1 in the corners, which is only cut off along with the outer deadzone
+    let scaled = if outer > 0.0 { scaled.min(1.0) } else { scaled };
+    scaled.powf(exponent)
+}
@@ -0,0 +1,225 @@
+use std::collections::{ HashMap, HashSet };
+
+use Event;
+use OwnedEvent;
+use WindowId;
+use Key;
+use KeyCode;
+use MouseButton;
+
+/// A keyboard key or mouse button, as tracked by [`InputState`].
+///
+/// [`InputState`]: struct.InputState.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Button {
+    Key(Key),
+    Mouse(MouseButton)
+}
+
+impl From<Key> for Button {
+    fn from(key: Key) -> Button {
+        Button::Key(key)
+    }
+}
+
+impl From<KeyCode> for Button {
+    fn from(key: KeyCode) -> Button {
+        Button::Key(Key::Named(key))
+    }
+}
+
+impl From<MouseButton> for Button {
+    fn from(button: MouseButton) -> Button {
+        Button::Mouse(button)
+    }
+}
+
+/// Keyboard and mouse state of each window, built from events.
+///
+/// Pass every event to [`handle_event()`] along with the time it was received, query the state,
+/// then call [`end_frame()`] once per frame to reset the per-frame state: the presses and
+/// releases, accumulated scrolling and cursor movement.
+///
+/// Unlike [`Window::get_key()`], presses and releases that happen between two frames are not
+/// lost, and nothing needs to be configured on the window.
+///
+/// ```no_run
+/// # use glfw_wrapper::*;
+/// # let glfw = init(Default::default()).unwrap();
+/// # let window = glfw.create_window(&Default::default(), 640, 480, "", None, None).unwrap();
+/// let mut input = InputState::new();
+/// loop {
+///     glfw.poll_events(&mut |e| input.handle_event(glfw.get_time(), &e)).unwrap();
+///     if input.was_pressed_this_frame(window.id(), KeyCode::Space) {
+///         // jump
+///     }
+///     input.end_frame();
+///     # break;
+/// }
+/// ```
+///
+/// [`handle_event()`]: #method.handle_event
+/// [`end_frame()`]: #method.end_frame
+/// [`Window::get_key()`]: struct.Window.html#method.get_key
+#[derive(Clone, Debug, Default)]
+pub struct InputState {
+    windows: HashMap<WindowId, WindowInput>
+}
+
+#[derive(Clone, Debug, Default)]
+struct WindowInput {
+    /// Buttons currently held, with the time they were pressed at.
+    down: HashMap<Button, f64>,
+    pressed: HashSet<Button>,
+    released: HashSet<Button>,
+    scroll: (f64, f64),
+    cursor: Option<(f64, f64)>,
+    cursor_delta: (f64, f64)
+}
+
+/// The parts of an event relevant to the input state.
+enum Input {
+    Press(Button),
+    Release(Button),
+    CursorMoved(f64, f64),
+    CursorEntered,
+    Scroll(f64, f64)
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Updates the state with an event received at the given GLFW time.
+    ///
+    /// Key repeats and events unrelated to keyboard and mouse input are ignored.
+    pub fn handle_event(&mut self, time: f64, event: &Event) {
+        use Event::*;
+        let (win, input) = match *event {
+            KeyDown { win, key, .. } => (win.id(), Input::Press(Button::Key(key))),
+            KeyUp { win, key, .. } => (win.id(), Input::Release(Button::Key(key))),
+            MouseButtonDown { win, button, .. } => (win.id(), Input::Press(Button::Mouse(button))),
+            MouseButtonUp { win, button, .. } => (win.id(), Input::Release(Button::Mouse(button))),
+            CursorMoved { win, x, y } => (win.id(), Input::CursorMoved(x, y)),
+            CursorEntered(win) => (win.id(), Input::CursorEntered),
+            Scroll { win, x_offset, y_offset } => (win.id(), Input::Scroll(x_offset, y_offset)),
+            _ => return
+        };
+        self.apply(time, win, input);
+    }
+
+    /// Like [`handle_event()`], for events that have been buffered as [`OwnedEvent`]s.
+    ///
+    /// [`handle_event()`]: #method.handle_event
+    /// [`OwnedEvent`]: enum.OwnedEvent.html
+    pub fn handle_owned_event(&mut self, time: f64, event: &OwnedEvent) {
+        use OwnedEvent::*;
+        let (win, input) = match *event {
+            KeyDown { win, key, .. } => (win, Input::Press(Button::Key(key))),
+            KeyUp { win, key, .. } => (win, Input::Release(Button::Key(key))),
+            MouseButtonDown { win, button, .. } => (win, Input::Press(Button::Mouse(button))),
+            MouseButtonUp { win, button, .. } => (win, Input::Release(Button::Mouse(button))),
+            CursorMoved { win, x, y } => (win, Input::CursorMoved(x, y)),
+            CursorEntered(win) => (win, Input::CursorEntered),
+            Scroll { win, x_offset, y_offset } => (win, Input::Scroll(x_offset, y_offset)),
+            _ => return
+        };
+        self.apply(time, win, input);
+    }
+
+    fn apply(&mut self, time: f64, win: WindowId, input: Input) {
+        let state = self.windows.entry(win).or_default();
+        match input {
+            Input::Press(button) => {
+                state.down.entry(button).or_insert(time);
+                state.pressed.insert(button);
+            }
+            Input::Release(button) => {
+                state.down.remove(&button);
+                state.released.insert(button);
+            }
+            Input::CursorMoved(x, y) => {
+                if let Some((old_x, old_y)) = state.cursor {
+                    state.cursor_delta.0 += x - old_x;
+                    state.cursor_delta.1 += y - old_y;
+                }
+                state.cursor = Some((x, y));
+            }
+            // The cursor may have moved anywhere while outside the window, so the jump to where it
+            // reentered is not counted as movement.
+            Input::CursorEntered => state.cursor = None,
+            Input::Scroll(x, y) => {
+                state.scroll.0 += x;
+                state.scroll.1 += y;
+            }
+        }
+    }
+
+    /// Resets the presses, releases, scrolling and cursor movement accumulated since the last
+    /// call.
+    pub fn end_frame(&mut self) {
+        for state in self.windows.values_mut() {
+            state.pressed.clear();
+            state.released.clear();
+            state.scroll = (0.0, 0.0);
+            state.cursor_delta = (0.0, 0.0);
+        }
+    }
+
+    /// Discards the state of a window, such as one that has been destroyed.
+    pub fn forget_window(&mut self, win: WindowId) {
+        self.windows.remove(&win);
+    }
+
+    /// Returns whether the button is held down in the window.
+    pub fn is_down<B: Into<Button>>(&self, win: WindowId, button: B) -> bool {
+        self.windows.get(&win).is_some_and(|s| s.down.contains_key(&button.into()))
+    }
+
+    /// Returns whether the button was pressed in the window since the last [`end_frame()`], even
+    /// if it has been released since.
+    ///
+    /// [`end_frame()`]: #method.end_frame
+    pub fn was_pressed_this_frame<B: Into<Button>>(&self, win: WindowId, button: B) -> bool {
+        self.windows.get(&win).is_some_and(|s| s.pressed.contains(&button.into()))
+    }
+
+    /// Returns whether the button was released in the window since the last [`end_frame()`], even
+    /// if it has been pressed again since.
+    ///
+    /// [`end_frame()`]: #method.end_frame
+    pub fn was_released_this_frame<B: Into<Button>>(&self, win: WindowId, button: B) -> bool {
+        self.windows.get(&win).is_some_and(|s| s.released.contains(&button.into()))
+    }
+
+    /// Returns for how long the button has been held down at the GLFW time `now`, or `None` if it
+    /// is not held down.
+    pub fn held_duration<B: Into<Button>>(&self, win: WindowId, button: B, now: f64)
+            -> Option<f64> {
+        self.windows.get(&win)
+                .and_then(|s| s.down.get(&button.into()))
+                .map(|&pressed| now - pressed)
+    }
+
+    /// Returns the scrolling accumulated in the window since the last [`end_frame()`].
+    ///
+    /// [`end_frame()`]: #method.end_frame
+    pub fn scroll(&self, win: WindowId) -> (f64, f64) {
+        self.windows.get(&win).map_or((0.0, 0.0), |s| s.scroll)
+    }
+
+    /// Returns how far the cursor moved in the window since the last [`end_frame()`], in screen
+    /// coordinates.
+    ///
+    /// [`end_frame()`]: #method.end_frame
+    pub fn cursor_delta(&self, win: WindowId) -> (f64, f64) {
+        self.windows.get(&win).map_or((0.0, 0.0), |s| s.cursor_delta)
+    }
+
+    /// Returns the last cursor position reported for the window, or `None` if the cursor has not
+    /// moved since it entered the window.
+    pub fn cursor_position(&self, win: WindowId) -> Option<(f64, f64)> {
+        self.windows.get(&win).and_then(|s| s.cursor)
+    }
+}
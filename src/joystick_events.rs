@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use enum_primitive::FromPrimitive;
+
+use ffi;
+use Glfw;
+use Event;
+use Joystick;
+use JoystickHatState;
+use GamepadButton;
+use GamepadAxis;
+use GamepadState;
+
+/// Finds joystick and gamepad events by comparing the state of each joystick with the state it
+/// was in during the previous event processing call.
+pub(crate) struct JoystickEvents {
+    axis_epsilon: f32,
+    joysticks: HashMap<Joystick, Snapshot>
+}
+
+/// The state of a joystick as of the last events reported for it. Axes hold the last reported
+/// values rather than the last polled ones, so slow movements add up to an event eventually.
+enum Snapshot {
+    Gamepad {
+        state: GamepadState,
+        axes: [f32; 6]
+    },
+    Raw {
+        axes: Vec<f32>,
+        buttons: Vec<bool>,
+        hats: Vec<JoystickHatState>
+    }
+}
+
+impl JoystickEvents {
+    pub(crate) fn new(axis_epsilon: f32) -> Self {
+        JoystickEvents {
+            axis_epsilon,
+            joysticks: HashMap::new()
+        }
+    }
+
+    /// Appends the changes since the last call to `events`.
+    ///
+    /// Nothing is reported for joysticks seen for the first time, or whose gamepad mapping was
+    /// added or removed; their current state becomes the starting point.
+    pub(crate) fn poll(&mut self, glfw: &Glfw, events: &mut Vec<Event<'static>>) {
+        for jid in ffi::GLFW_JOYSTICK_1..=ffi::GLFW_JOYSTICK_LAST {
+            let joystick = Joystick::from_i32(jid).unwrap();
+            let current = match snapshot(glfw, joystick) {
+                Some(current) => current,
+                None => {
+                    self.joysticks.remove(&joystick);
+                    continue
+                }
+            };
+            match self.joysticks.entry(joystick) {
+                Entry::Vacant(entry) => {
+                    entry.insert(current);
+                }
+                Entry::Occupied(mut entry) => {
+                    if !compare(entry.get_mut(), &current, joystick, self.axis_epsilon, events) {
+                        entry.insert(current);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn snapshot(glfw: &Glfw, joystick: Joystick) -> Option<Snapshot> {
+    if glfw.is_joystick_gamepad(joystick) {
+        glfw.get_gamepad_state(joystick).map(|state| {
+            let mut axes = [0.0; 6];
+            for (value, axis) in axes.iter_mut().zip(&GamepadAxis::ALL) {
+                *value = state[*axis];
+            }
+            Snapshot::Gamepad { state, axes }
+        })
+    } else {
+        match (
+            glfw.get_joystick_axes(joystick),
+            glfw.get_joystick_buttons(joystick),
+            glfw.get_joystick_hats(joystick)
+        ) {
+            (Ok(Some(axes)), Ok(Some(buttons)), Ok(Some(hats))) =>
+                    Some(Snapshot::Raw { axes, buttons, hats }),
+            _ => None
+        }
+    }
+}
+
+/// Reports the differences between `previous` and `current`, updating `previous` to match what
+/// was reported. Returns `false` if the two cannot be compared.
+fn compare(
+    previous: &mut Snapshot, current: &Snapshot, joystick: Joystick, epsilon: f32,
+    events: &mut Vec<Event<'static>>
+) -> bool {
+    match (previous, current) {
+        (Snapshot::Gamepad { state, axes },
+                Snapshot::Gamepad { state: new_state, axes: new_axes }) => {
+            for &button in &GamepadButton::ALL {
+                match (state[button], new_state[button]) {
+                    (false, true) => events.push(Event::GamepadButtonDown { joystick, button }),
+                    (true, false) => events.push(Event::GamepadButtonUp { joystick, button }),
+                    _ => {}
+                }
+            }
+            *state = *new_state;
+            for (i, (old, &new)) in axes.iter_mut().zip(new_axes).enumerate() {
+                if (new - *old).abs() > epsilon {
+                    let axis = GamepadAxis::ALL[i];
+                    events.push(Event::GamepadAxisMoved { joystick, axis, value: new });
+                    *old = new;
+                }
+            }
+            true
+        }
+        (Snapshot::Raw { axes, buttons, hats },
+                Snapshot::Raw { axes: new_axes, buttons: new_buttons, hats: new_hats })
+                if axes.len() == new_axes.len() && buttons.len() == new_buttons.len() &&
+                        hats.len() == new_hats.len() => {
+            for (button, (old, &new)) in buttons.iter_mut().zip(new_buttons).enumerate() {
+                if *old != new {
+                    events.push(if new {
+                        Event::JoystickButtonDown { joystick, button }
+                    } else {
+                        Event::JoystickButtonUp { joystick, button }
+                    });
+                    *old = new;
+                }
+            }
+            for (axis, (old, &new)) in axes.iter_mut().zip(new_axes).enumerate() {
+                if (new - *old).abs() > epsilon {
+                    events.push(Event::JoystickAxisMoved { joystick, axis, value: new });
+                    *old = new;
+                }
+            }
+            for (hat, (old, &new)) in hats.iter_mut().zip(new_hats).enumerate() {
+                if *old != new {
+                    events.push(Event::JoystickHatChanged { joystick, hat, state: new });
+                    *old = new;
+                }
+            }
+            true
+        }
+        _ => false
+    }
+}
@@ -16,6 +16,12 @@
 extern crate enum_primitive;
 #[macro_use]
 extern crate bitflags;
+#[cfg(feature = "log")]
+#[macro_use]
+extern crate log;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
 
 #[cfg(all(
     any(feature = "expose-win32", feature = "expose-wgl"),
@@ -33,11 +39,15 @@ use std::sync::atomic::{ AtomicBool, ATOMIC_BOOL_INIT, Ordering };
 use std::sync::{ Arc, Weak, Mutex };
 use std::marker::PhantomData;
 use std::ptr;
-use std::cell::RefCell;
+use std::cell::{ Cell, RefCell };
+use std::rc::Rc;
+use std::collections::{ HashMap, VecDeque };
 use std::slice;
 use std::ops::Deref;
 use std::os::raw::{ c_int, c_char };
 use std::mem;
+use std::any::Any;
+use std::fmt;
 
 use enum_primitive::FromPrimitive;
 
@@ -46,6 +56,22 @@ mod events;
 mod window;
 mod monitor;
 mod misc;
+mod record;
+mod input;
+mod binding;
+mod shortcut;
+mod gamepad;
+mod mappings;
+mod joystick_events;
+mod diagnostics;
+mod builder;
+mod fullscreen;
+mod placement;
+mod dpi;
+#[cfg(feature = "vulkan")]
+mod vulkan;
+#[cfg(feature = "mock")]
+pub mod mock;
 
 pub use enums::*;
 pub use window::*;
@@ -53,6 +79,19 @@ pub use monitor::*;
 pub use misc::*;
 pub use ffi::GLFWglproc as GlProc;
 pub use events::*;
+pub use record::*;
+pub use input::*;
+pub use binding::*;
+pub use shortcut::*;
+pub use gamepad::*;
+pub use mappings::*;
+pub use diagnostics::*;
+pub use builder::*;
+pub use fullscreen::*;
+pub use placement::*;
+pub use dpi::*;
+#[cfg(feature = "vulkan")]
+pub use vulkan::*;
 
 mod ffi;
 mod util;
@@ -66,9 +105,19 @@ pub const VERSION: (i32, i32, i32) =
 #[derive(Debug)]
 pub struct Error {
     pub kind: ErrorKind,
-    pub description: String
+    pub description: String,
+    /// The wrapper function that reported the error, like `"Window::set_title"`.
+    pub function: &'static str
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} failed: {} ({:?})", self.function, self.description, self.kind)
+    }
 }
 
+impl std::error::Error for Error {}
+
 /// Specialized `Result` type for GLFW errors.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -108,13 +157,21 @@ pub fn get_version_string() -> Cow<'static, str> {
 /// 
 /// [glfw]: http://www.glfw.org/docs/3.3/group__init.html#ga944986b4ec0b928d488141f92982aa18
 pub fn get_error() -> Result<()> {
+    get_error_in("get_error")
+}
+
+/// Like [`get_error()`], but reports the error as coming from `function`.
+/// 
+/// [`get_error()`]: fn.get_error.html
+pub(crate) fn get_error_in(function: &'static str) -> Result<()> {
     unsafe {
         let mut desc = std::ptr::null();
         let errorcode = ffi::glfwGetError(&mut desc);
         if let Some(error) = ErrorKind::from_i32(errorcode) {
             Err(Error {
                 kind: error,
-                description: CStr::from_ptr(desc).to_string_lossy().into_owned()
+                description: CStr::from_ptr(desc).to_string_lossy().into_owned(),
+                function
             })
         } else {
             assert!(errorcode == ffi::GLFW_NO_ERROR);
@@ -139,6 +196,24 @@ pub enum InitError {
     Failed(Error)
 }
 
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InitError::AlreadyInitialized => f.write_str("GLFW is already initialized"),
+            InitError::Failed(ref e) => write!(f, "GLFW initialization failed: {}", e)
+        }
+    }
+}
+
+impl std::error::Error for InitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            InitError::AlreadyInitialized => None,
+            InitError::Failed(ref e) => Some(e)
+        }
+    }
+}
+
 /// Initializes GLFW.
 /// 
 /// Calls to this function while a [`Glfw`] instance exists results in an
@@ -166,15 +241,13 @@ pub enum InitError {
 /// [`glfwInit()`]: http://www.glfw.org/docs/3.3/group__init.html#ga317aac130a235ab08c6db0834907d85e
 /// [`glfwInitHint()`]: http://www.glfw.org/docs/3.3/group__init.html#ga110fd1d3f0412822b4f1908c026f724a
 pub fn init(init_hints: InitHints) -> std::result::Result<Glfw, InitError> {
-    extern "C" fn err_cb(code: c_int, _: *const c_char) {
+    extern "C" fn err_cb(code: c_int, description: *const c_char) {
         if ErrorKind::from_i32(code).is_some() { return }
-        eprintln!("{} error occured. This should not be possible.", match code {
-            ffi::GLFW_NOT_INITIALIZED => "GLFW_NOT_INITIALIZED",
-            ffi::GLFW_INVALID_ENUM => "GLFW_INVALID_ENUM",
-            ffi::GLFW_NO_ERROR => "GLFW_NO_ERROR (???)",
-            _ => "??? (REALLY BAD)"
-        });
-        std::process::abort()
+        let description = match unsafe { description.as_ref() } {
+            Some(d) => unsafe { CStr::from_ptr(d) }.to_string_lossy(),
+            None => Cow::Borrowed("")
+        };
+        diagnostics::fatal(Diagnostic::UnexpectedError { code, description: &description })
     }
 
     if INIT_STATE.swap(true, Ordering::SeqCst) == false {
@@ -192,14 +265,19 @@ pub fn init(init_hints: InitHints) -> std::result::Result<Glfw, InitError> {
         }
         if cint_to_bool(unsafe { ffi::glfwInit() }) {
             events::initialize_callbacks();
+            diagnostics::set_panic_behavior(PanicBehavior::Abort);
             Ok(Glfw {
                 shared: SharedGlfw(PhantomData),
                 destruction_locker: Arc::new(Mutex::new(true)),
+                event_queue: RefCell::new(VecDeque::new()),
+                windows: RefCell::new(HashMap::new()),
+                lent_windows: Cell::new(0),
+                joystick_events: RefCell::new(None),
                 _phantom: PhantomData
             })
         } else {
             INIT_STATE.store(false, Ordering::SeqCst);
-            Err(InitError::Failed(get_error().unwrap_err()))
+            Err(InitError::Failed(get_error_in("init").unwrap_err()))
         }
     } else {
         Err(InitError::AlreadyInitialized)
@@ -215,7 +293,8 @@ thread_local! {
 
 enum ReentranceAvoidanceCommand {
     DestroyWindow(*mut ffi::GLFWwindow),
-    DestroyCursor(*mut ffi::GLFWcursor)
+    DestroyCursor(*mut ffi::GLFWcursor),
+    DropUserData(Box<dyn Any>)
 }
 
 /// Represents ownership of the GLFW library.
@@ -231,6 +310,19 @@ pub struct Glfw {
     /// 
     /// [`GlfwNotifier`]: struct.GlfwNotifier.html
     destruction_locker: Arc<Mutex<bool>>,
+    /// Events buffered by [`events()`] that have not been consumed yet.
+    /// 
+    /// [`events()`]: #method.events
+    event_queue: RefCell<VecDeque<OwnedEvent>>,
+    /// Registry of the windows created through this instance that have not been destroyed yet.
+    windows: RefCell<HashMap<WindowId, *mut ffi::GLFWwindow>>,
+    /// Number of [`with_window()`] calls in progress. While nonzero, window destruction is deferred
+    /// in the same way as during event processing, as the lent `Window` may refer to it.
+    /// 
+    /// [`with_window()`]: #method.with_window
+    lent_windows: Cell<usize>,
+    /// Set while gamepad events are enabled.
+    joystick_events: RefCell<Option<joystick_events::JoystickEvents>>,
     _phantom: PhantomData<*const ()>
 }
 
@@ -238,6 +330,9 @@ impl Drop for Glfw {
     fn drop(&mut self) {
         self.process_reentrance_avoidance();
         invalidate_all_monitors();
+        for jid in ffi::GLFW_JOYSTICK_1..ffi::GLFW_JOYSTICK_LAST + 1 {
+            take_joystick_user_data(jid);
+        }
         if let Ok(mut lock) = self.destruction_locker.lock() {
             use std::ops::DerefMut;
             *lock.deref_mut() = false;
@@ -248,8 +343,13 @@ impl Drop for Glfw {
 }
 
 impl Glfw {
+    fn destruction_deferred(&self) -> bool {
+        unsafe { EVENT_PROCESSOR.is_some() || self.lent_windows.get() > 0 }
+    }
+
     pub(crate) fn destroy_window(&self, ptr: *mut ffi::GLFWwindow) {
-        if unsafe { EVENT_PROCESSOR.is_some() } {
+        self.windows.borrow_mut().retain(|_, &mut p| p != ptr);
+        if self.destruction_deferred() {
             use ReentranceAvoidanceCommand::DestroyWindow;
             REENTRANCE_AVOIDANCE.with(|v| v.borrow_mut().push(DestroyWindow(ptr)));
             // Delayed window destruction; it may be a while before an event processing call occurs
@@ -257,6 +357,7 @@ impl Glfw {
             // the user to see a window still active that's not supposed to be there.
             unsafe { ffi::glfwHideWindow(ptr) };
         } else {
+            free_window_data(ptr);
             unsafe {
                 ffi::glfwDestroyWindow(ptr);
             }
@@ -264,7 +365,7 @@ impl Glfw {
     }
 
     pub(crate) fn destroy_cursor(&self, ptr: *mut ffi::GLFWcursor) {
-        if unsafe { EVENT_PROCESSOR.is_some() } {
+        if self.destruction_deferred() {
             use ReentranceAvoidanceCommand::DestroyCursor;
             REENTRANCE_AVOIDANCE.with(|v| v.borrow_mut().push(DestroyCursor(ptr)))
         } else {
@@ -274,17 +375,31 @@ impl Glfw {
         }
     }
 
+    /// Drops user data replaced while something may still hold a reference to it.
+    pub(crate) fn drop_user_data(&self, data: Box<dyn Any>) {
+        if self.destruction_deferred() {
+            use ReentranceAvoidanceCommand::DropUserData;
+            REENTRANCE_AVOIDANCE.with(|v| v.borrow_mut().push(DropUserData(data)))
+        } else {
+            drop(data);
+        }
+    }
+
     fn process_reentrance_avoidance(&self) {
-        REENTRANCE_AVOIDANCE.with(|v| {
-            let mut list = v.borrow_mut();
-            for todo in list.drain(..) {
-                use ReentranceAvoidanceCommand::*;
-                match todo {
-                    DestroyWindow(w) => unsafe { ffi::glfwDestroyWindow(w) }
-                    DestroyCursor(c) => unsafe { ffi::glfwDestroyCursor(c) }
+        // Take the list out first; dropping user data runs arbitrary code, which may destroy more
+        // windows and cursors.
+        let list: Vec<_> = REENTRANCE_AVOIDANCE.with(|v| v.borrow_mut().drain(..).collect());
+        for todo in list {
+            use ReentranceAvoidanceCommand::*;
+            match todo {
+                DestroyWindow(w) => {
+                    free_window_data(w);
+                    unsafe { ffi::glfwDestroyWindow(w) }
                 }
+                DestroyCursor(c) => unsafe { ffi::glfwDestroyCursor(c) },
+                DropUserData(d) => drop(d)
             }
-        })
+        }
     }
 
     /// Gets a type allowing access to the parts of GLFW accessible from any thread.
@@ -315,6 +430,8 @@ impl Glfw {
     /// "windowed fullscreen" or "borderless fullscreen" window. For more information, see the
     /// [GLFW Reference][window-full].
     /// 
+    /// [`WindowBuilder`] offers the same with named settings, and more of them.
+    /// 
     /// # Deviations from GLFW
     /// 
     /// Instead of setting window hints through a separate (stateful) function, this function takes
@@ -333,6 +450,7 @@ impl Glfw {
     /// [`make_context_current()`]: struct.Window.html#method.make_context_current
     /// [`WindowHints`]: struct.WindowHints.html
     /// [`init()`]: fn.init.html
+    /// [`WindowBuilder`]: struct.WindowBuilder.html
     pub fn create_window(
         &self,
         window_hints: &WindowHints,
@@ -416,16 +534,75 @@ impl Glfw {
             monitor.map_or(ptr::null_mut(), |m| m.get_ptr()),
             share.map_or(ptr::null_mut(), |w| w.ptr)
         )};
-        get_error().map(|_| {
+        get_error_in("Glfw::create_window").map(|_| {
             assert!(!ptr.is_null());
-            Window::init(Some(self), ptr)
+            attach_window_data(ptr);
+            let window = Window::init(Some(self), ptr);
+            self.windows.borrow_mut().insert(window.id(), ptr);
+            window
         })
     }
 
-    /// [GLFW Reference][glfw]
+    /// Returns whether the window with the given ID was created by this instance and has not
+    /// been destroyed yet.
+    pub fn window_exists(&self, id: WindowId) -> bool {
+        self.windows.borrow().contains_key(&id)
+    }
+
+    /// Looks up a window by ID and lends it to `f`.
     /// 
-    /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga37bd57223967b4211d60ca1a0bf3c832
-    pub fn poll_events(&self, handler: &mut FnMut(Event)) -> Result<()> {
+    /// This allows routing an [`OwnedEvent`] back to its window without keeping a map from
+    /// [`WindowId`]s to the `Window`s you own. Returns `None` if the window has been destroyed.
+    /// 
+    /// The `Window` passed to `f` does not own the underlying window. If the owning `Window` is
+    /// dropped while `f` runs, destruction is delayed until `f` returns.
+    /// 
+    /// [`OwnedEvent`]: enum.OwnedEvent.html
+    /// [`WindowId`]: struct.WindowId.html
+    pub fn with_window<R, F: FnOnce(&Window) -> R>(&self, id: WindowId, f: F) -> Option<R> {
+        struct Lend<'a>(&'a Glfw);
+        impl<'a> Drop for Lend<'a> {
+            fn drop(&mut self) {
+                let glfw = self.0;
+                glfw.lent_windows.set(glfw.lent_windows.get() - 1);
+                if !glfw.destruction_deferred() {
+                    glfw.process_reentrance_avoidance();
+                }
+            }
+        }
+
+        let ptr = match self.windows.borrow().get(&id) {
+            Some(&ptr) => ptr,
+            None => return None
+        };
+        self.lent_windows.set(self.lent_windows.get() + 1);
+        let _lend = Lend(self);
+        let window = Window::init(None, ptr);
+        Some(f(&window))
+    }
+
+    /// Runs `f` with `handler` installed as the event processor, followed by the joystick and
+    /// gamepad events if `poll_joysticks` is set, then performs the window and cursor destruction
+    /// deferred in the meantime. Errors are reported as coming from `function`.
+    fn dispatch_events<F: FnOnce()>(
+        &self, function: &'static str, handler: &mut dyn FnMut(Event), poll_joysticks: bool, f: F
+    ) -> Result<()> {
+        // Clears the event processor even if the handler panics while called from Rust code
+        struct Processing;
+        impl Drop for Processing {
+            fn drop(&mut self) {
+                unsafe { EVENT_PROCESSOR = None };
+            }
+        }
+
+        // Performs the deferred destruction after the event processor is cleared, even on panic
+        struct Deferred<'a>(&'a Glfw);
+        impl<'a> Drop for Deferred<'a> {
+            fn drop(&mut self) {
+                self.0.process_reentrance_avoidance();
+            }
+        }
+
         unsafe {
             if EVENT_PROCESSOR.is_some() {
                 panic!("Call to non-rentrant function during event processing.");
@@ -436,71 +613,141 @@ impl Glfw {
             // any problems.
             let handler_ptr = mem::transmute(handler);
             EVENT_PROCESSOR = Some(handler_ptr);
-            ffi::glfwPollEvents();
-            EVENT_PROCESSOR = None;
         }
-        let e = get_error();
-        self.process_reentrance_avoidance();
+        let deferred = Deferred(self);
+        let processing = Processing;
+        f();
+        if poll_joysticks && !diagnostics::panic_pending() {
+            // Collected first, and the source taken out while polling, as the handler may enable or
+            // disable gamepad events, both when called for the events and for disconnections found
+            // while polling
+            let mut joystick_events = vec![];
+            let source = self.joystick_events.borrow_mut().take();
+            if let Some(mut source) = source {
+                source.poll(self, &mut joystick_events);
+                let mut current = self.joystick_events.borrow_mut();
+                if current.is_none() {
+                    *current = Some(source);
+                }
+            }
+            for event in joystick_events {
+                events::emit(event);
+            }
+        }
+        drop(processing);
+        let e = get_error_in(function);
+        drop(deferred);
+        diagnostics::resume_pending_panic();
         e
     }
 
+    /// Sets what happens when an event handler panics while called from GLFW, which is to abort
+    /// by default. Handlers called from Rust code, like the joystick and gamepad events, always
+    /// unwind normally.
+    pub fn set_panic_behavior(&self, behavior: PanicBehavior) {
+        diagnostics::set_panic_behavior(behavior);
+    }
+
+    /// [GLFW Reference][glfw]
+    /// 
+    /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga37bd57223967b4211d60ca1a0bf3c832
+    pub fn poll_events(&self, handler: &mut FnMut(Event)) -> Result<()> {
+        self.dispatch_events("Glfw::poll_events", handler, true, || unsafe {
+            ffi::glfwPollEvents()
+        })
+    }
+
+    /// Processes pending events like [`poll_events()`], appending them to `events` as
+    /// [`OwnedEvent`]s instead of passing them to a handler.
+    /// 
+    /// [`poll_events()`]: #method.poll_events
+    /// [`OwnedEvent`]: enum.OwnedEvent.html
+    pub fn poll_events_into(&self, events: &mut Vec<OwnedEvent>) -> Result<()> {
+        self.poll_events(&mut |e| events.push(OwnedEvent::from(&e)))
+    }
+
+    /// Processes pending events into an internal queue and returns an iterator draining it.
+    /// 
+    /// Events left in the queue when the iterator is dropped early are kept and yielded first by
+    /// the next call. If polling fails, the events received before the failure are kept as well.
+    pub fn events(&self) -> Result<Events<'_>> {
+        let queue = &self.event_queue;
+        self.poll_events(&mut |e| queue.borrow_mut().push_back(OwnedEvent::from(&e)))?;
+        Ok(Events { queue })
+    }
+
     /// [GLFW Reference][glfw]
     /// 
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga554e37d781f0a997656c26b2c56c835e
     pub fn wait_events(&self, handler: &mut FnMut(Event)) -> Result<()> {
-        unsafe {
-            if EVENT_PROCESSOR.is_some() {
-                panic!("Call to non-rentrant function during event processing.");
-            }
-            // We need to extend the lifetime of the &mut FnMut(Event) to &mut (FnMut(Event) +
-            // 'static). That's what this transmute does. Yikes. We clear the pointer
-            // (EVENT_PROCESSOR = None) after we're done, so the lifetime extension shouldn't cause
-            // any problems.
-            let handler_ptr = mem::transmute(handler);
-            EVENT_PROCESSOR = Some(handler_ptr);
-            ffi::glfwWaitEvents();
-            EVENT_PROCESSOR = None;
-        }
-        let e = get_error();
-        self.process_reentrance_avoidance();
-        e
+        self.dispatch_events("Glfw::wait_events", handler, true, || unsafe {
+            ffi::glfwWaitEvents()
+        })
     }
 
     /// [GLFW Reference][glfw]
     /// 
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga605a178db92f1a7f1a925563ef3ea2cf
     pub fn wait_events_timeout(&self, timeout: f64, handler: &mut FnMut(Event)) -> Result<()> {
-        unsafe {
-            if EVENT_PROCESSOR.is_some() {
-                panic!("Call to non-rentrant function during event processing.");
+        self.dispatch_events("Glfw::wait_events_timeout", handler, true, || unsafe {
+            ffi::glfwWaitEventsTimeout(timeout)
+        })
+    }
+
+    /// Delivers a synthetic event to `handler` as if it was received by an event processing call.
+    /// 
+    /// The event goes through the same callbacks GLFW invokes, so the handler sees exactly what it
+    /// would for a real event, and windows destroyed by the handler are destroyed afterwards like
+    /// with [`poll_events()`]. GLFW itself is not involved, so its own state is left untouched:
+    /// for example, injecting a key press does not affect [`get_key()`], and injecting a joystick
+    /// disconnection keeps the user data of the joystick.
+    /// 
+    /// Returns an `InvalidValue` error for events about windows that do not exist, for monitor
+    /// events, which cannot be synthesized, and for file drops of paths containing NUL bytes.
+    /// 
+    /// Only available with the `event-injection` feature, intended for testing input handling.
+    /// 
+    /// [`poll_events()`]: #method.poll_events
+    /// [`get_key()`]: struct.Window.html#method.get_key
+    #[cfg(feature = "event-injection")]
+    pub fn inject_event(&self, event: &OwnedEvent, handler: &mut dyn FnMut(Event)) -> Result<()> {
+        let window = match *event {
+            OwnedEvent::MonitorConnected(_) | OwnedEvent::MonitorDisconnected(_) =>
+                    return Err(Error {
+                        kind: ErrorKind::InvalidValue,
+                        description: "Monitor events cannot be injected".to_owned(),
+                        function: "Glfw::inject_event"
+                    }),
+            // GLFW passes dropped paths as C strings
+            OwnedEvent::FileDrop(_, ref paths)
+                    if paths.iter().any(|p| p.to_string_lossy().contains('\0')) =>
+                    return Err(Error {
+                        kind: ErrorKind::InvalidValue,
+                        description: "Dropped paths cannot contain NUL bytes".to_owned(),
+                        function: "Glfw::inject_event"
+                    }),
+            _ => match event.window_id() {
+                Some(id) => match self.windows.borrow().get(&id) {
+                    Some(&ptr) => ptr,
+                    None => return Err(Error {
+                        kind: ErrorKind::InvalidValue,
+                        description: format!("No window with ID {:?}", id),
+                        function: "Glfw::inject_event"
+                    })
+                },
+                None => ptr::null_mut()
             }
-            // We need to extend the lifetime of the &mut FnMut(Event) to &mut (FnMut(Event) +
-            // 'static). That's what this transmute does. Yikes. We clear the pointer
-            // (EVENT_PROCESSOR = None) after we're done, so the lifetime extension shouldn't cause
-            // any problems.
-            let handler_ptr = mem::transmute(handler);
-            EVENT_PROCESSOR = Some(handler_ptr);
-            ffi::glfwWaitEventsTimeout(timeout);
-            EVENT_PROCESSOR = None;
-        }
-        let e = get_error();
-        self.process_reentrance_avoidance();
-        e
+        };
+        self.dispatch_events("Glfw::inject_event", handler, false, || {
+            events::inject(event, window)
+        })
     }
 
     /// [GLFW Reference][glfw]
     /// 
     /// [glfw]: http://www.glfw.org/docs/3.3/group__monitor.html#ga3fba51c8bd36491d4712aa5bd074a537
     pub fn get_monitors(&self) -> Vec<Monitor> {
-        let raw = unsafe {
-            let mut count = 0;
-            let ptr = ffi::glfwGetMonitors(&mut count);
-            if ptr.is_null() {
-                return vec![];
-            }
-            std::slice::from_raw_parts(ptr, count as usize)
-        };
-        raw.iter().map(|p| Monitor::create_from(*p)).collect()
+        monitor::connected_monitors()
     }
 
     /// [GLFW Reference][glfw]
@@ -529,17 +776,27 @@ impl Glfw {
             Key::Named(kc) => ffi::glfwGetKeyName(kc as i32, 0),
             Key::Unnamed(sc) => ffi::glfwGetKeyName(ffi::GLFW_KEY_UNKNOWN, sc)
         } };
-        get_error().map(|_| unsafe {
+        get_error_in("Glfw::get_key_name").map(|_| unsafe {
             ptr.as_ref().map(|p| CStr::from_ptr(p).to_string_lossy().into_owned())
         })
     }
 
+    /// Returns the layout-specific name of the key with the given platform-specific scancode,
+    /// such as the `scancode` of a [`KeyDown`] event.
+    /// 
+    /// Equivalent to `get_key_name(Key::Unnamed(scancode))`.
+    /// 
+    /// [`KeyDown`]: enum.Event.html#variant.KeyDown
+    pub fn get_scancode_name(&self, scancode: i32) -> Result<Option<String>> {
+        self.get_key_name(Key::Unnamed(scancode))
+    }
+
     /// [GLFW Reference][glfw]
     /// 
     /// [glfw]: http://www.glfw.org/docs/3.3/group__input.html#ga67ddd1b7dcbbaff03e4a76c0ea67103a
     pub fn get_key_scancode(&self, keycode: KeyCode) -> Result<i32> {
         let sc = unsafe { ffi::glfwGetKeyScancode(keycode as i32) };
-        get_error().map(|_| sc)
+        get_error_in("Glfw::get_key_scancode").map(|_| sc)
     }
 
     /// [GLFW Reference][glfw]
@@ -548,7 +805,7 @@ impl Glfw {
     pub fn create_cursor(&self, image: &Image, xhot: i32, yhot: i32) -> Result<Cursor> {
         let image = image.as_glfw_image();
         let ptr = unsafe { ffi::glfwCreateCursor(&image, xhot, yhot) };
-        get_error().map(|_| {
+        get_error_in("Glfw::create_cursor").map(|_| {
             assert!(!ptr.is_null());
             Cursor {
                 ptr: ptr,
@@ -562,7 +819,7 @@ impl Glfw {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__input.html#gaa65f416d03ebbbb5b8db71a489fcb894
     pub fn create_standard_cursor(&self, shape: StandardCursorShape) -> Result<Cursor> {
         let ptr = unsafe { ffi::glfwCreateStandardCursor(shape as i32) };
-        get_error().map(|_| {
+        get_error_in("Glfw::create_standard_cursor").map(|_| {
             assert!(!ptr.is_null());
             Cursor {
                 ptr: ptr,
@@ -576,7 +833,54 @@ impl Glfw {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__input.html#gaed0966cee139d815317f9ffcba64c9f1
     pub fn is_joystick_present(&self, joystick: Joystick) -> Result<bool> {
         let v = unsafe { ffi::glfwJoystickPresent(joystick as i32) };
-        get_error().map(|_| cint_to_bool(v))
+        get_error_in("Glfw::is_joystick_present").map(|_| cint_to_bool(v))
+    }
+
+    /// Returns the joysticks that are present, checking each one as the iterator reaches it. An
+    /// error checking a joystick is returned in its place, and the following ones are still
+    /// checked.
+    pub fn present_joysticks<'a>(&'a self) -> impl Iterator<Item = Result<Joystick>> + 'a {
+        (ffi::GLFW_JOYSTICK_1..=ffi::GLFW_JOYSTICK_LAST).filter_map(|jid| {
+            let present = unsafe { ffi::glfwJoystickPresent(jid) };
+            match get_error_in("Glfw::present_joysticks") {
+                Ok(()) if cint_to_bool(present) => Some(Ok(Joystick::from_i32(jid).unwrap())),
+                Ok(()) => None,
+                Err(e) => Some(Err(e))
+            }
+        })
+    }
+
+    /// Fills `snapshot` with the axes, buttons, hats, name and GUID of a joystick, reusing its
+    /// memory, instead of allocating for each of them.
+    /// 
+    /// Returns `false` and leaves `snapshot` empty if the joystick is not present, including when
+    /// it is disconnected partway through, so the snapshot is never a mix of two devices.
+    pub fn capture_joystick(&self, joystick: Joystick, snapshot: &mut JoystickSnapshot)
+            -> Result<bool> {
+        snapshot.clear();
+        let jid = joystick as i32;
+        let captured = unsafe {
+            let (mut axis_count, mut button_count, mut hat_count) = (0, 0, 0);
+            let axes = ffi::glfwGetJoystickAxes(jid, &mut axis_count);
+            let buttons = ffi::glfwGetJoystickButtons(jid, &mut button_count);
+            let hats = ffi::glfwGetJoystickHats(jid, &mut hat_count);
+            let name = ffi::glfwGetJoystickName(jid);
+            let guid = ffi::glfwGetJoystickGUID(jid);
+            if axes.is_null() || buttons.is_null() || hats.is_null() || name.is_null() ||
+                    guid.is_null() {
+                false
+            } else {
+                snapshot.axes.extend_from_slice(slice::from_raw_parts(axes, axis_count as usize));
+                snapshot.buttons.extend(slice::from_raw_parts(buttons, button_count as usize)
+                        .iter().map(|c| cuchar_to_bool(*c)));
+                snapshot.hats.extend(slice::from_raw_parts(hats, hat_count as usize)
+                        .iter().map(|c| JoystickHatState::from_bits_truncate(*c)));
+                snapshot.name.push_str(&CStr::from_ptr(name).to_string_lossy());
+                snapshot.guid.push_str(&CStr::from_ptr(guid).to_string_lossy());
+                true
+            }
+        };
+        get_error_in("Glfw::capture_joystick").map(|_| captured)
     }
 
     /// [GLFW Reference][glfw]
@@ -585,7 +889,7 @@ impl Glfw {
     pub fn get_joystick_axes(&self, joystick: Joystick) -> Result<Option<Vec<f32>>> {
         let mut count = 0;
         let ptr = unsafe { ffi::glfwGetJoystickAxes(joystick as i32, &mut count) };
-        get_error().map(|_| unsafe {
+        get_error_in("Glfw::get_joystick_axes").map(|_| unsafe {
             ptr.as_ref().map(|p| slice::from_raw_parts(p, count as usize).to_owned())
         })
     }
@@ -596,7 +900,7 @@ impl Glfw {
     pub fn get_joystick_buttons(&self, joystick: Joystick) -> Result<Option<Vec<bool>>> {
         let mut count = 0;
         let ptr = unsafe { ffi::glfwGetJoystickButtons(joystick as i32, &mut count) };
-        get_error().map(|_| unsafe {
+        get_error_in("Glfw::get_joystick_buttons").map(|_| unsafe {
             ptr.as_ref().map(|p| slice::from_raw_parts(p, count as usize)
                     .iter().map(|c| cuchar_to_bool(*c)).collect())
         })
@@ -608,7 +912,7 @@ impl Glfw {
     pub fn get_joystick_hats(&self, joystick: Joystick) -> Result<Option<Vec<JoystickHatState>>> {
         let mut count = 0;
         let ptr = unsafe { ffi::glfwGetJoystickHats(joystick as i32, &mut count) };
-        get_error().map(|_| unsafe {
+        get_error_in("Glfw::get_joystick_hats").map(|_| unsafe {
             ptr.as_ref().map(|p| slice::from_raw_parts(p, count as usize)
                     .iter().map(|c| JoystickHatState::from_bits(*c).unwrap()).collect())
         })
@@ -619,7 +923,7 @@ impl Glfw {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__input.html#gafbe3e51f670320908cfe4e20d3e5559e
     pub fn get_joystick_name(&self, joystick: Joystick) -> Result<Option<String>> {
         let ptr = unsafe { ffi::glfwGetJoystickName(joystick as i32) };
-        get_error().map(|_| unsafe {
+        get_error_in("Glfw::get_joystick_name").map(|_| unsafe {
             ptr.as_ref().map(|p| CStr::from_ptr(p).to_string_lossy().into_owned())
         })
     }
@@ -629,11 +933,72 @@ impl Glfw {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__input.html#gae168c2c0b8cf2a1cb67c6b3c00bdd543
     pub fn get_joystick_guid(&self, joystick: Joystick) -> Result<Option<String>> {
         let ptr = unsafe { ffi::glfwGetJoystickGUID(joystick as i32) };
-        get_error().map(|_| unsafe {
+        get_error_in("Glfw::get_joystick_guid").map(|_| unsafe {
             ptr.as_ref().map(|p| CStr::from_ptr(p).to_string_lossy().into_owned())
         })
     }
 
+    /// Attaches a value to a connected joystick, replacing any previous value.
+    /// 
+    /// The value is released when the joystick is disconnected, before the
+    /// [`JoystickDisconnected`] event is delivered, or when GLFW is terminated. Clones of the `Rc`
+    /// returned by [`joystick_user_data()`] keep it alive past that point.
+    /// 
+    /// Returns `false` and drops `data` if the joystick is not present.
+    /// 
+    /// [`JoystickDisconnected`]: enum.Event.html#variant.JoystickDisconnected
+    /// [`joystick_user_data()`]: #method.joystick_user_data
+    pub fn set_joystick_user_data<T: 'static>(&self, joystick: Joystick, data: T) -> bool {
+        if !cint_to_bool(unsafe { ffi::glfwJoystickPresent(joystick as i32) }) {
+            return false;
+        }
+        take_joystick_user_data(joystick as i32);
+        let data: Box<Rc<dyn Any>> = Box::new(Rc::new(data));
+        unsafe { ffi::glfwSetJoystickUserPointer(joystick as i32, Box::into_raw(data) as *mut _) };
+        true
+    }
+
+    /// Removes the value attached with [`set_joystick_user_data()`].
+    /// 
+    /// [`set_joystick_user_data()`]: #method.set_joystick_user_data
+    pub fn clear_joystick_user_data(&self, joystick: Joystick) {
+        take_joystick_user_data(joystick as i32);
+    }
+
+    /// Retrieves the value attached with [`set_joystick_user_data()`], if it is of type `T`.
+    /// 
+    /// [`set_joystick_user_data()`]: #method.set_joystick_user_data
+    pub fn joystick_user_data<T: 'static>(&self, joystick: Joystick) -> Option<Rc<T>> {
+        unsafe {
+            let data = ffi::glfwGetJoystickUserPointer(joystick as i32) as *const Rc<dyn Any>;
+            data.as_ref().and_then(|d| d.clone().downcast().ok())
+        }
+    }
+
+    /// Makes the event processing functions report changes in the state of joysticks and gamepads
+    /// as events, like [`Event::GamepadButtonDown`].
+    /// 
+    /// GLFW only reports joystick connection and disconnection, so the state of every joystick is
+    /// polled after processing the other events and compared with the previous call. Joysticks with
+    /// a gamepad mapping produce gamepad events, and other joysticks raw button, axis and hat events.
+    /// Axis events are only produced once an axis has moved more than `axis_epsilon` from the value
+    /// last reported, to filter out noise.
+    /// 
+    /// The current state of the joysticks is taken as the starting point, so nothing is reported
+    /// for buttons already held. Calling this again resets the starting point.
+    /// 
+    /// [`Event::GamepadButtonDown`]: enum.Event.html#variant.GamepadButtonDown
+    pub fn enable_gamepad_events(&self, axis_epsilon: f32) {
+        let mut source = joystick_events::JoystickEvents::new(axis_epsilon);
+        source.poll(self, &mut vec![]);
+        *self.joystick_events.borrow_mut() = Some(source);
+    }
+
+    /// Stops reporting changes in the state of joysticks and gamepads as events.
+    pub fn disable_gamepad_events(&self) {
+        *self.joystick_events.borrow_mut() = None;
+    }
+
     /// [GLFW Reference][glfw]
     /// 
     /// [glfw]: http://www.glfw.org/docs/3.3/group__input.html#gad0f676860f329d80f7e47e9f06a96f00
@@ -647,7 +1012,7 @@ impl Glfw {
     pub fn update_gamepad_mappings(&self, mapping: &str) -> Result<()> {
         let cstr = CString::new(mapping).unwrap();
         unsafe { ffi::glfwUpdateGamepadMappings(cstr.as_ptr()) };
-        get_error()
+        get_error_in("Glfw::update_gamepad_mappings")
     }
 
     /// [GLFW Reference][glfw]
@@ -678,7 +1043,7 @@ impl Glfw {
     pub fn set_clipboard_string(&self, string: &str) -> Result<()> {
         let cstr = CString::new(string).unwrap();
         unsafe { ffi::glfwSetClipboardString(ptr::null_mut(), cstr.as_ptr()) };
-        get_error()
+        get_error_in("Glfw::set_clipboard_string")
     }
 
     /// [GLFW Reference][glfw]
@@ -686,7 +1051,7 @@ impl Glfw {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__input.html#ga5aba1d704d9ab539282b1fbe9f18bb94
     pub fn get_clipboard_string(&self) -> Result<String> {
         let ptr = unsafe { ffi::glfwGetClipboardString(ptr::null_mut()) };
-        get_error().map(|_| unsafe {
+        get_error_in("Glfw::get_clipboard_string").map(|_| unsafe {
             assert!(!ptr.is_null());
             CStr::from_ptr(ptr).to_string_lossy().into_owned()
         })
@@ -715,7 +1080,7 @@ impl SharedGlfw {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#gab5997a25187e9fd5c6f2ecbbc8dfd7e9
     pub fn post_empty_event(&self) -> Result<()> {
         unsafe { ffi::glfwPostEmptyEvent() };
-        get_error()
+        get_error_in("SharedGlfw::post_empty_event")
     }
 
     /// [GLFW Reference][glfw]
@@ -723,7 +1088,7 @@ impl SharedGlfw {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__context.html#ga1c04dc242268f827290fe40aa1c91157
     pub unsafe fn clear_current_context(&self) -> Result<()> {
         ffi::glfwMakeContextCurrent(ptr::null_mut());
-        get_error()
+        get_error_in("SharedGlfw::clear_current_context")
     }
 
     /// [GLFW Reference][glfw]
@@ -731,7 +1096,7 @@ impl SharedGlfw {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__context.html#ga6d4e0cdf151b5e579bd67f13202994ed
     pub unsafe fn swap_interval(&self, interval: i32) -> Result<()> {
         ffi::glfwSwapInterval(interval);
-        get_error()
+        get_error_in("SharedGlfw::swap_interval")
     }
 
     /// [GLFW Reference][glfw]
@@ -740,7 +1105,7 @@ impl SharedGlfw {
     pub unsafe fn extension_supported(&self, extension: &str) -> Result<bool> {
         let cstr = CString::new(extension).unwrap();
         let supported = ffi::glfwExtensionSupported(cstr.as_ptr());
-        get_error().map(|_| cint_to_bool(supported))
+        get_error_in("SharedGlfw::extension_supported").map(|_| cint_to_bool(supported))
     }
 
     /// [GLFW Reference][glfw]
@@ -749,7 +1114,7 @@ impl SharedGlfw {
     pub unsafe fn get_proc_address(&self, proc_name: &str) -> Result<GlProc> {
         let cstr = CString::new(proc_name).unwrap();
         let proc = ffi::glfwGetProcAddress(cstr.as_ptr());
-        get_error().map(|_| proc)
+        get_error_in("SharedGlfw::get_proc_address").map(|_| proc)
     }
 
     /// [GLFW Reference][glfw]
@@ -764,7 +1129,7 @@ impl SharedGlfw {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__input.html#gaf59589ef6e8b8c8b5ad184b25afd4dc0
     pub fn set_time(&self, time: f64) -> Result<()> {
         unsafe { ffi::glfwSetTime(time) };
-        get_error()
+        get_error_in("SharedGlfw::set_time")
     }
 
     /// [GLFW Reference][glfw]
@@ -782,12 +1147,62 @@ impl SharedGlfw {
     }
 }
 
+/// Iterator over buffered events, returned by [`Glfw::events()`].
+/// 
+/// [`Glfw::events()`]: struct.Glfw.html#method.events
+#[derive(Debug)]
+pub struct Events<'a> {
+    queue: &'a RefCell<VecDeque<OwnedEvent>>
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = OwnedEvent;
+
+    fn next(&mut self) -> Option<OwnedEvent> {
+        self.queue.borrow_mut().pop_front()
+    }
+}
+
+/// Takes the user data out of the joystick user pointer, leaving it null.
+/// 
+/// Like monitors, the user pointer holds a `Box<Rc<dyn Any>>`, as joysticks can be disconnected
+/// during any event processing call.
+pub(crate) fn take_joystick_user_data(jid: c_int) -> Option<Rc<dyn Any>> {
+    unsafe {
+        let data = ffi::glfwGetJoystickUserPointer(jid) as *mut Rc<dyn Any>;
+        if data.is_null() {
+            None
+        } else {
+            ffi::glfwSetJoystickUserPointer(jid, ptr::null_mut());
+            Some(*Box::from_raw(data))
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum NotifierError {
     Terminated,
     Glfw(Error)
 }
 
+impl fmt::Display for NotifierError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NotifierError::Terminated => f.write_str("GLFW has been terminated"),
+            NotifierError::Glfw(ref e) => fmt::Display::fmt(e, f)
+        }
+    }
+}
+
+impl std::error::Error for NotifierError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            NotifierError::Terminated => None,
+            NotifierError::Glfw(ref e) => Some(e)
+        }
+    }
+}
+
 /// Allows you to wake up the event loop through an object with a static lifetime.
 /// 
 /// Exists because of the way WebRender's `RenderNotifier` works.
@@ -806,7 +1221,7 @@ impl GlfwNotifier {
                     unsafe {
                         ffi::glfwPostEmptyEvent();
                     }
-                    return get_error().map_err(|e| NotifierError::Glfw(e));
+                    return get_error_in("GlfwNotifier::post_empty_event").map_err(NotifierError::Glfw);
                 }
             }
         }
@@ -832,7 +1247,7 @@ impl SharedGlfw {
     pub unsafe fn set_x11_selection_string(&self, string: &str) -> Result<()> {
         let string = CString::new(string).unwrap();
         ffi::x11::glfwSetX11SelectionString(string.as_ptr());
-        get_error()
+        get_error_in("SharedGlfw::set_x11_selection_string")
     }
 
     /// [GLFW Reference][glfw]
@@ -840,7 +1255,7 @@ impl SharedGlfw {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__native.html#ga72f23e3980b83788c70aa854eca31430
     pub unsafe fn get_x11_selection_string(&self) -> Result<String> {
         let ptr = ffi::x11::glfwGetX11SelectionString();
-        get_error().map(|_| {
+        get_error_in("SharedGlfw::get_x11_selection_string").map(|_| {
             assert!(!ptr.is_null());
             CStr::from_ptr(ptr).to_string_lossy().into_owned()
         })
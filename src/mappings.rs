@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use Glfw;
+use Joystick;
+use Result;
+
+/// The keys GLFW reads from a mapping, which must have an input as their value.
+const INPUT_KEYS: [&str; 21] = [
+    "a", "b", "x", "y", "back", "start", "guide", "leftshoulder", "rightshoulder", "leftstick",
+    "rightstick", "dpup", "dpright", "dpdown", "dpleft", "lefttrigger", "righttrigger", "leftx",
+    "lefty", "rightx", "righty"
+];
+
+/// Returns the platform name used in mappings for the platform the program was compiled for.
+fn native_platform() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "Windows"
+    } else if cfg!(target_os = "macos") {
+        "Mac OS X"
+    } else if cfg!(target_os = "android") {
+        "Android"
+    } else if cfg!(target_os = "ios") {
+        "iOS"
+    } else {
+        "Linux"
+    }
+}
+
+/// Returns whether `value` is a joystick input in the SDL format, like `b0`, `-a2~` or `h0.4`.
+fn is_valid_input(value: &str) -> bool {
+    let mut chars = value.trim_start_matches(['+', '-']).chars();
+    let kind = chars.next();
+    let index = chars.as_str();
+    match kind {
+        Some('a') => index.trim_end_matches('~').parse::<u8>().is_ok(),
+        Some('b') => index.parse::<u8>().is_ok(),
+        Some('h') => {
+            let mut parts = index.splitn(2, '.');
+            parts.next().unwrap().parse::<u8>().is_ok() &&
+                    parts.next().and_then(|bit| bit.parse::<u8>().ok()).is_some()
+        }
+        _ => false
+    }
+}
+
+/// A gamepad mapping in the SDL `gamecontrollerdb.txt` format, like
+/// `03000000de280000ff11000001000000,Steam Virtual Gamepad,a:b0,b:b1,...,platform:Linux,`.
+///
+/// `Display` writes the mapping as it was parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GamepadMapping {
+    guid: String,
+    name: String,
+    platform: Option<String>,
+    text: String
+}
+
+impl GamepadMapping {
+    /// Returns the GUID of the joysticks the mapping is for, in lowercase.
+    pub fn guid(&self) -> &str {
+        &self.guid
+    }
+
+    /// Returns the name of the gamepad, as returned by [`Glfw::get_gamepad_name()`].
+    ///
+    /// [`Glfw::get_gamepad_name()`]: struct.Glfw.html#method.get_gamepad_name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the platform the mapping is limited to, if any.
+    pub fn platform(&self) -> Option<&str> {
+        self.platform.as_deref()
+    }
+}
+
+impl fmt::Display for GamepadMapping {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl FromStr for GamepadMapping {
+    type Err = ParseMappingError;
+
+    fn from_str(s: &str) -> ::std::result::Result<GamepadMapping, ParseMappingError> {
+        let text = s.trim();
+        let mut fields = text.split(',');
+        let guid = fields.next().unwrap().trim();
+        if guid.len() != 32 || !guid.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseMappingError::new(format!("invalid GUID `{}`", guid)));
+        }
+        let name = match fields.next() {
+            Some(name) if !name.trim().is_empty() => name.trim(),
+            _ => return Err(ParseMappingError::new("missing gamepad name"))
+        };
+
+        let mut platform = None;
+        for field in fields.map(str::trim).filter(|f| !f.is_empty()) {
+            let mut parts = field.splitn(2, ':');
+            let (key, value) = match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) => (key.trim(), value.trim()),
+                _ => return Err(ParseMappingError::new(format!("expected `key:value`, found `{}`",
+                        field)))
+            };
+            if key == "platform" {
+                platform = Some(value.to_owned());
+            } else if INPUT_KEYS.contains(&key) && !is_valid_input(value) {
+                return Err(ParseMappingError::new(format!("invalid input `{}` for `{}`", value,
+                        key)));
+            }
+        }
+        Ok(GamepadMapping {
+            guid: guid.to_lowercase(),
+            name: name.to_owned(),
+            platform,
+            text: text.to_owned()
+        })
+    }
+}
+
+/// Error returned when parsing a gamepad mapping fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseMappingError {
+    line: Option<usize>,
+    message: String
+}
+
+impl ParseMappingError {
+    fn new<T: fmt::Display>(message: T) -> ParseMappingError {
+        ParseMappingError {
+            line: None,
+            message: message.to_string()
+        }
+    }
+
+    /// Returns the line the error is on, when loading several mappings at once.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+}
+
+impl fmt::Display for ParseMappingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => f.write_str(&self.message)
+        }
+    }
+}
+
+impl ::std::error::Error for ParseMappingError {}
+
+/// A set of gamepad mappings, loaded from `gamecontrollerdb.txt` files and overridden per GUID,
+/// to be handed to GLFW with [`apply()`].
+///
+/// Only the mappings for the platform the program was compiled for are kept, as GLFW ignores the
+/// others. Mappings loaded later replace earlier ones with the same GUID, and overrides take
+/// precedence over all loaded mappings.
+///
+/// ```no_run
+/// # let glfw = glfw_wrapper::init(Default::default()).unwrap();
+/// let mut mappings = glfw_wrapper::GamepadMappings::new();
+/// for error in mappings.load_file("gamecontrollerdb.txt").unwrap() {
+///     eprintln!("skipped invalid mapping: {}", error);
+/// }
+/// mappings.apply(&glfw).unwrap();
+/// for (joystick, mapping) in mappings.assignments(&glfw).unwrap() {
+///     println!("{:?}: {}", joystick, mapping.map_or("no mapping", |m| m.name()));
+/// }
+/// ```
+///
+/// [`apply()`]: #method.apply
+#[derive(Clone, Debug, Default)]
+pub struct GamepadMappings {
+    loaded: BTreeMap<String, GamepadMapping>,
+    overrides: BTreeMap<String, GamepadMapping>
+}
+
+impl GamepadMappings {
+    pub fn new() -> Self {
+        GamepadMappings::default()
+    }
+
+    /// Adds the mappings in `text`, one per line, skipping empty lines and `#` comments.
+    ///
+    /// Invalid lines are skipped, and returned as errors with their line number.
+    pub fn load_str(&mut self, text: &str) -> Vec<ParseMappingError> {
+        let mut errors = vec![];
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.parse::<GamepadMapping>() {
+                Ok(mapping) => {
+                    if mapping.platform().unwrap_or(native_platform()) == native_platform() {
+                        self.loaded.insert(mapping.guid.clone(), mapping);
+                    }
+                }
+                Err(mut e) => {
+                    e.line = Some(i + 1);
+                    errors.push(e);
+                }
+            }
+        }
+        errors
+    }
+
+    /// Adds the mappings in a file, as with [`load_str()`].
+    ///
+    /// [`load_str()`]: #method.load_str
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<Vec<ParseMappingError>> {
+        Ok(self.load_str(&fs::read_to_string(path)?))
+    }
+
+    /// Sets the mapping to use for its GUID regardless of the loaded ones, returning the previous
+    /// override.
+    pub fn set_override(&mut self, mapping: GamepadMapping) -> Option<GamepadMapping> {
+        self.overrides.insert(mapping.guid.clone(), mapping)
+    }
+
+    /// Removes the override for a GUID, returning it.
+    pub fn remove_override(&mut self, guid: &str) -> Option<GamepadMapping> {
+        self.overrides.remove(&guid.to_lowercase())
+    }
+
+    /// Returns the mapping used for a GUID.
+    pub fn get(&self, guid: &str) -> Option<&GamepadMapping> {
+        let guid = guid.to_lowercase();
+        self.overrides.get(&guid).or_else(|| self.loaded.get(&guid))
+    }
+
+    /// Returns the mappings used, ordered by GUID.
+    pub fn iter(&self) -> impl Iterator<Item = &GamepadMapping> {
+        let mut mappings: BTreeMap<&str, &GamepadMapping> =
+                self.loaded.iter().map(|(guid, m)| (guid.as_str(), m)).collect();
+        mappings.extend(self.overrides.iter().map(|(guid, m)| (guid.as_str(), m)));
+        mappings.into_values()
+    }
+
+    /// Returns the number of mappings used.
+    pub fn len(&self) -> usize {
+        self.loaded.keys().filter(|guid| !self.overrides.contains_key(*guid)).count() +
+                self.overrides.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.loaded.is_empty() && self.overrides.is_empty()
+    }
+
+    /// Hands the mappings to GLFW with [`Glfw::update_gamepad_mappings()`].
+    ///
+    /// GLFW keeps mappings until it is terminated, so a removed override stays in effect unless a
+    /// loaded mapping replaces it.
+    ///
+    /// [`Glfw::update_gamepad_mappings()`]: struct.Glfw.html#method.update_gamepad_mappings
+    pub fn apply(&self, glfw: &Glfw) -> Result<()> {
+        let mut text = String::new();
+        for mapping in self.iter() {
+            text.push_str(&mapping.text);
+            text.push('\n');
+        }
+        glfw.update_gamepad_mappings(&text)
+    }
+
+    /// Returns the mapping used for a joystick, found by its GUID, or `None` if the joystick is
+    /// not present or has no mapping here.
+    pub fn joystick_mapping(&self, glfw: &Glfw, joystick: Joystick)
+            -> Result<Option<&GamepadMapping>> {
+        Ok(glfw.get_joystick_guid(joystick)?.and_then(|guid| self.get(&guid)))
+    }
+
+    /// Returns each present joystick with the mapping used for it.
+    pub fn assignments(&self, glfw: &Glfw) -> Result<Vec<(Joystick, Option<&GamepadMapping>)>> {
+        let mut assignments = vec![];
+        for joystick in glfw.present_joysticks() {
+            let joystick = joystick?;
+            if let Some(guid) = glfw.get_joystick_guid(joystick)? {
+                assignments.push((joystick, self.get(&guid)));
+            }
+        }
+        Ok(assignments)
+    }
+}
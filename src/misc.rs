@@ -3,6 +3,9 @@ use std::ops::{ Index, IndexMut };
 use ffi;
 use util::cuchar_to_bool;
 use Glfw;
+use GamepadButton;
+use GamepadAxis;
+use JoystickHatState;
 
 pub struct Image {
     pixels: Vec<Pixel>,
@@ -65,6 +68,11 @@ impl<'a> Drop for Cursor<'a> {
     }
 }
 
+/// The state of a gamepad. Buttons and axes can also be accessed by indexing with
+/// [`GamepadButton`] and [`GamepadAxis`].
+///
+/// [`GamepadButton`]: enum.GamepadButton.html
+/// [`GamepadAxis`]: enum.GamepadAxis.html
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct GamepadState {
     pub a_cross:      bool,
@@ -91,6 +99,108 @@ pub struct GamepadState {
     pub right_trigger: f32
 }
 
+impl GamepadState {
+    /// Returns whether the button is pressed.
+    pub fn button(&self, button: GamepadButton) -> bool {
+        self[button]
+    }
+
+    /// Returns the value of the axis, from -1.0 to 1.0.
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self[axis]
+    }
+
+    /// Returns the pressed buttons, ordered by their GLFW value.
+    pub fn pressed_buttons(&self) -> impl Iterator<Item = GamepadButton> {
+        let state = *self;
+        GamepadButton::ALL.iter().cloned().filter(move |&b| state[b])
+    }
+
+    /// Returns every axis with its value.
+    pub fn axes(&self) -> impl Iterator<Item = (GamepadAxis, f32)> {
+        let state = *self;
+        GamepadAxis::ALL.iter().map(move |&a| (a, state[a]))
+    }
+}
+
+impl Index<GamepadButton> for GamepadState {
+    type Output = bool;
+
+    fn index(&self, button: GamepadButton) -> &bool {
+        use GamepadButton::*;
+        match button {
+            A => &self.a_cross,
+            B => &self.b_circle,
+            X => &self.x_square,
+            Y => &self.y_triangle,
+            LeftBumper => &self.left_bumper,
+            RightBumper => &self.right_bumper,
+            Back => &self.back,
+            Start => &self.start,
+            Guide => &self.guide,
+            LeftThumb => &self.left_stick,
+            RightThumb => &self.right_stick,
+            DpadUp => &self.dpad_up,
+            DpadRight => &self.dpad_right,
+            DpadDown => &self.dpad_down,
+            DpadLeft => &self.dpad_left
+        }
+    }
+}
+
+impl IndexMut<GamepadButton> for GamepadState {
+    fn index_mut(&mut self, button: GamepadButton) -> &mut bool {
+        use GamepadButton::*;
+        match button {
+            A => &mut self.a_cross,
+            B => &mut self.b_circle,
+            X => &mut self.x_square,
+            Y => &mut self.y_triangle,
+            LeftBumper => &mut self.left_bumper,
+            RightBumper => &mut self.right_bumper,
+            Back => &mut self.back,
+            Start => &mut self.start,
+            Guide => &mut self.guide,
+            LeftThumb => &mut self.left_stick,
+            RightThumb => &mut self.right_stick,
+            DpadUp => &mut self.dpad_up,
+            DpadRight => &mut self.dpad_right,
+            DpadDown => &mut self.dpad_down,
+            DpadLeft => &mut self.dpad_left
+        }
+    }
+}
+
+impl Index<GamepadAxis> for GamepadState {
+    type Output = f32;
+
+    fn index(&self, axis: GamepadAxis) -> &f32 {
+        use GamepadAxis::*;
+        match axis {
+            LeftX => &self.left_stick_x,
+            LeftY => &self.left_stick_y,
+            RightX => &self.right_stick_x,
+            RightY => &self.right_stick_y,
+            LeftTrigger => &self.left_trigger,
+            RightTrigger => &self.right_trigger
+        }
+    }
+}
+
+impl IndexMut<GamepadAxis> for GamepadState {
+    fn index_mut(&mut self, axis: GamepadAxis) -> &mut f32 {
+        use GamepadAxis::*;
+        match axis {
+            LeftX => &mut self.left_stick_x,
+            LeftY => &mut self.left_stick_y,
+            RightX => &mut self.right_stick_x,
+            RightY => &mut self.right_stick_y,
+            LeftTrigger => &mut self.left_trigger,
+            RightTrigger => &mut self.right_trigger
+        }
+    }
+}
+
 impl From<ffi::GLFWgamepadstate> for GamepadState {
     fn from(c: ffi::GLFWgamepadstate) -> Self {
         GamepadState {
@@ -118,4 +228,34 @@ impl From<ffi::GLFWgamepadstate> for GamepadState {
             right_trigger: c.axes[ffi::GLFW_GAMEPAD_AXIS_RIGHT_TRIGGER as usize],
         }
     }
-}
\ No newline at end of file
+}
+
+/// The state and identity of a joystick, captured at once by [`Glfw::capture_joystick()`].
+///
+/// The vectors and strings are reused between captures, so keeping a snapshot per joystick
+/// avoids allocating once their sizes have settled.
+///
+/// [`Glfw::capture_joystick()`]: struct.Glfw.html#method.capture_joystick
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JoystickSnapshot {
+    pub name: String,
+    pub guid: String,
+    pub axes: Vec<f32>,
+    pub buttons: Vec<bool>,
+    pub hats: Vec<JoystickHatState>
+}
+
+impl JoystickSnapshot {
+    pub fn new() -> Self {
+        JoystickSnapshot::default()
+    }
+
+    /// Empties the snapshot, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.name.clear();
+        self.guid.clear();
+        self.axes.clear();
+        self.buttons.clear();
+        self.hats.clear();
+    }
+}
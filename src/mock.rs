@@ -0,0 +1,2196 @@
+//! A pure-Rust stand-in for the GLFW library, enabled with the `mock` feature.
+//!
+//! With the feature enabled, every GLFW function the wrapper calls is implemented by this module
+//! instead of the native library, so the wrapper can be tested on machines without a display. The
+//! mock keeps track of windows, monitors, joysticks, cursors, the error state and callbacks. It
+//! never blocks: [`wait_events()`] behaves like [`poll_events()`].
+//!
+//! The functions in this module drive the mock the way a user or the operating system would drive
+//! the real library. Input is queued and delivered, in order, by the next event processing call,
+//! in the same way GLFW delivers input it receives from the window system. Window geometry
+//! changes made through this module take effect immediately, with the matching callbacks also
+//! delivered by the next event processing call.
+//!
+//! There is only one mock library per process, just like there is only one GLFW library, so tests
+//! using it must not run in parallel: each one holds the guard returned by [`lock()`] while it
+//! runs.
+//!
+//! The mock keyboard layout maps each named key to the scancode given by [`scancode()`]. Every
+//! other scancode is an unnamed key.
+//!
+//! [`lock()`]: fn.lock.html
+//! [`wait_events()`]: ../struct.Glfw.html#method.wait_events
+//! [`poll_events()`]: ../struct.Glfw.html#method.poll_events
+//! [`scancode()`]: fn.scancode.html
+
+use std::collections::{ HashMap, VecDeque };
+use std::ffi::{ CStr, CString };
+use std::os::raw::{ c_int, c_char, c_float, c_double, c_uint, c_ushort, c_uchar, c_void };
+use std::ptr;
+use std::mem;
+use std::sync::{ Mutex, MutexGuard };
+
+use enum_primitive::FromPrimitive;
+
+use ffi::*;
+use ErrorKind;
+use GamepadState;
+use Joystick;
+use JoystickHatState;
+use KeyCode;
+use Modifiers;
+use MouseButton;
+use SharedWindow;
+use VideoMode;
+#[cfg(feature = "vulkan")]
+use VkInstance;
+
+/// Frame size of decorated, windowed mode windows, as `(left, top, right, bottom)`.
+pub const FRAME_SIZE: (i32, i32, i32, i32) = (4, 24, 4, 4);
+
+/// Describes a monitor connected to the mock library.
+#[derive(Clone, Debug)]
+pub struct MonitorConfig {
+    pub name: String,
+    pub position: (i32, i32),
+    /// `(x, y, width, height)`
+    pub workarea: (i32, i32, i32, i32),
+    /// In millimetres.
+    pub physical_size: (i32, i32),
+    pub content_scale: (f32, f32),
+    pub video_modes: Vec<VideoMode>,
+    pub current_video_mode: VideoMode
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        let mode = |width, height| VideoMode {
+            width,
+            height,
+            red_bits: 8,
+            green_bits: 8,
+            blue_bits: 8,
+            refresh_rate: 60
+        };
+        MonitorConfig {
+            name: "Mock Monitor".to_owned(),
+            position: (0, 0),
+            workarea: (0, 0, 1920, 1050),
+            physical_size: (527, 296),
+            content_scale: (1.0, 1.0),
+            video_modes: vec![mode(640, 480), mode(1280, 720), mode(1920, 1080)],
+            current_video_mode: mode(1920, 1080)
+        }
+    }
+}
+
+/// Describes a joystick connected to the mock library.
+#[derive(Clone, Debug)]
+pub struct JoystickConfig {
+    pub name: String,
+    /// SDL-style GUID, used to look up gamepad mappings.
+    pub guid: String,
+    pub axes: Vec<f32>,
+    pub buttons: Vec<bool>,
+    pub hats: Vec<JoystickHatState>,
+    /// The state reported through the gamepad API. A joystick with `None` here is still a gamepad
+    /// if a mapping for its GUID was loaded, but all of its gamepad inputs are then released.
+    pub gamepad: Option<GamepadState>
+}
+
+impl Default for JoystickConfig {
+    fn default() -> Self {
+        JoystickConfig {
+            name: "Mock Joystick".to_owned(),
+            guid: "03000000000000000000000000000000".to_owned(),
+            axes: vec![],
+            buttons: vec![],
+            hats: vec![],
+            gamepad: None
+        }
+    }
+}
+
+/// Returns the scancode of a named key in the mock keyboard layout.
+pub fn scancode(key: KeyCode) -> i32 {
+    key as i32 + 8
+}
+
+fn key_from_scancode(scancode: c_int) -> c_int {
+    KeyCode::from_i32(scancode - 8).map_or(GLFW_KEY_UNKNOWN, |k| k as c_int)
+}
+
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// Waits for other tests using the mock library to finish, returning a guard that keeps the
+/// library to the calling test until it is dropped.
+///
+/// A test that panics while holding the guard does not keep the following ones from running.
+pub fn lock() -> MutexGuard<'static, ()> {
+    TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Makes the next call to [`init()`] fail with the given error.
+///
+/// [`init()`]: ../fn.init.html
+pub fn fail_next_init(kind: ErrorKind, description: &str) {
+    with(|s| s.init_failure = Some((kind as c_int, description.to_owned())));
+}
+
+/// Sets the monitors that are connected when the library is initialized.
+///
+/// This applies to every following call to [`init()`]. By default, a single monitor described by
+/// `MonitorConfig::default()` is connected.
+///
+/// [`init()`]: ../fn.init.html
+pub fn set_initial_monitors(monitors: Vec<MonitorConfig>) {
+    with(|s| s.initial_monitors = monitors);
+}
+
+/// Reports an error, as if the last GLFW function called had failed with it.
+pub fn raise_error(kind: ErrorKind, description: &str) {
+    input_error(kind as c_int, description);
+}
+
+/// Advances the GLFW timer. The mock timer does not advance on its own.
+pub fn advance_time(seconds: f64) {
+    lib(|l| l.time += seconds);
+}
+
+/// Returns the number of windows that have been created and not destroyed yet.
+pub fn window_count() -> usize {
+    lib(|l| l.windows.len())
+}
+
+/// Returns the number of cursors that have been created and not destroyed yet.
+pub fn cursor_count() -> usize {
+    lib(|l| l.cursors.len())
+}
+
+/// Connects a monitor. It is added to the monitor list by the next event processing call.
+pub fn connect_monitor(config: MonitorConfig) {
+    lib(|l| l.pending.push_back(Pending::MonitorConnected(Box::new(MonitorState::new(config)))));
+}
+
+/// Disconnects the connected monitor with the given name. It is removed from the monitor list by
+/// the next event processing call.
+///
+/// Returns `false` if no monitor with that name is connected.
+pub fn disconnect_monitor(name: &str) -> bool {
+    lib(|l| {
+        let found = l.monitor_list.iter()
+                .map(|&m| m as *mut MonitorState)
+                .find(|&m| unsafe { (*m).config.name == name });
+        if let Some(monitor) = found {
+            l.pending.push_back(Pending::MonitorDisconnected(monitor));
+        }
+        found.is_some()
+    })
+}
+
+/// Connects a joystick. It becomes present with the next event processing call.
+pub fn connect_joystick(joystick: Joystick, config: JoystickConfig) {
+    lib(|l| l.pending.push_back(Pending::JoystickConnected(joystick as c_int, config)));
+}
+
+/// Disconnects a joystick. It stops being present with the next event processing call.
+pub fn disconnect_joystick(joystick: Joystick) {
+    lib(|l| l.pending.push_back(Pending::JoystickDisconnected(joystick as c_int)));
+}
+
+/// Unplugs a joystick without queuing its disconnection. Like GLFW, the mock notices when the
+/// joystick is next polled, by a function like [`Glfw::is_joystick_present()`], and reports the
+/// disconnection from there, which may be outside event processing.
+///
+/// [`Glfw::is_joystick_present()`]: ../struct.Glfw.html#method.is_joystick_present
+pub fn unplug_joystick(joystick: Joystick) {
+    lib(|l| if let Some(js) = l.joystick(joystick as c_int) {
+        js.unplugged = true;
+    });
+}
+
+/// Changes the state of a present joystick. Joysticks are polled, so this takes effect
+/// immediately.
+///
+/// Returns `false` if the joystick is not present.
+pub fn update_joystick<F: FnOnce(&mut JoystickConfig)>(joystick: Joystick, f: F) -> bool {
+    lib(|l| match l.joystick(joystick as c_int) {
+        Some(js) => {
+            f(&mut js.config);
+            js.update();
+            true
+        }
+        None => false
+    })
+}
+
+fn queue(window: &SharedWindow, event: WindowEvent) {
+    lib(|l| {
+        l.window(window.0);
+        l.pending.push_back(Pending::Window(window.0, event));
+    })
+}
+
+pub fn press_key(window: &SharedWindow, key: KeyCode, mods: Modifiers) {
+    queue(window, WindowEvent::Key(key as c_int, scancode(key), GLFW_PRESS, mods.bits()));
+}
+
+/// Sends a key repeat. Like GLFW, the mock also reports a press of a key that is already held as
+/// a repeat.
+pub fn repeat_key(window: &SharedWindow, key: KeyCode, mods: Modifiers) {
+    queue(window, WindowEvent::Key(key as c_int, scancode(key), GLFW_REPEAT, mods.bits()));
+}
+
+pub fn release_key(window: &SharedWindow, key: KeyCode, mods: Modifiers) {
+    queue(window, WindowEvent::Key(key as c_int, scancode(key), GLFW_RELEASE, mods.bits()));
+}
+
+/// Presses the key with the given scancode, which is an unnamed key unless it is the scancode of
+/// a named key.
+pub fn press_scancode(window: &SharedWindow, scancode: i32, mods: Modifiers) {
+    let key = key_from_scancode(scancode);
+    queue(window, WindowEvent::Key(key, scancode, GLFW_PRESS, mods.bits()));
+}
+
+pub fn release_scancode(window: &SharedWindow, scancode: i32, mods: Modifiers) {
+    let key = key_from_scancode(scancode);
+    queue(window, WindowEvent::Key(key, scancode, GLFW_RELEASE, mods.bits()));
+}
+
+/// Inputs a character. As on most platforms, characters typed with Control or Alt held are only
+/// reported through the character with modifiers callback.
+pub fn type_char(window: &SharedWindow, character: char, mods: Modifiers) {
+    queue(window, WindowEvent::Char(character as c_uint, mods.bits()));
+}
+
+pub fn press_mouse_button(window: &SharedWindow, button: MouseButton, mods: Modifiers) {
+    queue(window, WindowEvent::MouseButton(button as c_int, GLFW_PRESS, mods.bits()));
+}
+
+pub fn release_mouse_button(window: &SharedWindow, button: MouseButton, mods: Modifiers) {
+    queue(window, WindowEvent::MouseButton(button as c_int, GLFW_RELEASE, mods.bits()));
+}
+
+pub fn move_cursor(window: &SharedWindow, x: f64, y: f64) {
+    queue(window, WindowEvent::CursorPos(x, y));
+}
+
+pub fn enter_cursor(window: &SharedWindow, entered: bool) {
+    queue(window, WindowEvent::CursorEnter(entered));
+}
+
+pub fn scroll(window: &SharedWindow, x: f64, y: f64) {
+    queue(window, WindowEvent::Scroll(x, y));
+}
+
+pub fn drop_paths(window: &SharedWindow, paths: &[&str]) {
+    let paths = paths.iter().map(|p| CString::new(*p).unwrap()).collect();
+    queue(window, WindowEvent::Drop(paths));
+}
+
+/// Asks the window to close, as if the user clicked its close button.
+pub fn request_close(window: &SharedWindow) {
+    queue(window, WindowEvent::Close);
+}
+
+/// Asks the window to redraw its contents, as if it was uncovered.
+pub fn request_refresh(window: &SharedWindow) {
+    queue(window, WindowEvent::Refresh);
+}
+
+/// Moves the window, as if the user dragged it.
+pub fn move_window(window: &SharedWindow, x: i32, y: i32) {
+    lib(|l| l.move_window(window.0, x, y));
+}
+
+/// Resizes the window, as if the user dragged its border.
+pub fn resize_window(window: &SharedWindow, width: i32, height: i32) {
+    lib(|l| l.resize_window(window.0, width, height));
+}
+
+/// Changes the content scale of the window, as if it was moved to a monitor with a different
+/// scale.
+pub fn set_content_scale(window: &SharedWindow, x: f32, y: f32) {
+    lib(|l| {
+        l.window(window.0).content_scale = (x, y);
+        l.pending.push_back(Pending::Window(window.0, WindowEvent::ContentScale(x, y)));
+    })
+}
+
+/// Makes the framebuffer of the window `scale` times its size, as on platforms where screen
+/// coordinates are not physical pixels, like macOS. The scale is 1 by default.
+pub fn set_framebuffer_scale(window: &SharedWindow, scale: i32) {
+    lib(|l| {
+        let w = l.window(window.0);
+        w.framebuffer_scale = scale;
+        let (width, height) = w.size;
+        l.queue(window.0, WindowEvent::FramebufferSize(width * scale, height * scale));
+    })
+}
+
+#[cfg(feature = "vulkan")]
+/// Sets whether the mock Vulkan loader is available. It is available by default.
+pub fn set_vulkan_supported(supported: bool) {
+    with(|s| {
+        s.vulkan_supported = supported;
+        if let Some(ref mut l) = s.library {
+            l.vulkan_supported = supported;
+        }
+    })
+}
+
+#[cfg(feature = "vulkan")]
+/// Returns a non-null `VkInstance` for the mock loader. No driver is behind it, but instance-level
+/// commands can be resolved with it and window surfaces created for it.
+pub fn instance() -> VkInstance {
+    static INSTANCE: u8 = 0;
+    &INSTANCE as *const u8 as VkInstance
+}
+
+#[cfg(feature = "vulkan")]
+/// Returns the number of Vulkan surfaces that have been created and not destroyed yet.
+pub fn surface_count() -> usize {
+    lib(|l| l.surfaces.len())
+}
+
+/// Key and mouse button state of a released sticky key or button that has not been polled yet
+const STICK: c_char = 3;
+
+struct State {
+    error: Option<(c_int, CString)>,
+    /// Holds the description returned by the last `glfwGetError`
+    error_description: CString,
+    error_callback: Option<GLFWerrorfun>,
+    init_hints: HashMap<c_int, c_int>,
+    init_failure: Option<(c_int, String)>,
+    initial_monitors: Vec<MonitorConfig>,
+    #[cfg(feature = "vulkan")]
+    vulkan_supported: bool,
+    library: Option<Library>
+}
+
+// The raw pointers in the state are only ever dereferenced while the lock is held
+unsafe impl Send for State {}
+
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+fn with<R, F: FnOnce(&mut State) -> R>(f: F) -> R {
+    // A panicking test may poison the lock; the next `glfwInit` starts over anyway
+    let mut lock = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    f(lock.get_or_insert_with(|| State {
+        error: None,
+        error_description: CString::default(),
+        error_callback: None,
+        init_hints: HashMap::new(),
+        init_failure: None,
+        initial_monitors: vec![MonitorConfig::default()],
+        #[cfg(feature = "vulkan")]
+        vulkan_supported: true,
+        library: None
+    }))
+}
+
+fn lib<R, F: FnOnce(&mut Library) -> R>(f: F) -> R {
+    with(|s| match s.library {
+        Some(ref mut l) => f(l),
+        None => panic!("GLFW function called while the library is not initialized")
+    })
+}
+
+/// Stores the error and invokes the error callback. Must not be called with the lock held.
+fn input_error(code: c_int, description: &str) {
+    let description = CString::new(description).unwrap();
+    let callback = with(|s| {
+        s.error = Some((code, description.clone()));
+        s.error_callback
+    });
+    if let Some(callback) = callback {
+        callback(code, description.as_ptr());
+    }
+}
+
+type Failure = (c_int, String);
+
+/// Reports the error of a failed call, returning `fallback` in its place.
+fn report<R>(result: Result<R, Failure>, fallback: R) -> R {
+    match result {
+        Ok(v) => v,
+        Err((code, description)) => {
+            input_error(code, &description);
+            fallback
+        }
+    }
+}
+
+fn fail<R>(code: c_int, description: &str) -> Result<R, Failure> {
+    Err((code, description.to_owned()))
+}
+
+struct Library {
+    monitors: Vec<*mut MonitorState>,
+    /// Connected monitors, primary first. A disconnecting monitor is removed from this list before
+    /// the callback, but stays in `monitors` until after it.
+    monitor_list: Vec<*mut GLFWmonitor>,
+    windows: Vec<*mut WindowState>,
+    cursors: Vec<*mut CursorState>,
+    joysticks: Vec<Option<JoystickState>>,
+    mappings: HashMap<String, CString>,
+    monitor_callback: Option<GLFWmonitorfun>,
+    joystick_callback: Option<GLFWjoystickfun>,
+    hints: HashMap<c_int, c_int>,
+    joystick_hat_buttons: bool,
+    pending: VecDeque<Pending>,
+    clipboard: Option<CString>,
+    key_name: CString,
+    time: f64,
+    current_context: *mut GLFWwindow,
+    #[cfg(feature = "vulkan")]
+    vulkan_supported: bool,
+    /// Owns the strings `extension_ptrs` points to
+    #[cfg(feature = "vulkan")]
+    #[allow(unused)]
+    extension_names: Vec<CString>,
+    #[cfg(feature = "vulkan")]
+    extension_ptrs: Vec<*const c_char>,
+    #[cfg(feature = "vulkan")]
+    surfaces: Vec<u64>,
+    #[cfg(feature = "vulkan")]
+    next_surface: u64
+}
+
+impl Library {
+    fn hint(&self, hint: c_int, default: c_int) -> c_int {
+        self.hints.get(&hint).cloned().unwrap_or(default)
+    }
+
+    /// Looks up a live window, panicking on use of an invalid one like the real library would
+    /// (eventually) crash.
+    fn window(&mut self, ptr: *mut GLFWwindow) -> &mut WindowState {
+        assert!(self.windows.contains(&(ptr as *mut _)), "use of an invalid GLFWwindow");
+        unsafe { &mut *(ptr as *mut WindowState) }
+    }
+
+    fn monitor(&self, ptr: *mut GLFWmonitor) -> &MonitorState {
+        assert!(self.monitors.contains(&(ptr as *mut _)), "use of an invalid GLFWmonitor");
+        unsafe { &*(ptr as *mut MonitorState) }
+    }
+
+    fn monitor_mut(&mut self, ptr: *mut GLFWmonitor) -> &mut MonitorState {
+        assert!(self.monitors.contains(&(ptr as *mut _)), "use of an invalid GLFWmonitor");
+        unsafe { &mut *(ptr as *mut MonitorState) }
+    }
+
+    fn joystick(&mut self, jid: c_int) -> Option<&mut JoystickState> {
+        self.joysticks[jid as usize].as_mut().filter(|js| !js.disconnected)
+    }
+
+    fn is_gamepad(&self, jid: c_int) -> bool {
+        self.joysticks[jid as usize].as_ref().filter(|js| !js.disconnected).is_some_and(|js| {
+            js.config.gamepad.is_some() || self.mappings.contains_key(&js.config.guid)
+        })
+    }
+
+    fn queue(&mut self, window: *mut GLFWwindow, event: WindowEvent) {
+        self.pending.push_back(Pending::Window(window, event));
+    }
+
+    fn set_pos(&mut self, window: *mut GLFWwindow, pos: (i32, i32)) {
+        let w = self.window(window);
+        if w.pos != pos {
+            w.pos = pos;
+            self.queue(window, WindowEvent::Pos(pos.0, pos.1));
+        }
+    }
+
+    fn set_size(&mut self, window: *mut GLFWwindow, size: (i32, i32)) {
+        let w = self.window(window);
+        if w.size != size {
+            w.size = size;
+            let scale = w.framebuffer_scale;
+            self.queue(window, WindowEvent::Size(size.0, size.1));
+            self.queue(window, WindowEvent::FramebufferSize(size.0 * scale, size.1 * scale));
+        }
+    }
+
+    fn move_window(&mut self, window: *mut GLFWwindow, x: i32, y: i32) {
+        if self.window(window).monitor.is_null() {
+            self.set_pos(window, (x, y));
+        }
+    }
+
+    fn resize_window(&mut self, window: *mut GLFWwindow, width: i32, height: i32) {
+        let size = self.window(window).clamp_size(width, height);
+        self.set_size(window, size);
+    }
+
+    fn set_focus(&mut self, window: *mut GLFWwindow, focused: bool) {
+        let w = self.window(window);
+        if w.focused != focused {
+            w.focused = focused;
+            self.queue(window, WindowEvent::Focus(focused));
+        }
+    }
+
+    fn focus(&mut self, window: *mut GLFWwindow) {
+        for other in self.windows.clone() {
+            let other = other as *mut GLFWwindow;
+            if other != window {
+                self.set_focus(other, false);
+            }
+        }
+        self.set_focus(window, true);
+    }
+
+    fn primary_workarea(&self) -> (i32, i32, i32, i32) {
+        self.monitor_list.first()
+                .map_or((0, 0, 1920, 1080), |&m| self.monitor(m).config.workarea)
+    }
+
+    fn content_scale(&self, monitor: *mut GLFWmonitor) -> (f32, f32) {
+        if !monitor.is_null() {
+            self.monitor(monitor).config.content_scale
+        } else {
+            self.monitor_list.first().map_or((1.0, 1.0), |&m| self.monitor(m).config.content_scale)
+        }
+    }
+
+    fn free_window(&mut self, window: *mut GLFWwindow) {
+        self.window(window);
+        self.windows.retain(|&w| w as *mut GLFWwindow != window);
+        self.pending.retain(|e| match *e {
+            Pending::Window(w, _) => w != window,
+            _ => true
+        });
+        if self.current_context == window {
+            self.current_context = ptr::null_mut();
+        }
+        unsafe { drop(Box::from_raw(window as *mut WindowState)) };
+    }
+}
+
+impl Drop for Library {
+    fn drop(&mut self) {
+        for window in self.windows.clone() {
+            self.free_window(window as *mut GLFWwindow);
+        }
+        for cursor in self.cursors.drain(..) {
+            unsafe { drop(Box::from_raw(cursor)) };
+        }
+        for monitor in self.monitors.drain(..) {
+            unsafe { drop(Box::from_raw(monitor)) };
+        }
+        for event in self.pending.drain(..) {
+            if let Pending::MonitorConnected(monitor) = event {
+                drop(monitor);
+            }
+        }
+    }
+}
+
+struct MonitorState {
+    config: MonitorConfig,
+    name: CString,
+    ramp: [Vec<c_ushort>; 3],
+    ramp_view: GLFWgammaramp,
+    user_pointer: *mut c_void
+}
+
+impl MonitorState {
+    fn new(config: MonitorConfig) -> MonitorState {
+        let identity: Vec<_> = (0..256).map(|i| (i * 257) as c_ushort).collect();
+        MonitorState {
+            name: CString::new(config.name.clone()).unwrap(),
+            config,
+            ramp: [identity.clone(), identity.clone(), identity],
+            ramp_view: GLFWgammaramp {
+                red: ptr::null_mut(),
+                green: ptr::null_mut(),
+                blue: ptr::null_mut(),
+                size: 0
+            },
+            user_pointer: ptr::null_mut()
+        }
+    }
+}
+
+struct CursorState {
+    #[allow(unused)]
+    shape: c_int
+}
+
+struct JoystickState {
+    config: JoystickConfig,
+    name: CString,
+    guid: CString,
+    buttons: Vec<c_uchar>,
+    hats: Vec<c_uchar>,
+    /// Including the hats as buttons, if the `GLFW_JOYSTICK_HAT_BUTTONS` init hint is set.
+    hat_buttons: Vec<c_uchar>,
+    user_pointer: *mut c_void,
+    /// Set by `unplug_joystick`, until the disconnection is noticed
+    unplugged: bool,
+    /// Set while the disconnection is reported. Like GLFW, the joystick is no longer present
+    /// then, but its user pointer is still available.
+    disconnected: bool
+}
+
+impl JoystickState {
+    fn new(config: JoystickConfig) -> JoystickState {
+        let mut this = JoystickState {
+            name: CString::new(config.name.clone()).unwrap(),
+            guid: CString::new(config.guid.clone()).unwrap(),
+            config,
+            buttons: vec![],
+            hats: vec![],
+            hat_buttons: vec![],
+            user_pointer: ptr::null_mut(),
+            unplugged: false,
+            disconnected: false
+        };
+        this.update();
+        this
+    }
+
+    fn update(&mut self) {
+        self.buttons = self.config.buttons.iter().map(|&b| b as c_uchar).collect();
+        self.hats = self.config.hats.iter().map(|h| h.bits()).collect();
+        self.hat_buttons = self.buttons.clone();
+        for hat in &self.hats {
+            for &dir in &[GLFW_HAT_UP, GLFW_HAT_RIGHT, GLFW_HAT_DOWN, GLFW_HAT_LEFT] {
+                self.hat_buttons.push((*hat as c_int & dir != 0) as c_uchar);
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+struct Callbacks {
+    pos: Option<GLFWwindowposfun>,
+    size: Option<GLFWwindowsizefun>,
+    close: Option<GLFWwindowclosefun>,
+    refresh: Option<GLFWwindowrefreshfun>,
+    focus: Option<GLFWwindowfocusfun>,
+    iconify: Option<GLFWwindowiconifyfun>,
+    maximize: Option<GLFWwindowmaximizefun>,
+    framebuffer_size: Option<GLFWframebuffersizefun>,
+    content_scale: Option<GLFWwindowcontentscalefun>,
+    mouse_button: Option<GLFWmousebuttonfun>,
+    cursor_pos: Option<GLFWcursorposfun>,
+    cursor_enter: Option<GLFWcursorenterfun>,
+    scroll: Option<GLFWscrollfun>,
+    key: Option<GLFWkeyfun>,
+    character: Option<GLFWcharfun>,
+    char_mods: Option<GLFWcharmodsfun>,
+    drop: Option<GLFWdropfun>
+}
+
+struct WindowState {
+    title: String,
+    pos: (i32, i32),
+    size: (i32, i32),
+    /// Position and size to go back to when restored from being maximized
+    restore: ((i32, i32), (i32, i32)),
+    size_limits: (c_int, c_int, c_int, c_int),
+    aspect_ratio: (c_int, c_int),
+    content_scale: (f32, f32),
+    /// Framebuffer pixels per screen coordinate
+    framebuffer_scale: i32,
+    opacity: f32,
+    monitor: *mut GLFWmonitor,
+    icon_count: c_int,
+    should_close: bool,
+    focused: bool,
+    iconified: bool,
+    maximized: bool,
+    hovered: bool,
+    visible: bool,
+    resizable: bool,
+    decorated: bool,
+    auto_iconify: bool,
+    floating: bool,
+    transparent: bool,
+    focus_on_show: bool,
+    client_api: c_int,
+    creation_api: c_int,
+    version: (c_int, c_int, c_int),
+    robustness: c_int,
+    forward_compat: bool,
+    debug: bool,
+    profile: c_int,
+    release_behavior: c_int,
+    no_error: bool,
+    cursor_mode: c_int,
+    sticky_keys: bool,
+    sticky_mouse_buttons: bool,
+    lock_key_mods: bool,
+    raw_mouse_motion: bool,
+    keys: [c_char; GLFW_KEY_LAST as usize + 1],
+    mouse_buttons: [c_char; GLFW_MOUSE_BUTTON_LAST as usize + 1],
+    cursor_pos: (f64, f64),
+    cursor: *mut GLFWcursor,
+    user_pointer: *mut c_void,
+    callbacks: Callbacks
+}
+
+impl WindowState {
+    fn clamp_size(&self, width: i32, height: i32) -> (i32, i32) {
+        let clamp = |v: i32, min: c_int, max: c_int| {
+            let v = if min != GLFW_DONT_CARE { v.max(min) } else { v };
+            if max != GLFW_DONT_CARE { v.min(max) } else { v }
+        };
+        let (minw, minh, maxw, maxh) = self.size_limits;
+        (clamp(width, minw, maxw), clamp(height, minh, maxh))
+    }
+
+    fn strip_lock_mods(&self, mods: c_int) -> c_int {
+        if self.lock_key_mods { mods } else { mods & !(GLFW_MOD_CAPS_LOCK | GLFW_MOD_NUM_LOCK) }
+    }
+
+    /// Updates the state for an event about to be delivered, the way `_glfwInput*` does. Returns
+    /// the event to deliver, or `None` if GLFW would drop it.
+    fn apply(&mut self, event: WindowEvent) -> Option<WindowEvent> {
+        use self::WindowEvent::*;
+        match event {
+            Key(key, scancode, mut action, mods) => {
+                if (0..=GLFW_KEY_LAST).contains(&key) {
+                    let state = &mut self.keys[key as usize];
+                    let repeated = action == GLFW_PRESS && *state as c_int == GLFW_PRESS;
+                    if action == GLFW_RELEASE && *state as c_int == GLFW_RELEASE {
+                        return None
+                    }
+                    *state = if action == GLFW_RELEASE && self.sticky_keys {
+                        STICK
+                    } else if action == GLFW_RELEASE {
+                        GLFW_RELEASE as c_char
+                    } else {
+                        GLFW_PRESS as c_char
+                    };
+                    if repeated {
+                        action = GLFW_REPEAT;
+                    }
+                }
+                Some(Key(key, scancode, action, self.strip_lock_mods(mods)))
+            }
+            Char(codepoint, mods) => {
+                if codepoint < 32 || (codepoint > 126 && codepoint < 160) {
+                    return None
+                }
+                Some(Char(codepoint, self.strip_lock_mods(mods)))
+            }
+            MouseButton(button, action, mods) => {
+                self.mouse_buttons[button as usize] =
+                        if action == GLFW_RELEASE && self.sticky_mouse_buttons {
+                            STICK
+                        } else {
+                            action as c_char
+                        };
+                Some(MouseButton(button, action, self.strip_lock_mods(mods)))
+            }
+            CursorPos(x, y) => {
+                if self.cursor_pos == (x, y) {
+                    return None
+                }
+                self.cursor_pos = (x, y);
+                Some(event)
+            }
+            CursorEnter(entered) => {
+                self.hovered = entered;
+                Some(event)
+            }
+            Close => {
+                self.should_close = true;
+                Some(event)
+            }
+            _ => Some(event)
+        }
+    }
+}
+
+enum WindowEvent {
+    Pos(c_int, c_int),
+    Size(c_int, c_int),
+    Close,
+    Refresh,
+    Focus(bool),
+    Iconify(bool),
+    Maximize(bool),
+    FramebufferSize(c_int, c_int),
+    ContentScale(c_float, c_float),
+    MouseButton(c_int, c_int, c_int),
+    CursorPos(c_double, c_double),
+    CursorEnter(bool),
+    Scroll(c_double, c_double),
+    Key(c_int, c_int, c_int, c_int),
+    Char(c_uint, c_int),
+    Drop(Vec<CString>)
+}
+
+enum Pending {
+    MonitorConnected(Box<MonitorState>),
+    MonitorDisconnected(*mut MonitorState),
+    JoystickConnected(c_int, JoystickConfig),
+    JoystickDisconnected(c_int),
+    Window(*mut GLFWwindow, WindowEvent)
+}
+
+/// Delivers an event, invoking the callback with the lock released so that it can call back into
+/// the library.
+fn deliver(event: Pending) {
+    match event {
+        Pending::MonitorConnected(monitor) => {
+            let (callback, monitor) = lib(|l| {
+                let monitor = Box::into_raw(monitor);
+                l.monitors.push(monitor);
+                l.monitor_list.push(monitor as *mut GLFWmonitor);
+                (l.monitor_callback, monitor as *mut GLFWmonitor)
+            });
+            if let Some(callback) = callback {
+                callback(monitor, GLFW_CONNECTED);
+            }
+        }
+        Pending::MonitorDisconnected(monitor) => {
+            let monitor = monitor as *mut GLFWmonitor;
+            let callback = lib(|l| {
+                if !l.monitor_list.contains(&monitor) {
+                    return None
+                }
+                l.monitor_list.retain(|&m| m != monitor);
+                for &w in &l.windows {
+                    let w = unsafe { &mut *w };
+                    if w.monitor == monitor {
+                        w.monitor = ptr::null_mut();
+                    }
+                }
+                Some(l.monitor_callback)
+            });
+            if let Some(callback) = callback {
+                if let Some(callback) = callback {
+                    callback(monitor, GLFW_DISCONNECTED);
+                }
+                lib(|l| l.monitors.retain(|&m| m as *mut GLFWmonitor != monitor));
+                unsafe { drop(Box::from_raw(monitor as *mut MonitorState)) };
+            }
+        }
+        Pending::JoystickConnected(jid, config) => {
+            let callback = lib(|l| {
+                if l.joysticks[jid as usize].is_some() {
+                    return None
+                }
+                l.joysticks[jid as usize] = Some(JoystickState::new(config));
+                Some(l.joystick_callback)
+            });
+            if let Some(Some(callback)) = callback {
+                callback(jid, GLFW_CONNECTED);
+            }
+        }
+        Pending::JoystickDisconnected(jid) => {
+            let callback = lib(|l| {
+                let callback = l.joystick_callback;
+                l.joystick(jid).map(|js| {
+                    js.disconnected = true;
+                    callback
+                })
+            });
+            if let Some(callback) = callback {
+                if let Some(callback) = callback {
+                    callback(jid, GLFW_DISCONNECTED);
+                }
+                lib(|l| l.joysticks[jid as usize] = None);
+            }
+        }
+        Pending::Window(window, event) => {
+            let (cb, event) = match lib(|l| {
+                // The window may have been destroyed by an earlier callback in this batch
+                if !l.windows.contains(&(window as *mut _)) {
+                    return None
+                }
+                let w = l.window(window);
+                w.apply(event).map(|e| (w.callbacks, e))
+            }) {
+                Some(v) => v,
+                None => return
+            };
+            use self::WindowEvent::*;
+            match event {
+                Pos(x, y) => if let Some(f) = cb.pos { f(window, x, y) },
+                Size(w, h) => if let Some(f) = cb.size { f(window, w, h) },
+                Close => if let Some(f) = cb.close { f(window) },
+                Refresh => if let Some(f) = cb.refresh { f(window) },
+                Focus(v) => if let Some(f) = cb.focus { f(window, v as c_int) },
+                Iconify(v) => if let Some(f) = cb.iconify { f(window, v as c_int) },
+                Maximize(v) => if let Some(f) = cb.maximize { f(window, v as c_int) },
+                FramebufferSize(w, h) => if let Some(f) = cb.framebuffer_size { f(window, w, h) },
+                ContentScale(x, y) => if let Some(f) = cb.content_scale { f(window, x, y) },
+                MouseButton(button, action, mods) =>
+                        if let Some(f) = cb.mouse_button { f(window, button, action, mods) },
+                CursorPos(x, y) => if let Some(f) = cb.cursor_pos { f(window, x, y) },
+                CursorEnter(v) => if let Some(f) = cb.cursor_enter { f(window, v as c_int) },
+                Scroll(x, y) => if let Some(f) = cb.scroll { f(window, x, y) },
+                Key(key, scancode, action, mods) =>
+                        if let Some(f) = cb.key { f(window, key, scancode, action, mods) },
+                Char(codepoint, mods) => {
+                    if let Some(f) = cb.char_mods {
+                        f(window, codepoint, mods);
+                    }
+                    let plain = mods & (GLFW_MOD_CONTROL | GLFW_MOD_ALT) == 0;
+                    // The window may have been destroyed by the callback above
+                    let cb = lib(|l| if l.windows.contains(&(window as *mut _)) {
+                        l.window(window).callbacks.character
+                    } else {
+                        None
+                    });
+                    if let (true, Some(f)) = (plain, cb) {
+                        f(window, codepoint);
+                    }
+                }
+                Drop(paths) => if let Some(f) = cb.drop {
+                    let ptrs: Vec<_> = paths.iter().map(|p| p.as_ptr()).collect();
+                    f(window, ptrs.len() as c_int, ptrs.as_ptr());
+                }
+            }
+        }
+    }
+}
+
+fn gamepad_to_raw(s: &GamepadState) -> GLFWgamepadstate {
+    let mut raw = GLFWgamepadstate::default();
+    {
+        let mut button = |i: c_int, v: bool| raw.buttons[i as usize] = v as c_uchar;
+        button(GLFW_GAMEPAD_BUTTON_A, s.a_cross);
+        button(GLFW_GAMEPAD_BUTTON_B, s.b_circle);
+        button(GLFW_GAMEPAD_BUTTON_X, s.x_square);
+        button(GLFW_GAMEPAD_BUTTON_Y, s.y_triangle);
+        button(GLFW_GAMEPAD_BUTTON_LEFT_BUMPER, s.left_bumper);
+        button(GLFW_GAMEPAD_BUTTON_RIGHT_BUMPER, s.right_bumper);
+        button(GLFW_GAMEPAD_BUTTON_BACK, s.back);
+        button(GLFW_GAMEPAD_BUTTON_START, s.start);
+        button(GLFW_GAMEPAD_BUTTON_GUIDE, s.guide);
+        button(GLFW_GAMEPAD_BUTTON_LEFT_THUMB, s.left_stick);
+        button(GLFW_GAMEPAD_BUTTON_RIGHT_THUMB, s.right_stick);
+        button(GLFW_GAMEPAD_BUTTON_DPAD_UP, s.dpad_up);
+        button(GLFW_GAMEPAD_BUTTON_DPAD_RIGHT, s.dpad_right);
+        button(GLFW_GAMEPAD_BUTTON_DPAD_DOWN, s.dpad_down);
+        button(GLFW_GAMEPAD_BUTTON_DPAD_LEFT, s.dpad_left);
+    }
+    raw.axes[GLFW_GAMEPAD_AXIS_LEFT_X as usize] = s.left_stick_x;
+    raw.axes[GLFW_GAMEPAD_AXIS_LEFT_Y as usize] = s.left_stick_y;
+    raw.axes[GLFW_GAMEPAD_AXIS_RIGHT_X as usize] = s.right_stick_x;
+    raw.axes[GLFW_GAMEPAD_AXIS_RIGHT_Y as usize] = s.right_stick_y;
+    raw.axes[GLFW_GAMEPAD_AXIS_LEFT_TRIGGER as usize] = s.left_trigger;
+    raw.axes[GLFW_GAMEPAD_AXIS_RIGHT_TRIGGER as usize] = s.right_trigger;
+    raw
+}
+
+/// Returns the printable name of a key, the way GLFW does for a US keyboard layout.
+fn key_name(key: c_int) -> Option<String> {
+    match key {
+        GLFW_KEY_APOSTROPHE..=GLFW_KEY_GRAVE_ACCENT =>
+                Some(((key as u8 as char).to_ascii_lowercase()).to_string()),
+        GLFW_KEY_KP_0..=GLFW_KEY_KP_9 => Some(((key - GLFW_KEY_KP_0) as u8 + b'0').to_string()),
+        GLFW_KEY_KP_DECIMAL => Some(".".to_owned()),
+        GLFW_KEY_KP_DIVIDE => Some("/".to_owned()),
+        GLFW_KEY_KP_MULTIPLY => Some("*".to_owned()),
+        GLFW_KEY_KP_SUBTRACT => Some("-".to_owned()),
+        GLFW_KEY_KP_ADD => Some("+".to_owned()),
+        GLFW_KEY_KP_EQUAL => Some("=".to_owned()),
+        _ => None
+    }
+}
+
+fn mapping_platform() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "Windows"
+    } else if cfg!(target_os = "macos") {
+        "Mac OS X"
+    } else {
+        "Linux"
+    }
+}
+
+/// Parses a mapping line into its GUID and name. Returns `Ok(None)` for lines meant for another
+/// platform.
+fn parse_mapping(line: &str) -> Result<Option<(String, String)>, Failure> {
+    let mut fields = line.split(',');
+    let guid = fields.next().unwrap_or("");
+    let name = match fields.next() {
+        Some(name) if guid.len() == 32 && guid.chars().all(|c| c.is_ascii_hexdigit()) => name,
+        _ => return fail(GLFW_INVALID_VALUE, &format!("Invalid gamepad mapping: {}", line))
+    };
+    for field in fields {
+        let field = field.trim();
+        if field.is_empty() {
+            continue
+        }
+        let mut kv = field.splitn(2, ':');
+        let key = kv.next().unwrap();
+        let value = match kv.next() {
+            Some(v) => v,
+            None => return fail(GLFW_INVALID_VALUE, &format!("Invalid gamepad mapping: {}", line))
+        };
+        if key == "platform" && value != mapping_platform() {
+            return Ok(None)
+        }
+    }
+    Ok(Some((guid.to_owned(), name.to_owned())))
+}
+
+/// Validates the requested context the way `_glfwIsValidContextConfig` does, returning the
+/// version of the context the mock "creates".
+fn context_version(l: &Library) -> Result<(c_int, c_int, c_int), Failure> {
+    let api = l.hint(GLFW_CLIENT_API, GLFW_OPENGL_API);
+    let major = l.hint(GLFW_CONTEXT_VERSION_MAJOR, 1);
+    let minor = l.hint(GLFW_CONTEXT_VERSION_MINOR, 0);
+    match api {
+        GLFW_NO_API => Ok((0, 0, 0)),
+        GLFW_OPENGL_API => {
+            if major < 1 || minor < 0 || (major == 1 && minor > 5) || (major == 2 && minor > 1) ||
+                    (major == 3 && minor > 3) {
+                return fail(GLFW_INVALID_VALUE,
+                        &format!("Invalid OpenGL version {}.{}", major, minor))
+            }
+            if l.hint(GLFW_OPENGL_PROFILE, GLFW_OPENGL_ANY_PROFILE) != GLFW_OPENGL_ANY_PROFILE &&
+                    (major <= 2 || (major == 3 && minor < 2)) {
+                return fail(GLFW_INVALID_VALUE,
+                        "Context profiles are only defined for OpenGL version 3.2 and above")
+            }
+            if l.hint(GLFW_OPENGL_FORWARD_COMPAT, GLFW_FALSE) != GLFW_FALSE && major <= 2 {
+                return fail(GLFW_INVALID_VALUE,
+                        "Forward-compatibility is only defined for OpenGL version 3.0 and above")
+            }
+            if major > 4 || (major == 4 && minor > 6) {
+                return fail(GLFW_VERSION_UNAVAILABLE,
+                        &format!("Requested OpenGL version {}.{} is not supported", major, minor))
+            }
+            Ok((4, 6, 0))
+        }
+        _ => {
+            if major < 1 || minor < 0 || (major == 1 && minor > 1) || (major == 2 && minor > 0) {
+                return fail(GLFW_INVALID_VALUE,
+                        &format!("Invalid OpenGL ES version {}.{}", major, minor))
+            }
+            if major > 3 || (major == 3 && minor > 2) {
+                return fail(GLFW_VERSION_UNAVAILABLE, &format!(
+                    "Requested OpenGL ES version {}.{} is not supported", major, minor
+                ))
+            }
+            Ok((3, 2, 0))
+        }
+    }
+}
+
+/// Implementations of the GLFW functions, re-exported by `ffi` in place of the native ones.
+#[allow(bad_style)]
+pub(crate) mod glfw {
+    use super::*;
+
+    pub unsafe fn glfwInit() -> c_int {
+        let result = with(|s| {
+            if s.library.is_some() {
+                return Ok(())
+            }
+            if let Some((code, description)) = s.init_failure.take() {
+                return Err((code, description))
+            }
+            let monitors: Vec<_> = s.initial_monitors.iter().cloned()
+                    .map(|c| Box::into_raw(Box::new(MonitorState::new(c))))
+                    .collect();
+            #[cfg(feature = "vulkan")]
+            let extension_names = vec![
+                CString::new("VK_KHR_surface").unwrap(),
+                CString::new("VK_MOCK_surface").unwrap()
+            ];
+            s.library = Some(Library {
+                monitor_list: monitors.iter().map(|&m| m as *mut GLFWmonitor).collect(),
+                monitors,
+                windows: vec![],
+                cursors: vec![],
+                joysticks: (0..GLFW_JOYSTICK_LAST + 1).map(|_| None).collect(),
+                mappings: HashMap::new(),
+                monitor_callback: None,
+                joystick_callback: None,
+                hints: HashMap::new(),
+                joystick_hat_buttons:
+                        s.init_hints.get(&GLFW_JOYSTICK_HAT_BUTTONS) != Some(&GLFW_FALSE),
+                pending: VecDeque::new(),
+                clipboard: None,
+                key_name: CString::default(),
+                time: 0.0,
+                current_context: ptr::null_mut(),
+                #[cfg(feature = "vulkan")]
+                vulkan_supported: s.vulkan_supported,
+                #[cfg(feature = "vulkan")]
+                extension_ptrs: extension_names.iter().map(|e| e.as_ptr()).collect(),
+                #[cfg(feature = "vulkan")]
+                extension_names,
+                #[cfg(feature = "vulkan")]
+                surfaces: vec![],
+                #[cfg(feature = "vulkan")]
+                next_surface: 1
+            });
+            Ok(())
+        });
+        report(result.map(|_| GLFW_TRUE), GLFW_FALSE)
+    }
+
+    pub unsafe fn glfwTerminate() {
+        let library = with(|s| s.library.take());
+        // Dropped outside the lock, as freeing it does not call back into the library
+        drop(library);
+    }
+
+    pub unsafe fn glfwInitHint(hint: c_int, value: c_int) {
+        with(|s| s.init_hints.insert(hint, value));
+    }
+
+    pub unsafe fn glfwGetVersion(major: *mut c_int, minor: *mut c_int, rev: *mut c_int) {
+        *major = GLFW_VERSION_MAJOR;
+        *minor = GLFW_VERSION_MINOR;
+        *rev = GLFW_VERSION_REVISION;
+    }
+
+    pub unsafe fn glfwGetVersionString() -> *const c_char {
+        b"3.3.0 Mock\0".as_ptr() as *const c_char
+    }
+
+    pub unsafe fn glfwGetError(description: *mut *const c_char) -> c_int {
+        with(|s| match s.error.take() {
+            Some((code, desc)) => {
+                s.error_description = desc;
+                if !description.is_null() {
+                    *description = s.error_description.as_ptr();
+                }
+                code
+            }
+            None => {
+                if !description.is_null() {
+                    *description = ptr::null();
+                }
+                GLFW_NO_ERROR
+            }
+        })
+    }
+
+    pub unsafe fn glfwSetErrorCallback(cbfun: Option<GLFWerrorfun>) -> Option<GLFWerrorfun> {
+        with(|s| mem::replace(&mut s.error_callback, cbfun))
+    }
+
+    pub unsafe fn glfwGetMonitors(count: *mut c_int) -> *const *mut GLFWmonitor {
+        lib(|l| {
+            *count = l.monitor_list.len() as c_int;
+            if l.monitor_list.is_empty() { ptr::null() } else { l.monitor_list.as_ptr() }
+        })
+    }
+
+    pub unsafe fn glfwGetPrimaryMonitor() -> *mut GLFWmonitor {
+        lib(|l| l.monitor_list.first().cloned().unwrap_or(ptr::null_mut()))
+    }
+
+    pub unsafe fn glfwGetMonitorPos(monitor: *mut GLFWmonitor, xpos: *mut c_int, ypos: *mut c_int) {
+        let (x, y) = lib(|l| l.monitor(monitor).config.position);
+        *xpos = x;
+        *ypos = y;
+    }
+
+    pub unsafe fn glfwGetMonitorWorkarea(monitor: *mut GLFWmonitor, xpos: *mut c_int, ypos: *mut c_int, width: *mut c_int, height: *mut c_int) {
+        let (x, y, w, h) = lib(|l| l.monitor(monitor).config.workarea);
+        *xpos = x;
+        *ypos = y;
+        *width = w;
+        *height = h;
+    }
+
+    pub unsafe fn glfwGetMonitorPhysicalSize(monitor: *mut GLFWmonitor, widthMM: *mut c_int, heightMM: *mut c_int) {
+        let (w, h) = lib(|l| l.monitor(monitor).config.physical_size);
+        *widthMM = w;
+        *heightMM = h;
+    }
+
+    pub unsafe fn glfwGetMonitorContentScale(monitor: *mut GLFWmonitor, xscale: *mut c_float, yscale: *mut c_float) {
+        let (x, y) = lib(|l| l.monitor(monitor).config.content_scale);
+        *xscale = x;
+        *yscale = y;
+    }
+
+    pub unsafe fn glfwGetMonitorName(monitor: *mut GLFWmonitor) -> *const c_char {
+        lib(|l| l.monitor(monitor).name.as_ptr())
+    }
+
+    pub unsafe fn glfwSetMonitorUserPointer(monitor: *mut GLFWmonitor, pointer: *mut c_void) {
+        lib(|l| l.monitor_mut(monitor).user_pointer = pointer);
+    }
+
+    pub unsafe fn glfwGetMonitorUserPointer(monitor: *mut GLFWmonitor) -> *mut c_void {
+        lib(|l| l.monitor(monitor).user_pointer)
+    }
+
+    pub unsafe fn glfwSetMonitorCallback(cbfun: Option<GLFWmonitorfun>) -> Option<GLFWmonitorfun> {
+        lib(|l| mem::replace(&mut l.monitor_callback, cbfun))
+    }
+
+    pub unsafe fn glfwGetVideoModes(monitor: *mut GLFWmonitor, count: *mut c_int) -> *const GLFWvidmode {
+        lib(|l| {
+            let modes = &l.monitor(monitor).config.video_modes;
+            *count = modes.len() as c_int;
+            modes.as_ptr()
+        })
+    }
+
+    pub unsafe fn glfwGetVideoMode(monitor: *mut GLFWmonitor) -> *const GLFWvidmode {
+        lib(|l| &l.monitor(monitor).config.current_video_mode as *const _)
+    }
+
+    pub unsafe fn glfwSetGamma(monitor: *mut GLFWmonitor, gamma: c_float) {
+        if !(gamma > 0.0 && gamma.is_finite()) {
+            return input_error(GLFW_INVALID_VALUE, &format!("Invalid gamma value {}", gamma))
+        }
+        lib(|l| {
+            let m = l.monitor_mut(monitor);
+            let size = m.ramp[0].len();
+            let ramp: Vec<_> = (0..size).map(|i| {
+                let value = (i as f32 / (size - 1) as f32).powf(1.0 / gamma) * 65535.0 + 0.5;
+                value.min(65535.0) as c_ushort
+            }).collect();
+            m.ramp = [ramp.clone(), ramp.clone(), ramp];
+        })
+    }
+
+    pub unsafe fn glfwGetGammaRamp(monitor: *mut GLFWmonitor) -> *const GLFWgammaramp {
+        lib(|l| {
+            let m = l.monitor_mut(monitor);
+            m.ramp_view = GLFWgammaramp {
+                red: m.ramp[0].as_mut_ptr(),
+                green: m.ramp[1].as_mut_ptr(),
+                blue: m.ramp[2].as_mut_ptr(),
+                size: m.ramp[0].len() as c_uint
+            };
+            &m.ramp_view as *const _
+        })
+    }
+
+    pub unsafe fn glfwSetGammaRamp(monitor: *mut GLFWmonitor, ramp: *const GLFWgammaramp) {
+        let ramp = &*ramp;
+        if ramp.size == 0 {
+            return input_error(GLFW_INVALID_VALUE, "Invalid gamma ramp size 0")
+        }
+        let size = ramp.size as usize;
+        let copy = |p: *mut c_ushort| ::std::slice::from_raw_parts(p, size).to_vec();
+        let new = [copy(ramp.red), copy(ramp.green), copy(ramp.blue)];
+        lib(|l| l.monitor_mut(monitor).ramp = new);
+    }
+
+    #[allow(unused)]
+    pub unsafe fn glfwDefaultWindowHints() {
+        lib(|l| l.hints.clear());
+    }
+
+    pub unsafe fn glfwWindowHint(hint: c_int, value: c_int) {
+        lib(|l| l.hints.insert(hint, value));
+    }
+
+    pub unsafe fn glfwWindowHintString(_hint: c_int, _value: *const c_char) {
+        // The string hints only affect platforms the mock does not pretend to be
+    }
+
+    pub unsafe fn glfwCreateWindow(width: c_int, height: c_int, title: *const c_char, monitor: *mut GLFWmonitor, share: *mut GLFWwindow) -> *mut GLFWwindow {
+        let title = CStr::from_ptr(title).to_string_lossy().into_owned();
+        let result = lib(|l| {
+            if width <= 0 || height <= 0 {
+                return fail(GLFW_INVALID_VALUE, &format!("Invalid window size {}x{}", width, height))
+            }
+            if !monitor.is_null() {
+                l.monitor(monitor);
+            }
+            if !share.is_null() && l.window(share).client_api == GLFW_NO_API {
+                return fail(GLFW_NO_WINDOW_CONTEXT,
+                        "Cannot share with a window that has no OpenGL or OpenGL ES context")
+            }
+            let version = context_version(l)?;
+            let flag = |hint, default| l.hint(hint, default) != GLFW_FALSE;
+            let (wx, wy, _, _) = l.primary_workarea();
+            let pos = if !monitor.is_null() {
+                l.monitor(monitor).config.position
+            } else {
+                (wx + FRAME_SIZE.0, wy + FRAME_SIZE.1)
+            };
+            let w = Box::new(WindowState {
+                title,
+                pos,
+                size: (width, height),
+                restore: (pos, (width, height)),
+                size_limits: (GLFW_DONT_CARE, GLFW_DONT_CARE, GLFW_DONT_CARE, GLFW_DONT_CARE),
+                aspect_ratio: (GLFW_DONT_CARE, GLFW_DONT_CARE),
+                content_scale: l.content_scale(monitor),
+                framebuffer_scale: 1,
+                opacity: 1.0,
+                monitor,
+                icon_count: 0,
+                should_close: false,
+                focused: false,
+                iconified: false,
+                maximized: false,
+                hovered: false,
+                visible: false,
+                resizable: flag(GLFW_RESIZABLE, GLFW_TRUE),
+                decorated: flag(GLFW_DECORATED, GLFW_TRUE),
+                auto_iconify: flag(GLFW_AUTO_ICONIFY, GLFW_TRUE),
+                floating: flag(GLFW_FLOATING, GLFW_FALSE),
+                transparent: flag(GLFW_TRANSPARENT_FRAMEBUFFER, GLFW_FALSE),
+                focus_on_show: flag(GLFW_FOCUS_ON_SHOW, GLFW_TRUE),
+                client_api: l.hint(GLFW_CLIENT_API, GLFW_OPENGL_API),
+                creation_api: l.hint(GLFW_CONTEXT_CREATION_API, GLFW_NATIVE_CONTEXT_API),
+                version,
+                robustness: l.hint(GLFW_CONTEXT_ROBUSTNESS, GLFW_NO_ROBUSTNESS),
+                forward_compat: flag(GLFW_OPENGL_FORWARD_COMPAT, GLFW_FALSE),
+                debug: flag(GLFW_OPENGL_DEBUG_CONTEXT, GLFW_FALSE),
+                profile: l.hint(GLFW_OPENGL_PROFILE, GLFW_OPENGL_ANY_PROFILE),
+                release_behavior: l.hint(GLFW_CONTEXT_RELEASE_BEHAVIOR, GLFW_ANY_RELEASE_BEHAVIOR),
+                no_error: flag(GLFW_CONTEXT_NO_ERROR, GLFW_FALSE),
+                cursor_mode: GLFW_CURSOR_NORMAL,
+                sticky_keys: false,
+                sticky_mouse_buttons: false,
+                lock_key_mods: false,
+                raw_mouse_motion: false,
+                keys: [GLFW_RELEASE as c_char; GLFW_KEY_LAST as usize + 1],
+                mouse_buttons: [GLFW_RELEASE as c_char; GLFW_MOUSE_BUTTON_LAST as usize + 1],
+                cursor_pos: (0.0, 0.0),
+                cursor: ptr::null_mut(),
+                user_pointer: ptr::null_mut(),
+                callbacks: Callbacks::default()
+            });
+            let (maximized, visible, focused) = (
+                flag(GLFW_MAXIMIZED, GLFW_FALSE),
+                flag(GLFW_VISIBLE, GLFW_TRUE),
+                flag(GLFW_FOCUSED, GLFW_TRUE)
+            );
+            let window = Box::into_raw(w);
+            l.windows.push(window);
+            let window = window as *mut GLFWwindow;
+            if monitor.is_null() && maximized {
+                maximize(l, window);
+            }
+            if visible {
+                l.window(window).visible = true;
+                if focused {
+                    l.focus(window);
+                }
+            }
+            Ok(window)
+        });
+        report(result, ptr::null_mut())
+    }
+
+    pub unsafe fn glfwDestroyWindow(window: *mut GLFWwindow) {
+        if !window.is_null() {
+            lib(|l| l.free_window(window));
+        }
+    }
+
+    pub unsafe fn glfwWindowShouldClose(window: *mut GLFWwindow) -> c_int {
+        lib(|l| l.window(window).should_close as c_int)
+    }
+
+    pub unsafe fn glfwSetWindowShouldClose(window: *mut GLFWwindow, value: c_int) {
+        lib(|l| l.window(window).should_close = value != GLFW_FALSE);
+    }
+
+    pub unsafe fn glfwSetWindowTitle(window: *mut GLFWwindow, title: *const c_char) {
+        let title = CStr::from_ptr(title).to_string_lossy().into_owned();
+        lib(|l| l.window(window).title = title);
+    }
+
+    pub unsafe fn glfwSetWindowIcon(window: *mut GLFWwindow, count: c_int, images: *const GLFWimage) {
+        if count < 0 || (count > 0 && images.is_null()) {
+            return input_error(GLFW_INVALID_VALUE, "Invalid image count for window icon")
+        }
+        lib(|l| l.window(window).icon_count = count);
+    }
+
+    pub unsafe fn glfwGetWindowPos(window: *mut GLFWwindow, xpos: *mut c_int, ypos: *mut c_int) {
+        let (x, y) = lib(|l| l.window(window).pos);
+        *xpos = x;
+        *ypos = y;
+    }
+
+    pub unsafe fn glfwSetWindowPos(window: *mut GLFWwindow, xpos: c_int, ypos: c_int) {
+        lib(|l| l.move_window(window, xpos, ypos));
+    }
+
+    pub unsafe fn glfwGetWindowSize(window: *mut GLFWwindow, width: *mut c_int, height: *mut c_int) {
+        let (w, h) = lib(|l| l.window(window).size);
+        *width = w;
+        *height = h;
+    }
+
+    pub unsafe fn glfwSetWindowSizeLimits(window: *mut GLFWwindow, minwidth: c_int, minheight: c_int, maxwidth: c_int, maxheight: c_int) {
+        let invalid = |min: c_int, max: c_int| {
+            (min != GLFW_DONT_CARE && min < 0) ||
+                (max != GLFW_DONT_CARE && (max < 0 || (min != GLFW_DONT_CARE && max < min)))
+        };
+        if invalid(minwidth, maxwidth) || invalid(minheight, maxheight) {
+            return input_error(GLFW_INVALID_VALUE, &format!(
+                "Invalid window size limits {}x{} to {}x{}", minwidth, minheight, maxwidth, maxheight
+            ))
+        }
+        lib(|l| {
+            l.window(window).size_limits = (minwidth, minheight, maxwidth, maxheight);
+            let (w, h) = l.window(window).size;
+            l.resize_window(window, w, h);
+        })
+    }
+
+    pub unsafe fn glfwSetWindowAspectRatio(window: *mut GLFWwindow, numer: c_int, denom: c_int) {
+        if (numer != GLFW_DONT_CARE || denom != GLFW_DONT_CARE) && (numer <= 0 || denom <= 0) {
+            return input_error(GLFW_INVALID_VALUE,
+                    &format!("Invalid window aspect ratio {}:{}", numer, denom))
+        }
+        lib(|l| l.window(window).aspect_ratio = (numer, denom));
+    }
+
+    pub unsafe fn glfwSetWindowSize(window: *mut GLFWwindow, width: c_int, height: c_int) {
+        lib(|l| l.resize_window(window, width, height));
+    }
+
+    pub unsafe fn glfwGetFramebufferSize(window: *mut GLFWwindow, width: *mut c_int, height: *mut c_int) {
+        let ((w, h), scale) = lib(|l| {
+            let w = l.window(window);
+            (w.size, w.framebuffer_scale)
+        });
+        *width = w * scale;
+        *height = h * scale;
+    }
+
+    pub unsafe fn glfwGetWindowFrameSize(window: *mut GLFWwindow, left: *mut c_int, top: *mut c_int, right: *mut c_int, bottom: *mut c_int) {
+        let (l, t, r, b) = lib(|l| {
+            let w = l.window(window);
+            if w.decorated && w.monitor.is_null() { FRAME_SIZE } else { (0, 0, 0, 0) }
+        });
+        if !left.is_null() { *left = l }
+        if !top.is_null() { *top = t }
+        if !right.is_null() { *right = r }
+        if !bottom.is_null() { *bottom = b }
+    }
+
+    pub unsafe fn glfwGetWindowContentScale(window: *mut GLFWwindow, xscale: *mut c_float, yscale: *mut c_float) {
+        let (x, y) = lib(|l| l.window(window).content_scale);
+        *xscale = x;
+        *yscale = y;
+    }
+
+    pub unsafe fn glfwGetWindowOpacity(window: *mut GLFWwindow) -> c_float {
+        lib(|l| l.window(window).opacity)
+    }
+
+    pub unsafe fn glfwSetWindowOpacity(window: *mut GLFWwindow, opacity: c_float) {
+        if !(0.0..=1.0).contains(&opacity) {
+            return input_error(GLFW_INVALID_VALUE,
+                    &format!("Invalid window opacity {}", opacity))
+        }
+        lib(|l| l.window(window).opacity = opacity);
+    }
+
+    pub unsafe fn glfwIconifyWindow(window: *mut GLFWwindow) {
+        lib(|l| {
+            let w = l.window(window);
+            if !w.iconified {
+                w.iconified = true;
+                l.queue(window, WindowEvent::Iconify(true));
+            }
+        })
+    }
+
+    pub unsafe fn glfwRestoreWindow(window: *mut GLFWwindow) {
+        lib(|l| {
+            let w = l.window(window);
+            if w.iconified {
+                w.iconified = false;
+                l.queue(window, WindowEvent::Iconify(false));
+            } else if w.maximized {
+                w.maximized = false;
+                let (pos, size) = w.restore;
+                l.queue(window, WindowEvent::Maximize(false));
+                l.set_pos(window, pos);
+                l.set_size(window, size);
+            }
+        })
+    }
+
+    fn maximize(l: &mut Library, window: *mut GLFWwindow) {
+        let (x, y, width, height) = l.primary_workarea();
+        let w = l.window(window);
+        if w.maximized || !w.monitor.is_null() {
+            return
+        }
+        w.maximized = true;
+        w.restore = (w.pos, w.size);
+        let (left, top, right, bottom) = if w.decorated { FRAME_SIZE } else { (0, 0, 0, 0) };
+        l.queue(window, WindowEvent::Maximize(true));
+        l.set_pos(window, (x + left, y + top));
+        l.set_size(window, (width - left - right, height - top - bottom));
+    }
+
+    pub unsafe fn glfwMaximizeWindow(window: *mut GLFWwindow) {
+        lib(|l| maximize(l, window));
+    }
+
+    pub unsafe fn glfwShowWindow(window: *mut GLFWwindow) {
+        lib(|l| {
+            let w = l.window(window);
+            if !w.monitor.is_null() || w.visible {
+                return
+            }
+            w.visible = true;
+            if w.focus_on_show {
+                l.focus(window);
+            }
+        })
+    }
+
+    pub unsafe fn glfwHideWindow(window: *mut GLFWwindow) {
+        lib(|l| {
+            let w = l.window(window);
+            if !w.monitor.is_null() {
+                return
+            }
+            w.visible = false;
+            l.set_focus(window, false);
+        })
+    }
+
+    pub unsafe fn glfwFocusWindow(window: *mut GLFWwindow) {
+        lib(|l| l.focus(window));
+    }
+
+    pub unsafe fn glfwRequestWindowAttention(window: *mut GLFWwindow) {
+        lib(|l| { l.window(window); });
+    }
+
+    pub unsafe fn glfwGetWindowMonitor(window: *mut GLFWwindow) -> *mut GLFWmonitor {
+        lib(|l| l.window(window).monitor)
+    }
+
+    pub unsafe fn glfwSetWindowMonitor(window: *mut GLFWwindow, monitor: *mut GLFWmonitor, xpos: c_int, ypos: c_int, width: c_int, height: c_int, refreshRate: c_int) {
+        if width <= 0 || height <= 0 {
+            return input_error(GLFW_INVALID_VALUE,
+                    &format!("Invalid window size {}x{}", width, height))
+        }
+        if refreshRate < 0 && refreshRate != GLFW_DONT_CARE {
+            return input_error(GLFW_INVALID_VALUE,
+                    &format!("Invalid refresh rate {}", refreshRate))
+        }
+        lib(|l| {
+            let pos = if monitor.is_null() {
+                (xpos, ypos)
+            } else {
+                l.monitor(monitor).config.position
+            };
+            let scale = l.content_scale(monitor);
+            let w = l.window(window);
+            w.monitor = monitor;
+            w.maximized = false;
+            if w.content_scale != scale {
+                w.content_scale = scale;
+                l.queue(window, WindowEvent::ContentScale(scale.0, scale.1));
+            }
+            l.set_pos(window, pos);
+            l.set_size(window, (width, height));
+        })
+    }
+
+    pub unsafe fn glfwGetWindowAttrib(window: *mut GLFWwindow, attrib: c_int) -> c_int {
+        let result = lib(|l| {
+            let w = l.window(window);
+            Ok(match attrib {
+                GLFW_FOCUSED => w.focused as c_int,
+                GLFW_ICONIFIED => w.iconified as c_int,
+                GLFW_MAXIMIZED => w.maximized as c_int,
+                GLFW_HOVERED => w.hovered as c_int,
+                GLFW_VISIBLE => w.visible as c_int,
+                GLFW_RESIZABLE => w.resizable as c_int,
+                GLFW_DECORATED => w.decorated as c_int,
+                GLFW_AUTO_ICONIFY => w.auto_iconify as c_int,
+                GLFW_FLOATING => w.floating as c_int,
+                GLFW_TRANSPARENT_FRAMEBUFFER => w.transparent as c_int,
+                GLFW_FOCUS_ON_SHOW => w.focus_on_show as c_int,
+                GLFW_CLIENT_API => w.client_api,
+                GLFW_CONTEXT_CREATION_API => w.creation_api,
+                GLFW_CONTEXT_VERSION_MAJOR => w.version.0,
+                GLFW_CONTEXT_VERSION_MINOR => w.version.1,
+                GLFW_CONTEXT_REVISION => w.version.2,
+                GLFW_CONTEXT_ROBUSTNESS => w.robustness,
+                GLFW_OPENGL_FORWARD_COMPAT => w.forward_compat as c_int,
+                GLFW_OPENGL_DEBUG_CONTEXT => w.debug as c_int,
+                GLFW_OPENGL_PROFILE => w.profile,
+                GLFW_CONTEXT_RELEASE_BEHAVIOR => w.release_behavior,
+                GLFW_CONTEXT_NO_ERROR => w.no_error as c_int,
+                _ => return fail(GLFW_INVALID_ENUM, &format!("Invalid window attribute 0x{:08X}", attrib))
+            })
+        });
+        report(result, 0)
+    }
+
+    pub unsafe fn glfwSetWindowAttrib(window: *mut GLFWwindow, attrib: c_int, value: c_int) {
+        let result = lib(|l| {
+            let w = l.window(window);
+            let value = value != GLFW_FALSE;
+            match attrib {
+                GLFW_RESIZABLE => w.resizable = value,
+                GLFW_DECORATED => w.decorated = value,
+                GLFW_FLOATING => w.floating = value,
+                GLFW_AUTO_ICONIFY => w.auto_iconify = value,
+                GLFW_FOCUS_ON_SHOW => w.focus_on_show = value,
+                _ => return fail(GLFW_INVALID_ENUM, &format!("Invalid window attribute 0x{:08X}", attrib))
+            }
+            Ok(())
+        });
+        report(result, ())
+    }
+
+    pub unsafe fn glfwSetWindowUserPointer(window: *mut GLFWwindow, pointer: *mut c_void) {
+        lib(|l| l.window(window).user_pointer = pointer);
+    }
+
+    pub unsafe fn glfwGetWindowUserPointer(window: *mut GLFWwindow) -> *mut c_void {
+        lib(|l| l.window(window).user_pointer)
+    }
+
+    macro_rules! callback_setters {
+        ($($name:ident: $field:ident: $ty:ty;)*) => {
+            $(pub unsafe fn $name(window: *mut GLFWwindow, cbfun: Option<$ty>) -> Option<$ty> {
+                lib(|l| mem::replace(&mut l.window(window).callbacks.$field, cbfun))
+            })*
+        }
+    }
+
+    callback_setters! {
+        glfwSetWindowPosCallback: pos: GLFWwindowposfun;
+        glfwSetWindowSizeCallback: size: GLFWwindowsizefun;
+        glfwSetWindowCloseCallback: close: GLFWwindowclosefun;
+        glfwSetWindowRefreshCallback: refresh: GLFWwindowrefreshfun;
+        glfwSetWindowFocusCallback: focus: GLFWwindowfocusfun;
+        glfwSetWindowIconifyCallback: iconify: GLFWwindowiconifyfun;
+        glfwSetWindowMaximizeCallback: maximize: GLFWwindowmaximizefun;
+        glfwSetFramebufferSizeCallback: framebuffer_size: GLFWframebuffersizefun;
+        glfwSetWindowContentScaleCallback: content_scale: GLFWwindowcontentscalefun;
+        glfwSetKeyCallback: key: GLFWkeyfun;
+        glfwSetCharCallback: character: GLFWcharfun;
+        glfwSetCharModsCallback: char_mods: GLFWcharmodsfun;
+        glfwSetMouseButtonCallback: mouse_button: GLFWmousebuttonfun;
+        glfwSetCursorPosCallback: cursor_pos: GLFWcursorposfun;
+        glfwSetCursorEnterCallback: cursor_enter: GLFWcursorenterfun;
+        glfwSetScrollCallback: scroll: GLFWscrollfun;
+        glfwSetDropCallback: drop: GLFWdropfun;
+    }
+
+    pub unsafe fn glfwPollEvents() {
+        // Events queued by the callbacks are left for the next call, like events arriving from the
+        // window system while GLFW is processing
+        let pending: Vec<_> = lib(|l| l.pending.drain(..).collect());
+        for event in pending {
+            deliver(event);
+        }
+    }
+
+    pub unsafe fn glfwWaitEvents() {
+        glfwPollEvents();
+    }
+
+    pub unsafe fn glfwWaitEventsTimeout(timeout: c_double) {
+        if !(timeout >= 0.0 && timeout.is_finite()) {
+            return input_error(GLFW_INVALID_VALUE, &format!("Invalid time {}", timeout))
+        }
+        glfwPollEvents();
+    }
+
+    pub unsafe fn glfwPostEmptyEvent() {
+        lib(|_| ());
+    }
+
+    pub unsafe fn glfwGetInputMode(window: *mut GLFWwindow, mode: c_int) -> c_int {
+        let result = lib(|l| {
+            let w = l.window(window);
+            Ok(match mode {
+                GLFW_CURSOR => w.cursor_mode,
+                GLFW_STICKY_KEYS => w.sticky_keys as c_int,
+                GLFW_STICKY_MOUSE_BUTTONS => w.sticky_mouse_buttons as c_int,
+                GLFW_LOCK_KEY_MODS => w.lock_key_mods as c_int,
+                GLFW_RAW_MOUSE_MOTION => w.raw_mouse_motion as c_int,
+                _ => return fail(GLFW_INVALID_ENUM, &format!("Invalid input mode 0x{:08X}", mode))
+            })
+        });
+        report(result, 0)
+    }
+
+    pub unsafe fn glfwSetInputMode(window: *mut GLFWwindow, mode: c_int, value: c_int) {
+        let result = lib(|l| {
+            let w = l.window(window);
+            let flag = value != GLFW_FALSE;
+            match mode {
+                GLFW_CURSOR => match value {
+                    GLFW_CURSOR_NORMAL | GLFW_CURSOR_HIDDEN | GLFW_CURSOR_DISABLED =>
+                            w.cursor_mode = value,
+                    _ => return fail(GLFW_INVALID_ENUM,
+                            &format!("Invalid cursor mode 0x{:08X}", value))
+                },
+                GLFW_STICKY_KEYS => {
+                    if !flag {
+                        for k in w.keys.iter_mut().filter(|k| **k == STICK) {
+                            *k = GLFW_RELEASE as c_char;
+                        }
+                    }
+                    w.sticky_keys = flag;
+                }
+                GLFW_STICKY_MOUSE_BUTTONS => {
+                    if !flag {
+                        for b in w.mouse_buttons.iter_mut().filter(|b| **b == STICK) {
+                            *b = GLFW_RELEASE as c_char;
+                        }
+                    }
+                    w.sticky_mouse_buttons = flag;
+                }
+                GLFW_LOCK_KEY_MODS => w.lock_key_mods = flag,
+                GLFW_RAW_MOUSE_MOTION => w.raw_mouse_motion = flag,
+                _ => return fail(GLFW_INVALID_ENUM, &format!("Invalid input mode 0x{:08X}", mode))
+            }
+            Ok(())
+        });
+        report(result, ())
+    }
+
+    pub unsafe fn glfwRawMouseMotionSupported() -> c_int {
+        GLFW_TRUE
+    }
+
+    pub unsafe fn glfwGetKeyName(key: c_int, scancode: c_int) -> *const c_char {
+        let key = if key != GLFW_KEY_UNKNOWN { key } else { key_from_scancode(scancode) };
+        lib(|l| match key_name(key) {
+            Some(name) => {
+                l.key_name = CString::new(name).unwrap();
+                l.key_name.as_ptr()
+            }
+            None => ptr::null()
+        })
+    }
+
+    pub unsafe fn glfwGetKeyScancode(key: c_int) -> c_int {
+        match KeyCode::from_i32(key) {
+            Some(key) => scancode(key),
+            None => {
+                input_error(GLFW_INVALID_ENUM, &format!("Invalid key {}", key));
+                -1
+            }
+        }
+    }
+
+    pub unsafe fn glfwGetKey(window: *mut GLFWwindow, key: c_int) -> c_int {
+        lib(|l| {
+            let state = &mut l.window(window).keys[key as usize];
+            if *state == STICK {
+                *state = GLFW_RELEASE as c_char;
+                GLFW_PRESS
+            } else {
+                *state as c_int
+            }
+        })
+    }
+
+    pub unsafe fn glfwGetMouseButton(window: *mut GLFWwindow, button: c_int) -> c_int {
+        lib(|l| {
+            let state = &mut l.window(window).mouse_buttons[button as usize];
+            if *state == STICK {
+                *state = GLFW_RELEASE as c_char;
+                GLFW_PRESS
+            } else {
+                *state as c_int
+            }
+        })
+    }
+
+    pub unsafe fn glfwGetCursorPos(window: *mut GLFWwindow, xpos: *mut c_double, ypos: *mut c_double) {
+        let (x, y) = lib(|l| l.window(window).cursor_pos);
+        if !xpos.is_null() { *xpos = x }
+        if !ypos.is_null() { *ypos = y }
+    }
+
+    pub unsafe fn glfwSetCursorPos(window: *mut GLFWwindow, xpos: c_double, ypos: c_double) {
+        if !xpos.is_finite() || !ypos.is_finite() {
+            return input_error(GLFW_INVALID_VALUE,
+                    &format!("Invalid cursor position {} {}", xpos, ypos))
+        }
+        lib(|l| l.window(window).cursor_pos = (xpos, ypos));
+    }
+
+    pub unsafe fn glfwCreateCursor(image: *const GLFWimage, _xhot: c_int, _yhot: c_int) -> *mut GLFWcursor {
+        let image = &*image;
+        if image.width <= 0 || image.height <= 0 {
+            input_error(GLFW_INVALID_VALUE, &format!(
+                "Invalid image dimensions for cursor {}x{}", image.width, image.height
+            ));
+            return ptr::null_mut()
+        }
+        lib(|l| {
+            let cursor = Box::into_raw(Box::new(CursorState { shape: 0 }));
+            l.cursors.push(cursor);
+            cursor as *mut GLFWcursor
+        })
+    }
+
+    pub unsafe fn glfwCreateStandardCursor(shape: c_int) -> *mut GLFWcursor {
+        if !(GLFW_ARROW_CURSOR..=GLFW_VRESIZE_CURSOR).contains(&shape) {
+            input_error(GLFW_INVALID_ENUM, &format!("Invalid standard cursor 0x{:08X}", shape));
+            return ptr::null_mut()
+        }
+        lib(|l| {
+            let cursor = Box::into_raw(Box::new(CursorState { shape }));
+            l.cursors.push(cursor);
+            cursor as *mut GLFWcursor
+        })
+    }
+
+    pub unsafe fn glfwDestroyCursor(cursor: *mut GLFWcursor) {
+        if cursor.is_null() {
+            return
+        }
+        lib(|l| {
+            assert!(l.cursors.contains(&(cursor as *mut _)), "use of an invalid GLFWcursor");
+            l.cursors.retain(|&c| c as *mut GLFWcursor != cursor);
+            for &w in &l.windows {
+                let w = &mut *w;
+                if w.cursor == cursor {
+                    w.cursor = ptr::null_mut();
+                }
+            }
+            drop(Box::from_raw(cursor as *mut CursorState));
+        })
+    }
+
+    pub unsafe fn glfwSetCursor(window: *mut GLFWwindow, cursor: *mut GLFWcursor) {
+        lib(|l| {
+            assert!(
+                cursor.is_null() || l.cursors.contains(&(cursor as *mut _)),
+                "use of an invalid GLFWcursor"
+            );
+            l.window(window).cursor = cursor;
+        })
+    }
+
+    /// Reports the disconnection of an unplugged joystick, as GLFW does when polling it.
+    fn poll_joystick(jid: c_int) {
+        if lib(|l| l.joystick(jid).is_some_and(|js| js.unplugged)) {
+            deliver(Pending::JoystickDisconnected(jid));
+        }
+    }
+
+    pub unsafe fn glfwJoystickPresent(jid: c_int) -> c_int {
+        poll_joystick(jid);
+        lib(|l| l.joystick(jid).is_some() as c_int)
+    }
+
+    pub unsafe fn glfwGetJoystickAxes(jid: c_int, count: *mut c_int) -> *const c_float {
+        poll_joystick(jid);
+        lib(|l| match l.joystick(jid) {
+            Some(js) => {
+                *count = js.config.axes.len() as c_int;
+                js.config.axes.as_ptr()
+            }
+            None => {
+                *count = 0;
+                ptr::null()
+            }
+        })
+    }
+
+    pub unsafe fn glfwGetJoystickButtons(jid: c_int, count: *mut c_int) -> *const c_uchar {
+        poll_joystick(jid);
+        lib(|l| {
+            let hat_buttons = l.joystick_hat_buttons;
+            match l.joystick(jid) {
+                Some(js) => {
+                    let buttons = if hat_buttons { &js.hat_buttons } else { &js.buttons };
+                    *count = buttons.len() as c_int;
+                    buttons.as_ptr()
+                }
+                None => {
+                    *count = 0;
+                    ptr::null()
+                }
+            }
+        })
+    }
+
+    pub unsafe fn glfwGetJoystickHats(jid: c_int, count: *mut c_int) -> *const c_uchar {
+        poll_joystick(jid);
+        lib(|l| match l.joystick(jid) {
+            Some(js) => {
+                *count = js.hats.len() as c_int;
+                js.hats.as_ptr()
+            }
+            None => {
+                *count = 0;
+                ptr::null()
+            }
+        })
+    }
+
+    pub unsafe fn glfwGetJoystickName(jid: c_int) -> *const c_char {
+        lib(|l| l.joystick(jid).map_or(ptr::null(), |js| js.name.as_ptr()))
+    }
+
+    pub unsafe fn glfwGetJoystickGUID(jid: c_int) -> *const c_char {
+        lib(|l| l.joystick(jid).map_or(ptr::null(), |js| js.guid.as_ptr()))
+    }
+
+    pub unsafe fn glfwSetJoystickUserPointer(jid: c_int, pointer: *mut c_void) {
+        lib(|l| if let Some(js) = l.joysticks[jid as usize].as_mut() {
+            js.user_pointer = pointer;
+        })
+    }
+
+    pub unsafe fn glfwGetJoystickUserPointer(jid: c_int) -> *mut c_void {
+        lib(|l| l.joysticks[jid as usize].as_ref().map_or(ptr::null_mut(), |js| js.user_pointer))
+    }
+
+    pub unsafe fn glfwJoystickIsGamepad(jid: c_int) -> c_int {
+        lib(|l| l.is_gamepad(jid) as c_int)
+    }
+
+    pub unsafe fn glfwSetJoystickCallback(cbfun: Option<GLFWjoystickfun>) -> Option<GLFWjoystickfun> {
+        lib(|l| mem::replace(&mut l.joystick_callback, cbfun))
+    }
+
+    pub unsafe fn glfwUpdateGamepadMappings(string: *const c_char) -> c_int {
+        let string = CStr::from_ptr(string).to_string_lossy().into_owned();
+        let mut result = GLFW_TRUE;
+        for line in string.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+            match parse_mapping(line) {
+                Ok(Some((guid, name))) => {
+                    lib(|l| l.mappings.insert(guid, CString::new(name).unwrap()));
+                }
+                Ok(None) => {}
+                Err((code, description)) => {
+                    input_error(code, &description);
+                    result = GLFW_FALSE;
+                }
+            }
+        }
+        result
+    }
+
+    pub unsafe fn glfwGetGamepadName(jid: c_int) -> *const c_char {
+        lib(|l| {
+            if !l.is_gamepad(jid) {
+                return ptr::null()
+            }
+            let js = l.joysticks[jid as usize].as_ref().unwrap();
+            match l.mappings.get(&js.config.guid) {
+                Some(name) => name.as_ptr(),
+                None => js.name.as_ptr()
+            }
+        })
+    }
+
+    pub unsafe fn glfwGetGamepadState(jid: c_int, state: *mut GLFWgamepadstate) -> c_int {
+        poll_joystick(jid);
+        lib(|l| {
+            if !l.is_gamepad(jid) {
+                return GLFW_FALSE
+            }
+            let js = l.joysticks[jid as usize].as_ref().unwrap();
+            *state = js.config.gamepad.as_ref().map_or_else(Default::default, gamepad_to_raw);
+            GLFW_TRUE
+        })
+    }
+
+    pub unsafe fn glfwSetClipboardString(_window: *mut GLFWwindow, string: *const c_char) {
+        let string = CStr::from_ptr(string).to_owned();
+        lib(|l| l.clipboard = Some(string));
+    }
+
+    pub unsafe fn glfwGetClipboardString(_window: *mut GLFWwindow) -> *const c_char {
+        let clipboard = lib(|l| l.clipboard.as_ref().map(|c| c.as_ptr()));
+        clipboard.unwrap_or_else(|| {
+            input_error(GLFW_FORMAT_UNAVAILABLE, "Failed to convert selection to string");
+            ptr::null()
+        })
+    }
+
+    pub unsafe fn glfwGetTime() -> c_double {
+        lib(|l| l.time)
+    }
+
+    pub unsafe fn glfwSetTime(time: c_double) {
+        if !(0.0..=18446744073.0).contains(&time) {
+            return input_error(GLFW_INVALID_VALUE, &format!("Invalid time {}", time))
+        }
+        lib(|l| l.time = time);
+    }
+
+    pub unsafe fn glfwGetTimerValue() -> u64 {
+        lib(|l| (l.time * 1_000_000.0) as u64)
+    }
+
+    pub unsafe fn glfwGetTimerFrequency() -> u64 {
+        1_000_000
+    }
+
+    pub unsafe fn glfwMakeContextCurrent(window: *mut GLFWwindow) {
+        let result = lib(|l| {
+            if !window.is_null() && l.window(window).client_api == GLFW_NO_API {
+                return fail(GLFW_NO_WINDOW_CONTEXT,
+                        "Cannot make current with a window that has no OpenGL or OpenGL ES context")
+            }
+            l.current_context = window;
+            Ok(())
+        });
+        report(result, ())
+    }
+
+    #[allow(unused)]
+    pub unsafe fn glfwGetCurrentContext() -> *mut GLFWwindow {
+        lib(|l| l.current_context)
+    }
+
+    pub unsafe fn glfwSwapBuffers(window: *mut GLFWwindow) {
+        if lib(|l| l.window(window).client_api == GLFW_NO_API) {
+            input_error(GLFW_NO_WINDOW_CONTEXT,
+                    "Cannot swap buffers of a window that has no OpenGL or OpenGL ES context");
+        }
+    }
+
+    fn require_context() -> bool {
+        let current = lib(|l| !l.current_context.is_null());
+        if !current {
+            input_error(GLFW_NO_CURRENT_CONTEXT, "No context is current for this thread");
+        }
+        current
+    }
+
+    pub unsafe fn glfwSwapInterval(_interval: c_int) {
+        require_context();
+    }
+
+    pub unsafe fn glfwExtensionSupported(_extension: *const c_char) -> c_int {
+        require_context();
+        GLFW_FALSE
+    }
+
+    pub unsafe fn glfwGetProcAddress(_procname: *const c_char) -> GLFWglproc {
+        require_context();
+        ptr::null()
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub mod vulkan {
+        use super::*;
+        use ffi::vulkan::*;
+
+        fn loader_missing() -> Failure {
+            (GLFW_API_UNAVAILABLE, "Vulkan: Loader not found".to_owned())
+        }
+
+        extern "system" fn vk_get_instance_proc_addr(
+            instance: VkInstance,
+            name: *const c_char
+        ) -> GLFWvkproc {
+            unsafe { glfwGetInstanceProcAddress(instance, name) }
+        }
+
+        extern "system" fn vk_create_instance(
+            _create_info: *const c_void,
+            _allocator: *const VkAllocationCallbacks,
+            _instance: *mut VkInstance
+        ) -> VkResult {
+            // VK_ERROR_INITIALIZATION_FAILED; there is no driver behind the mock loader
+            -3
+        }
+
+        extern "system" fn vk_destroy_surface(
+            _instance: VkInstance,
+            surface: VkSurfaceKHR,
+            _allocator: *const VkAllocationCallbacks
+        ) {
+            lib(|l| {
+                assert!(l.surfaces.contains(&surface), "use of an invalid VkSurfaceKHR");
+                l.surfaces.retain(|&s| s != surface);
+            })
+        }
+
+        pub unsafe fn glfwVulkanSupported() -> c_int {
+            lib(|l| l.vulkan_supported as c_int)
+        }
+
+        pub unsafe fn glfwGetRequiredInstanceExtensions(count: *mut u32) -> *const *const c_char {
+            *count = 0;
+            let result = lib(|l| {
+                if !l.vulkan_supported {
+                    return Err(loader_missing())
+                }
+                *count = l.extension_ptrs.len() as u32;
+                Ok(l.extension_ptrs.as_ptr())
+            });
+            report(result, ptr::null())
+        }
+
+        /// Like a real loader, only global commands can be resolved without an instance.
+        pub unsafe fn glfwGetInstanceProcAddress(instance: VkInstance, procname: *const c_char) -> GLFWvkproc {
+            let name = CStr::from_ptr(procname).to_bytes();
+            let result = lib(|l| {
+                if !l.vulkan_supported {
+                    return Err(loader_missing())
+                }
+                Ok(match name {
+                    b"vkGetInstanceProcAddr" => vk_get_instance_proc_addr as GLFWvkproc,
+                    b"vkCreateInstance" => vk_create_instance as GLFWvkproc,
+                    b"vkDestroySurfaceKHR" if !instance.is_null() => vk_destroy_surface as GLFWvkproc,
+                    _ => ptr::null()
+                })
+            });
+            report(result, ptr::null())
+        }
+
+        fn extension_missing() -> Failure {
+            (GLFW_API_UNAVAILABLE, "Vulkan: Instance missing the required surface extension".to_owned())
+        }
+
+        pub unsafe fn glfwGetPhysicalDevicePresentationSupport(instance: VkInstance, _device: VkPhysicalDevice, _queuefamily: u32) -> c_int {
+            let result = lib(|l| {
+                if !l.vulkan_supported {
+                    return Err(loader_missing())
+                }
+                if instance.is_null() {
+                    return Err(extension_missing())
+                }
+                Ok(GLFW_TRUE)
+            });
+            report(result, GLFW_FALSE)
+        }
+
+        pub unsafe fn glfwCreateWindowSurface(instance: VkInstance, window: *mut GLFWwindow, _allocator: *const VkAllocationCallbacks, surface: *mut VkSurfaceKHR) -> VkResult {
+            *surface = 0;
+            let result = lib(|l| {
+                if !l.vulkan_supported {
+                    return Err(loader_missing())
+                }
+                if instance.is_null() {
+                    return Err(extension_missing())
+                }
+                if l.window(window).client_api != GLFW_NO_API {
+                    return fail(GLFW_INVALID_VALUE, "Vulkan: Window surface creation requires the window to have the client API set to GLFW_NO_API")
+                }
+                let handle = l.next_surface;
+                l.next_surface += 1;
+                l.surfaces.push(handle);
+                *surface = handle;
+                Ok(VK_SUCCESS)
+            });
+            // VK_ERROR_EXTENSION_NOT_PRESENT or VK_ERROR_NATIVE_WINDOW_IN_USE_KHR
+            let code = match result {
+                Err((GLFW_API_UNAVAILABLE, _)) => -7,
+                _ => -1000000001
+            };
+            report(result, code)
+        }
+    }
+}
@@ -3,9 +3,11 @@ use std::rc::{ Rc, Weak };
 use std::cell::{ Cell, RefCell };
 use std::ffi::CStr;
 use std::slice;
+use std::ptr;
+use std::any::Any;
 
 use ffi;
-use get_error;
+use get_error_in;
 use Result;
 use util::*;
 
@@ -21,7 +23,31 @@ thread_local! {
             = RefCell::new(Vec::new());
 }
 
+/// Takes the user data out of the monitor user pointer, leaving it null.
+/// 
+/// The user pointer holds a `Box<Rc<dyn Any>>`. Data is handed out as `Rc`s since a monitor can be
+/// disconnected during any event processing call, while the user may still be using the data.
+fn take_monitor_user_data(ptr: *mut ffi::GLFWmonitor) -> Option<Rc<dyn Any>> {
+    unsafe {
+        let data = ffi::glfwGetMonitorUserPointer(ptr) as *mut Rc<dyn Any>;
+        if data.is_null() {
+            None
+        } else {
+            ffi::glfwSetMonitorUserPointer(ptr, ptr::null_mut());
+            Some(*Box::from_raw(data))
+        }
+    }
+}
+
 pub(crate) fn invalidate_all_monitors() {
+    let raw = unsafe {
+        let mut count = 0;
+        let ptr = ffi::glfwGetMonitors(&mut count);
+        if ptr.is_null() { &[] } else { slice::from_raw_parts(ptr, count as usize) }
+    };
+    for &monitor in raw {
+        take_monitor_user_data(monitor);
+    }
     MONITORS.with(|v| {
         let mut v = v.borrow_mut();
         for cell in v.drain(..).filter_map(|w| w.upgrade()) {
@@ -31,6 +57,7 @@ pub(crate) fn invalidate_all_monitors() {
 }
 
 pub(crate) fn invalidate_monitor(ptr: *mut ffi::GLFWmonitor) {
+    take_monitor_user_data(ptr);
     MONITORS.with(|v| {
         let mut v = v.borrow_mut();
         v.retain(|w| match w.upgrade() {
@@ -51,6 +78,16 @@ pub(crate) fn invalidate_monitor(ptr: *mut ffi::GLFWmonitor) {
     })
 }
 
+/// Returns the connected monitors, primary first.
+pub(crate) fn connected_monitors() -> Vec<Monitor> {
+    let raw = unsafe {
+        let mut count = 0;
+        let ptr = ffi::glfwGetMonitors(&mut count);
+        if ptr.is_null() { &[] } else { slice::from_raw_parts(ptr, count as usize) }
+    };
+    raw.iter().map(|&p| Monitor::create_from(p)).collect()
+}
+
 pub type VideoMode = ffi::GLFWvidmode;
 
 pub struct DisconnectedMonitor(pub(crate) *mut ffi::GLFWmonitor);
@@ -102,13 +139,48 @@ impl Monitor {
         self.0.get().and_then(|p1| other.0.get().map(|p2| p1 == p2)).unwrap_or(false)
     }
 
+    /// Attaches a value to this monitor, replacing any previous value.
+    /// 
+    /// The value is shared by every `Monitor` referring to the same monitor, and is released when
+    /// the monitor is disconnected, before the [`MonitorDisconnected`] event is delivered, or when
+    /// GLFW is terminated. Clones of the `Rc` returned by [`user_data()`] keep it alive past that
+    /// point.
+    /// 
+    /// This is backed by the GLFW monitor user pointer.
+    /// 
+    /// [`MonitorDisconnected`]: enum.Event.html#variant.MonitorDisconnected
+    /// [`user_data()`]: #method.user_data
+    pub fn set_user_data<T: 'static>(&self, data: T) {
+        let ptr = self.get_ptr();
+        take_monitor_user_data(ptr);
+        let data: Box<Rc<dyn Any>> = Box::new(Rc::new(data));
+        unsafe { ffi::glfwSetMonitorUserPointer(ptr, Box::into_raw(data) as *mut _) };
+    }
+
+    /// Removes the value attached with [`set_user_data()`].
+    /// 
+    /// [`set_user_data()`]: #method.set_user_data
+    pub fn clear_user_data(&self) {
+        take_monitor_user_data(self.get_ptr());
+    }
+
+    /// Retrieves the value attached with [`set_user_data()`], if it is of type `T`.
+    /// 
+    /// [`set_user_data()`]: #method.set_user_data
+    pub fn user_data<T: 'static>(&self) -> Option<Rc<T>> {
+        unsafe {
+            let data = ffi::glfwGetMonitorUserPointer(self.get_ptr()) as *const Rc<dyn Any>;
+            data.as_ref().and_then(|d| d.clone().downcast().ok())
+        }
+    }
+
     /// [GLFW Reference][glfw]
     /// 
     /// [glfw]: http://www.glfw.org/docs/3.3/group__monitor.html#ga102f54e7acc9149edbcf0997152df8c9
     pub fn get_pos(&self) -> Result<(i32, i32)> {
         let mut p = (0, 0);
         unsafe { ffi::glfwGetMonitorPos(self.get_ptr(), &mut p.0, &mut p.1) };
-        get_error().map(|_| p)
+        get_error_in("Monitor::get_pos").map(|_| p)
     }
 
     /// [GLFW Reference][glfw]
@@ -124,7 +196,7 @@ impl Monitor {
         unsafe {
             ffi::glfwGetMonitorWorkarea(self.get_ptr(), xpos.ptr(), ypos.ptr(), width.ptr(), height.ptr());
         }
-        get_error()
+        get_error_in("Monitor::get_workarea")
     }
 
     /// [GLFW Reference][glfw]
@@ -142,7 +214,7 @@ impl Monitor {
     pub fn get_content_scale(&self) -> Result<(f32, f32)> {
         let mut s = (0.0, 0.0);
         unsafe { ffi::glfwGetMonitorContentScale(self.get_ptr(), &mut s.0, &mut s.1) };
-        get_error().map(|_| s)
+        get_error_in("Monitor::get_content_scale").map(|_| s)
     }
 
     /// [GLFW Reference][glfw]
@@ -162,7 +234,7 @@ impl Monitor {
             let ptr = ffi::glfwGetVideoModes(self.get_ptr(), &mut count);
             slice::from_raw_parts(ptr, count as usize)
         };
-        get_error().map(|_| raw.into())
+        get_error_in("Monitor::get_video_modes").map(|_| raw.into())
     }
 
     /// [GLFW Reference][glfw]
@@ -170,7 +242,7 @@ impl Monitor {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__monitor.html#gafc1bb972a921ad5b3bd5d63a95fc2d52
     pub fn get_video_mode(&self) -> Result<VideoMode> {
         let mode = unsafe { ffi::glfwGetVideoMode(self.get_ptr()) };
-        get_error().map(|_| unsafe { assert!(!mode.is_null()); *mode })
+        get_error_in("Monitor::get_video_mode").map(|_| unsafe { assert!(!mode.is_null()); *mode })
     }
 
     /// [GLFW Reference][glfw]
@@ -178,7 +250,7 @@ impl Monitor {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__monitor.html#ga6ac582625c990220785ddd34efa3169a
     pub fn set_gamma(&self, gamma: f32) -> Result<()> {
         unsafe { ffi::glfwSetGamma(self.get_ptr(), gamma) };
-        get_error()
+        get_error_in("Monitor::set_gamma")
     }
 
     /// [GLFW Reference][glfw]
@@ -186,7 +258,7 @@ impl Monitor {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__monitor.html#gab7c41deb2219bde3e1eb756ddaa9ec80
     pub fn get_gamma_ramp(&self) -> Result<GammaRamp> {
         let ramp = unsafe { ffi::glfwGetGammaRamp(self.get_ptr()) };
-        get_error().map(|_| {
+        get_error_in("Monitor::get_gamma_ramp").map(|_| {
             assert!(!ramp.is_null());
             let ramp = unsafe { &*ramp };
             let (red, green, blue) = unsafe {(
@@ -219,7 +291,7 @@ impl Monitor {
             };
             ffi::glfwSetGammaRamp(self.get_ptr(), &ramp);
         }
-        get_error()
+        get_error_in("Monitor::set_gamma_ramp")
     }
 }
 
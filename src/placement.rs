@@ -0,0 +1,311 @@
+use FullscreenMode;
+use Monitor;
+use Result;
+use VideoMode;
+use Window;
+use WindowAttribute;
+use monitor::connected_monitors;
+
+/// How far cascaded windows are offset from each other when they have no title bar.
+const CASCADE_OFFSET: i32 = 24;
+
+/// The position and size of a window, and the state it is in, for saving across sessions.
+///
+/// With the `serde` feature, this can be serialized to any format supported by serde.
+///
+/// ```no_run
+/// # let glfw = glfw_wrapper::init(Default::default()).unwrap();
+/// # let window = glfw.create_window(&Default::default(), 640, 480, "", None, None).unwrap();
+/// # fn load() -> Option<glfw_wrapper::WindowPlacement> { None }
+/// # fn save(_: glfw_wrapper::WindowPlacement) {}
+/// if let Some(placement) = load() {
+///     window.set_placement(&placement).unwrap();
+/// }
+/// // ...
+/// save(window.get_placement().unwrap());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WindowPlacement {
+    /// The position of the client area in windowed mode.
+    pub position: (i32, i32),
+    /// The size of the client area in windowed mode.
+    pub size: (i32, i32),
+    /// Whether the window is maximized in windowed mode. GLFW only reports the maximized position
+    /// and size, so those are what the window is restored to when un-maximized.
+    pub maximized: bool,
+    pub iconified: bool,
+    pub fullscreen: Option<FullscreenPlacement>,
+    /// The content scale of the window, for applications scaling their contents themselves.
+    /// This is not used when restoring the placement.
+    pub content_scale: (f32, f32)
+}
+
+/// The fullscreen mode of a saved [`WindowPlacement`].
+///
+/// [`WindowPlacement`]: struct.WindowPlacement.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FullscreenPlacement {
+    /// The name of the monitor, as returned by [`Monitor::get_name()`].
+    ///
+    /// [`Monitor::get_name()`]: struct.Monitor.html#method.get_name
+    pub monitor_name: String,
+    /// The position of the monitor, which tells apart monitors with the same name.
+    pub monitor_position: (i32, i32),
+    /// The `(width, height, refresh_rate)` of exclusive fullscreen, or `None` for borderless
+    /// fullscreen.
+    pub exclusive_mode: Option<(i32, i32, i32)>
+}
+
+impl<'a> Window<'a> {
+    /// Captures the placement of the window. For fullscreen windows, the position and size are
+    /// the ones the window returns to in windowed mode.
+    pub fn get_placement(&self) -> Result<WindowPlacement> {
+        let windowed = self.data().windowed.get();
+        let (position, size, maximized) = match windowed {
+            Some(state) => (state.pos, state.size, state.maximized),
+            None => (self.get_pos()?, self.get_window_size()?,
+                    self.get_attribute(WindowAttribute::Maximized)?)
+        };
+        let fullscreen = match self.get_monitor() {
+            Some(monitor) => {
+                let mode = monitor.get_video_mode()?;
+                Some(fullscreen_placement(&monitor,
+                        Some((mode.width, mode.height, mode.refresh_rate)))?)
+            }
+            None if windowed.is_some() => match self.current_monitor()? {
+                Some(monitor) => Some(fullscreen_placement(&monitor, None)?),
+                None => None
+            },
+            None => None
+        };
+        Ok(WindowPlacement {
+            position,
+            size,
+            maximized,
+            iconified: self.get_attribute(WindowAttribute::Iconified)?,
+            fullscreen,
+            content_scale: self.get_content_scale()?
+        })
+    }
+
+    /// Restores a placement captured with [`get_placement()`].
+    ///
+    /// The window is moved and shrunk as needed for its frame to fit in the work area of the
+    /// connected monitor it overlaps the most, or of the primary monitor if it overlaps none, so
+    /// it never ends up off-screen. If the monitor the window was fullscreen on is no longer
+    /// connected, the window is left in windowed mode.
+    ///
+    /// [`get_placement()`]: #method.get_placement
+    pub fn set_placement(&self, placement: &WindowPlacement) -> Result<()> {
+        self.set_fullscreen(FullscreenMode::Windowed)?;
+        if self.get_monitor().is_none() && (self.get_attribute(WindowAttribute::Maximized)? ||
+                self.get_attribute(WindowAttribute::Iconified)?) {
+            self.restore()?;
+        }
+
+        let mut monitors = connected_monitors();
+        let mut areas = Vec::with_capacity(monitors.len());
+        let mut fullscreen_monitor = None;
+        for (i, monitor) in monitors.iter().enumerate() {
+            areas.push(workarea(monitor)?);
+            if let Some(ref fullscreen) = placement.fullscreen {
+                if monitor.get_name() == fullscreen.monitor_name &&
+                        monitor.get_pos()? == fullscreen.monitor_position {
+                    fullscreen_monitor = Some(i);
+                }
+            }
+        }
+
+        // The placement may come from a file, so any values have to be handled without overflow
+        let (left, top, right, bottom) = self.frame_size()?;
+        let (x, y) = placement.position;
+        let (width, height) = placement.size;
+        let outer = (
+            x.saturating_sub(left),
+            y.saturating_sub(top),
+            width.max(1).saturating_add(left + right),
+            height.max(1).saturating_add(top + bottom)
+        );
+        let (x, y, width, height) = match best_area(outer, &areas) {
+            Some(area) => clamp_rect(outer, area),
+            None => outer
+        };
+        self.set_monitor(None, x + left, y + top, (width - left - right).max(1),
+                (height - top - bottom).max(1), None)?;
+        if placement.maximized {
+            self.maximize()?;
+        }
+
+        if let (Some(i), Some(fullscreen)) = (fullscreen_monitor, placement.fullscreen.as_ref()) {
+            let monitor = monitors.swap_remove(i);
+            match fullscreen.exclusive_mode {
+                Some((width, height, refresh_rate)) => {
+                    let mode = VideoMode {
+                        width,
+                        height,
+                        refresh_rate,
+                        ..monitor.get_video_mode()?
+                    };
+                    self.set_fullscreen(FullscreenMode::Exclusive(monitor, mode))?;
+                }
+                None => self.set_fullscreen(FullscreenMode::Borderless(monitor))?
+            }
+        }
+        if placement.iconified {
+            self.iconify()?;
+        }
+        Ok(())
+    }
+
+    /// Centers the window frame in the work area of `monitor`. If the frame is larger than the
+    /// work area, it is aligned to the top left corner instead, keeping the title bar visible.
+    pub fn center_on_monitor(&self, monitor: &Monitor) -> Result<()> {
+        let area = workarea(monitor)?;
+        let (_, _, width, height) = self.outer_rect()?;
+        let x = area.0 + (area.2 - width).max(0) / 2;
+        let y = area.1 + (area.3 - height).max(0) / 2;
+        self.set_outer_pos(x, y)
+    }
+
+    /// Centers the window frame over the frame of `parent`, as for dialogs, keeping it within
+    /// the work area of the monitor it ends up on.
+    pub fn center_over(&self, parent: &Window) -> Result<()> {
+        let (px, py, pwidth, pheight) = parent.outer_rect()?;
+        let (_, _, width, height) = self.outer_rect()?;
+        let rect = (px + (pwidth - width) / 2, py + (pheight - height) / 2, width, height);
+        let (x, y) = keep_on_screen(rect)?;
+        self.set_outer_pos(x, y)
+    }
+
+    /// Places the window frame below and to the right of the frame of `previous`, offset by the
+    /// height of its title bar, as when opening several documents. The window starts over at the
+    /// top left corner of the work area when it would not fit.
+    pub fn cascade_from(&self, previous: &Window) -> Result<()> {
+        let (_, top, _, _) = previous.frame_size()?;
+        let offset = if top > 0 { top } else { CASCADE_OFFSET };
+        let (px, py, _, _) = previous.outer_rect()?;
+        let (_, _, width, height) = self.outer_rect()?;
+        let rect = (px + offset, py + offset, width, height);
+        let areas = workareas()?;
+        let (x, y) = match best_area(rect, &areas) {
+            Some(area) if rect.0 + width > area.0 + area.2 || rect.1 + height > area.1 + area.3 =>
+                    (area.0, area.1),
+            _ => (rect.0, rect.1)
+        };
+        self.set_outer_pos(x, y)
+    }
+
+    /// Moves the window frame flush against the edges of the work area it is on that it is
+    /// within `distance` of, returning whether it was moved. This is meant to be called when the
+    /// window is moved.
+    pub fn snap_to_edges(&self, distance: i32) -> Result<bool> {
+        let rect = self.outer_rect()?;
+        let area = match best_area(rect, &workareas()?) {
+            Some(area) => area,
+            None => return Ok(false)
+        };
+        let snap = |pos: i32, size: i32, start: i32, length: i32| {
+            if (pos - start).abs() <= distance {
+                start
+            } else if (start + length - pos - size).abs() <= distance {
+                start + length - size
+            } else {
+                pos
+            }
+        };
+        let x = snap(rect.0, rect.2, area.0, area.2);
+        let y = snap(rect.1, rect.3, area.1, area.3);
+        if (x, y) == (rect.0, rect.1) {
+            return Ok(false);
+        }
+        self.set_outer_pos(x, y)?;
+        Ok(true)
+    }
+
+    /// Returns the size of the frame as `(left, top, right, bottom)`.
+    fn frame_size(&self) -> Result<(i32, i32, i32, i32)> {
+        let mut f = (0, 0, 0, 0);
+        self.get_frame_size(Some(&mut f.0), Some(&mut f.1), Some(&mut f.2), Some(&mut f.3))?;
+        Ok(f)
+    }
+
+    /// Returns the position and size of the window including its frame.
+    fn outer_rect(&self) -> Result<(i32, i32, i32, i32)> {
+        let (left, top, right, bottom) = self.frame_size()?;
+        let (x, y) = self.get_pos()?;
+        let (width, height) = self.get_window_size()?;
+        Ok((x - left, y - top, width + left + right, height + top + bottom))
+    }
+
+    /// Moves the window so its frame starts at `(x, y)`.
+    fn set_outer_pos(&self, x: i32, y: i32) -> Result<()> {
+        let (left, top, _, _) = self.frame_size()?;
+        self.set_pos(x + left, y + top)
+    }
+}
+
+fn fullscreen_placement(monitor: &Monitor, exclusive_mode: Option<(i32, i32, i32)>)
+        -> Result<FullscreenPlacement> {
+    Ok(FullscreenPlacement {
+        monitor_name: monitor.get_name(),
+        monitor_position: monitor.get_pos()?,
+        exclusive_mode
+    })
+}
+
+/// Returns the work area of a monitor as `(x, y, width, height)`.
+pub(crate) fn workarea(monitor: &Monitor) -> Result<(i32, i32, i32, i32)> {
+    let mut a = (0, 0, 0, 0);
+    monitor.get_workarea(Some(&mut a.0), Some(&mut a.1), Some(&mut a.2), Some(&mut a.3))?;
+    Ok(a)
+}
+
+/// Returns the work areas of the connected monitors, primary first.
+fn workareas() -> Result<Vec<(i32, i32, i32, i32)>> {
+    connected_monitors().iter().map(workarea).collect()
+}
+
+/// Returns the position `rect` has to be moved to for it to be within the work area it overlaps
+/// the most.
+fn keep_on_screen(rect: (i32, i32, i32, i32)) -> Result<(i32, i32)> {
+    Ok(match best_area(rect, &workareas()?) {
+        Some(area) => {
+            let (x, y, _, _) = clamp_rect(rect, area);
+            (x, y)
+        }
+        None => (rect.0, rect.1)
+    })
+}
+
+/// Returns the area of the intersection of two `(x, y, width, height)` rects, computed in `i64`
+/// so that it does not overflow for any rects.
+pub(crate) fn overlap(a: (i32, i32, i32, i32), b: (i32, i32, i32, i32)) -> i64 {
+    let span = |start: i32, length: i32, other_start: i32, other_length: i32| {
+        let end = i64::from(start) + i64::from(length);
+        let other_end = i64::from(other_start) + i64::from(other_length);
+        end.min(other_end) - i64::from(start.max(other_start))
+    };
+    let width = span(a.0, a.2, b.0, b.2);
+    let height = span(a.1, a.3, b.1, b.3);
+    if width > 0 && height > 0 { width * height } else { 0 }
+}
+
+/// Returns the area `rect` overlaps the most, or the first one if it overlaps none.
+fn best_area(rect: (i32, i32, i32, i32), areas: &[(i32, i32, i32, i32)])
+        -> Option<(i32, i32, i32, i32)> {
+    areas.iter().cloned().fold(None, |best: Option<(i32, i32, i32, i32)>, area| match best {
+        Some(best) if overlap(rect, best) >= overlap(rect, area) => Some(best),
+        _ => Some(area)
+    })
+}
+
+/// Moves and shrinks `rect` to fit in `area`.
+fn clamp_rect(rect: (i32, i32, i32, i32), area: (i32, i32, i32, i32)) -> (i32, i32, i32, i32) {
+    let width = rect.2.min(area.2);
+    let height = rect.3.min(area.3);
+    let x = rect.0.max(area.0).min(area.0 + area.2 - width);
+    let y = rect.1.max(area.1).min(area.1 + area.3 - height);
+    (x, y, width, height)
+}
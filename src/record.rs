@@ -0,0 +1,598 @@
+use std::collections::{ HashMap, VecDeque };
+use std::error;
+use std::io::{ self, Read, Write };
+use std::path::PathBuf;
+use std::char::from_u32;
+
+use enum_primitive::FromPrimitive;
+
+use Event;
+use OwnedEvent;
+use WindowId;
+use SharedGlfw;
+use Key;
+use KeyCode;
+use MouseButton;
+use Modifiers;
+use Joystick;
+use JoystickHatState;
+use GamepadButton;
+use GamepadAxis;
+use ffi;
+
+const MAGIC: &[u8; 8] = b"GLFWREC\0";
+const FORMAT_VERSION: u16 = 1;
+
+/// Writes events to a compact binary stream for later replay by an [`EventPlayer`].
+///
+/// Each event is stored with the time it was recorded at, relative to the creation of the
+/// recorder. Windows are stored as small indices assigned in the order they first appear in the
+/// recording, since [`WindowId`]s are not the same from one run to the next.
+///
+/// As event handlers cannot return errors, the first I/O error is kept and reported by
+/// [`finish()`]; nothing more is written after it.
+///
+/// ```no_run
+/// # let glfw = glfw_wrapper::init(Default::default()).unwrap();
+/// # let file = std::fs::File::create("input.rec").unwrap();
+/// let mut recorder = glfw_wrapper::EventRecorder::new(&glfw, file).unwrap();
+/// loop {
+///     glfw.poll_events(&mut recorder.wrap(&glfw, &mut |event| {
+///         // handle the event as usual
+///     })).unwrap();
+///     # break;
+/// }
+/// recorder.finish().unwrap();
+/// ```
+///
+/// [`EventPlayer`]: struct.EventPlayer.html
+/// [`WindowId`]: struct.WindowId.html
+/// [`finish()`]: #method.finish
+pub struct EventRecorder<W: Write> {
+    writer: W,
+    start: f64,
+    windows: HashMap<WindowId, u32>,
+    error: Option<io::Error>
+}
+
+impl<W: Write> EventRecorder<W> {
+    /// Creates a recorder writing to `writer`, starting the clock at the current GLFW time.
+    pub fn new(glfw: &SharedGlfw, mut writer: W) -> io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        Ok(EventRecorder {
+            writer,
+            start: glfw.get_time(),
+            windows: HashMap::new(),
+            error: None
+        })
+    }
+
+    /// Returns the index the window is stored as, assigning the next one if the window has not
+    /// appeared in the recording yet.
+    ///
+    /// Calling this for the windows of the program before recording any events fixes their
+    /// indices, regardless of which one receives an event first.
+    pub fn window_index(&mut self, id: WindowId) -> u32 {
+        let next = self.windows.len() as u32;
+        *self.windows.entry(id).or_insert(next)
+    }
+
+    /// Records an event at the given GLFW time.
+    pub fn record(&mut self, time: f64, event: &OwnedEvent) {
+        if self.error.is_some() {
+            return;
+        }
+        let window = event.window_id().map(|id| self.window_index(id));
+        let mut buf = vec![];
+        write_f64(&mut buf, time - self.start);
+        encode(&mut buf, event, window);
+        if let Err(e) = self.writer.write_all(&buf) {
+            self.error = Some(e);
+        }
+    }
+
+    /// Wraps an event handler so every event passed to it is recorded first, timestamped with
+    /// [`SharedGlfw::get_time()`].
+    ///
+    /// [`SharedGlfw::get_time()`]: struct.SharedGlfw.html#method.get_time
+    pub fn wrap<'r>(&'r mut self, glfw: &'r SharedGlfw, handler: &'r mut dyn FnMut(Event))
+            -> impl FnMut(Event) + 'r {
+        move |event| {
+            self.record(glfw.get_time(), &OwnedEvent::from(&event));
+            handler(event);
+        }
+    }
+
+    /// Returns the first error encountered while writing, if any.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Flushes the writer and returns it, or the first error encountered while recording.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Replays events written by an [`EventRecorder`].
+///
+/// Events are replayed at the same times relative to the start of playback as they were recorded
+/// at relative to the start of recording, so calling [`play_until()`] once per frame spreads them
+/// over frames like the original input. Recorded windows must be assigned to windows of the
+/// running program with [`map_window()`]; events about unassigned windows are skipped.
+///
+/// [`EventRecorder`]: struct.EventRecorder.html
+/// [`play_until()`]: #method.play_until
+/// [`map_window()`]: #method.map_window
+pub struct EventPlayer {
+    records: VecDeque<Record>,
+    windows: HashMap<u32, WindowId>
+}
+
+struct Record {
+    time: f64,
+    window: Option<u32>,
+    event: OwnedEvent
+}
+
+impl EventPlayer {
+    /// Reads a whole recording.
+    ///
+    /// Returns an `InvalidData` error if the data is not a recording in a supported format.
+    pub fn new<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not an event recording"));
+        }
+        let version = read_u16(&mut reader)?;
+        if version != FORMAT_VERSION {
+            return Err(invalid_data(format!("unsupported recording version {}", version)));
+        }
+
+        let mut records = VecDeque::new();
+        loop {
+            // A clean end of stream can only occur between records
+            let mut time = [0; 8];
+            match reader.read(&mut time[..1])? {
+                0 => break,
+                _ => reader.read_exact(&mut time[1..])?
+            }
+            let time = f64::from_le_bytes(time);
+            let (window, event) = decode(&mut reader)?;
+            records.push_back(Record { time, window, event });
+        }
+        Ok(EventPlayer {
+            records,
+            windows: HashMap::new()
+        })
+    }
+
+    /// Assigns a window of the running program to the window stored with the given index.
+    ///
+    /// See [`EventRecorder::window_index()`] for how indices are assigned.
+    ///
+    /// [`EventRecorder::window_index()`]: struct.EventRecorder.html#method.window_index
+    pub fn map_window(&mut self, index: u32, window: WindowId) {
+        self.windows.insert(index, window);
+    }
+
+    /// Passes every remaining event recorded at or before `time` seconds into the recording to
+    /// `handler`, in order.
+    pub fn play_until(&mut self, time: f64, handler: &mut dyn FnMut(OwnedEvent)) {
+        while let Some(record) = self.next_due(time) {
+            handler(record);
+        }
+    }
+
+    /// Like [`play_until()`], but delivers the events through [`Glfw::inject_event()`] so
+    /// `handler` receives them exactly like events from [`Glfw::poll_events()`].
+    ///
+    /// Monitor events cannot be injected and are skipped, as are events about windows that no
+    /// longer exist.
+    ///
+    /// Only available with the `event-injection` feature.
+    ///
+    /// [`play_until()`]: #method.play_until
+    /// [`Glfw::inject_event()`]: struct.Glfw.html#method.inject_event
+    /// [`Glfw::poll_events()`]: struct.Glfw.html#method.poll_events
+    #[cfg(feature = "event-injection")]
+    pub fn inject_until(&mut self, glfw: &::Glfw, time: f64, handler: &mut dyn FnMut(Event))
+            -> ::Result<()> {
+        while let Some(event) = self.next_due(time) {
+            match event {
+                OwnedEvent::MonitorConnected(_) | OwnedEvent::MonitorDisconnected(_) => continue,
+                _ => {}
+            }
+            if event.window_id().is_none_or(|id| glfw.window_exists(id)) {
+                glfw.inject_event(&event, handler)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether every event of the recording has been played.
+    pub fn is_finished(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Returns the time of the last event of the recording.
+    pub fn duration(&self) -> f64 {
+        self.records.back().map_or(0.0, |r| r.time)
+    }
+
+    fn next_due(&mut self, time: f64) -> Option<OwnedEvent> {
+        while self.records.front().is_some_and(|r| r.time <= time) {
+            let mut record = self.records.pop_front().unwrap();
+            match record.window {
+                None => return Some(record.event),
+                Some(index) => if let Some(&id) = self.windows.get(&index) {
+                    set_window_id(&mut record.event, id);
+                    return Some(record.event);
+                }
+            }
+        }
+        None
+    }
+}
+
+fn invalid_data<E: Into<Box<dyn error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn set_window_id(event: &mut OwnedEvent, id: WindowId) {
+    use OwnedEvent::*;
+    match *event {
+        MonitorConnected(_) | MonitorDisconnected(_) |
+        JoystickConnected(_) | JoystickDisconnected(_) |
+        GamepadButtonDown { .. } | GamepadButtonUp { .. } | GamepadAxisMoved { .. } |
+        JoystickButtonDown { .. } | JoystickButtonUp { .. } | JoystickAxisMoved { .. } |
+        JoystickHatChanged { .. } => {}
+        WindowPosition { ref mut win, .. } | WindowResize { ref mut win, .. } |
+        FramebufferSizeChanged { ref mut win, .. } | ContentScaleChanged { ref mut win, .. } |
+        MouseButtonDown { ref mut win, .. } | MouseButtonUp { ref mut win, .. } |
+        CursorMoved { ref mut win, .. } | Scroll { ref mut win, .. } |
+        KeyDown { ref mut win, .. } | KeyUp { ref mut win, .. } | KeyRepeat { ref mut win, .. } |
+        CharTypedWithModifiers { ref mut win, .. } => *win = id,
+        WindowCloseRequested(ref mut win) | WindowRefresh(ref mut win) |
+        WindowFocused(ref mut win) | WindowDefocused(ref mut win) |
+        WindowIconified(ref mut win) | WindowDeiconified(ref mut win) |
+        WindowMaximized(ref mut win) | WindowDemaximized(ref mut win) |
+        CursorEntered(ref mut win) | CursorExited(ref mut win) |
+        CharTyped(ref mut win, _) | FileDrop(ref mut win, _) => *win = id
+    }
+}
+
+// Record layout, all little endian: time (f64), tag (u8), window index (u32) for window events,
+// then the remaining fields of the event in declaration order.
+
+fn write_f64(buf: &mut Vec<u8>, v: f64) {
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+fn write_i32(buf: &mut Vec<u8>, v: i32) {
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+fn write_u32(buf: &mut Vec<u8>, v: u32) {
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+fn write_str(buf: &mut Vec<u8>, s: &str) {
+    write_u32(buf, s.len() as u32);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn write_key(buf: &mut Vec<u8>, key: Key, scancode: i32, modifiers: Modifiers) {
+    write_i32(buf, match key {
+        Key::Named(code) => code as i32,
+        Key::Unnamed(_) => ffi::GLFW_KEY_UNKNOWN
+    });
+    write_i32(buf, scancode);
+    buf.push(modifiers.bits() as u8);
+}
+
+fn encode(buf: &mut Vec<u8>, event: &OwnedEvent, window: Option<u32>) {
+    use OwnedEvent::*;
+    buf.push(match *event {
+        MonitorConnected(_) => 0,
+        MonitorDisconnected(_) => 1,
+        JoystickConnected(_) => 2,
+        JoystickDisconnected(_) => 3,
+        WindowPosition { .. } => 4,
+        WindowResize { .. } => 5,
+        WindowCloseRequested(_) => 6,
+        WindowRefresh(_) => 7,
+        WindowFocused(_) => 8,
+        WindowDefocused(_) => 9,
+        WindowIconified(_) => 10,
+        WindowDeiconified(_) => 11,
+        WindowMaximized(_) => 12,
+        WindowDemaximized(_) => 13,
+        FramebufferSizeChanged { .. } => 14,
+        ContentScaleChanged { .. } => 15,
+        MouseButtonDown { .. } => 16,
+        MouseButtonUp { .. } => 17,
+        CursorMoved { .. } => 18,
+        CursorEntered(_) => 19,
+        CursorExited(_) => 20,
+        Scroll { .. } => 21,
+        KeyDown { .. } => 22,
+        KeyUp { .. } => 23,
+        KeyRepeat { .. } => 24,
+        CharTyped(..) => 25,
+        CharTypedWithModifiers { .. } => 26,
+        FileDrop(..) => 27,
+        GamepadButtonDown { .. } => 28,
+        GamepadButtonUp { .. } => 29,
+        GamepadAxisMoved { .. } => 30,
+        JoystickButtonDown { .. } => 31,
+        JoystickButtonUp { .. } => 32,
+        JoystickAxisMoved { .. } => 33,
+        JoystickHatChanged { .. } => 34
+    });
+    if let Some(index) = window {
+        write_u32(buf, index);
+    }
+    match *event {
+        MonitorConnected(ref name) | MonitorDisconnected(ref name) => write_str(buf, name),
+        JoystickConnected(j) | JoystickDisconnected(j) => buf.push(j as u8),
+        WindowPosition { x, y, .. } => {
+            write_i32(buf, x);
+            write_i32(buf, y);
+        }
+        WindowResize { width, height, .. } | FramebufferSizeChanged { width, height, .. } => {
+            write_i32(buf, width);
+            write_i32(buf, height);
+        }
+        WindowCloseRequested(_) | WindowRefresh(_) | WindowFocused(_) | WindowDefocused(_) |
+        WindowIconified(_) | WindowDeiconified(_) | WindowMaximized(_) | WindowDemaximized(_) |
+        CursorEntered(_) | CursorExited(_) => {}
+        ContentScaleChanged { x_scale, y_scale, .. } => {
+            buf.extend_from_slice(&x_scale.to_le_bytes());
+            buf.extend_from_slice(&y_scale.to_le_bytes());
+        }
+        MouseButtonDown { button, modifiers, .. } | MouseButtonUp { button, modifiers, .. } => {
+            buf.push(button as u8);
+            buf.push(modifiers.bits() as u8);
+        }
+        CursorMoved { x, y, .. } => {
+            write_f64(buf, x);
+            write_f64(buf, y);
+        }
+        Scroll { x_offset, y_offset, .. } => {
+            write_f64(buf, x_offset);
+            write_f64(buf, y_offset);
+        }
+        KeyDown { key, scancode, modifiers, .. } | KeyUp { key, scancode, modifiers, .. } |
+        KeyRepeat { key, scancode, modifiers, .. } => write_key(buf, key, scancode, modifiers),
+        CharTyped(_, c) => write_u32(buf, c as u32),
+        CharTypedWithModifiers { character, modifiers, .. } => {
+            write_u32(buf, character as u32);
+            buf.push(modifiers.bits() as u8);
+        }
+        FileDrop(_, ref paths) => {
+            write_u32(buf, paths.len() as u32);
+            for path in paths {
+                write_str(buf, &path.to_string_lossy());
+            }
+        }
+        GamepadButtonDown { joystick, button } | GamepadButtonUp { joystick, button } => {
+            buf.push(joystick as u8);
+            buf.push(button as u8);
+        }
+        GamepadAxisMoved { joystick, axis, value } => {
+            buf.push(joystick as u8);
+            buf.push(axis as u8);
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+        JoystickButtonDown { joystick, button } | JoystickButtonUp { joystick, button } => {
+            buf.push(joystick as u8);
+            write_u32(buf, button as u32);
+        }
+        JoystickAxisMoved { joystick, axis, value } => {
+            buf.push(joystick as u8);
+            write_u32(buf, axis as u32);
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+        JoystickHatChanged { joystick, hat, state } => {
+            buf.push(joystick as u8);
+            write_u32(buf, hat as u32);
+            buf.push(state.bits());
+        }
+    }
+}
+
+fn read_u8<R: Read>(r: &mut R) -> io::Result<u8> {
+    let mut b = [0; 1];
+    r.read_exact(&mut b)?;
+    Ok(b[0])
+}
+
+fn read_u16<R: Read>(r: &mut R) -> io::Result<u16> {
+    let mut b = [0; 2];
+    r.read_exact(&mut b)?;
+    Ok(u16::from_le_bytes(b))
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut b = [0; 4];
+    r.read_exact(&mut b)?;
+    Ok(u32::from_le_bytes(b))
+}
+
+fn read_i32<R: Read>(r: &mut R) -> io::Result<i32> {
+    let mut b = [0; 4];
+    r.read_exact(&mut b)?;
+    Ok(i32::from_le_bytes(b))
+}
+
+fn read_f32<R: Read>(r: &mut R) -> io::Result<f32> {
+    let mut b = [0; 4];
+    r.read_exact(&mut b)?;
+    Ok(f32::from_le_bytes(b))
+}
+
+fn read_f64<R: Read>(r: &mut R) -> io::Result<f64> {
+    let mut b = [0; 8];
+    r.read_exact(&mut b)?;
+    Ok(f64::from_le_bytes(b))
+}
+
+fn read_string<R: Read>(r: &mut R) -> io::Result<String> {
+    let len = read_u32(r)?;
+    let mut bytes = vec![];
+    r.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len as usize {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(bytes).map_err(invalid_data)
+}
+
+fn read_char<R: Read>(r: &mut R) -> io::Result<char> {
+    from_u32(read_u32(r)?).ok_or_else(|| invalid_data("invalid character"))
+}
+
+fn read_modifiers<R: Read>(r: &mut R) -> io::Result<Modifiers> {
+    Modifiers::from_bits(read_u8(r)? as i32).ok_or_else(|| invalid_data("invalid modifiers"))
+}
+
+fn read_joystick<R: Read>(r: &mut R) -> io::Result<Joystick> {
+    Joystick::from_u8(read_u8(r)?).ok_or_else(|| invalid_data("invalid joystick"))
+}
+
+fn read_gamepad_button<R: Read>(r: &mut R) -> io::Result<GamepadButton> {
+    GamepadButton::from_u8(read_u8(r)?).ok_or_else(|| invalid_data("invalid gamepad button"))
+}
+
+fn read_gamepad_axis<R: Read>(r: &mut R) -> io::Result<GamepadAxis> {
+    GamepadAxis::from_u8(read_u8(r)?).ok_or_else(|| invalid_data("invalid gamepad axis"))
+}
+
+fn read_hat_state<R: Read>(r: &mut R) -> io::Result<JoystickHatState> {
+    JoystickHatState::from_bits(read_u8(r)?).ok_or_else(|| invalid_data("invalid hat state"))
+}
+
+fn read_mouse_button<R: Read>(r: &mut R) -> io::Result<MouseButton> {
+    MouseButton::from_u8(read_u8(r)?).ok_or_else(|| invalid_data("invalid mouse button"))
+}
+
+fn read_key<R: Read>(r: &mut R) -> io::Result<(Key, i32, Modifiers)> {
+    let code = read_i32(r)?;
+    let scancode = read_i32(r)?;
+    let key = if code == ffi::GLFW_KEY_UNKNOWN {
+        Key::Unnamed(scancode)
+    } else {
+        Key::Named(KeyCode::from_i32(code).ok_or_else(|| invalid_data("invalid key"))?)
+    };
+    Ok((key, scancode, read_modifiers(r)?))
+}
+
+/// Reads the tag and fields of a record. Window events are returned with a placeholder ID, to be
+/// replaced once the returned window index is mapped to a window.
+fn decode<R: Read>(r: &mut R) -> io::Result<(Option<u32>, OwnedEvent)> {
+    use OwnedEvent::*;
+    let tag = read_u8(r)?;
+    if tag > 34 {
+        return Err(invalid_data(format!("invalid event tag {}", tag)));
+    }
+    if !(4..=27).contains(&tag) {
+        let event = match tag {
+            0 => MonitorConnected(read_string(r)?),
+            1 => MonitorDisconnected(read_string(r)?),
+            2 => JoystickConnected(read_joystick(r)?),
+            3 => JoystickDisconnected(read_joystick(r)?),
+            28 => GamepadButtonDown {
+                joystick: read_joystick(r)?,
+                button: read_gamepad_button(r)?
+            },
+            29 => GamepadButtonUp {
+                joystick: read_joystick(r)?,
+                button: read_gamepad_button(r)?
+            },
+            30 => GamepadAxisMoved {
+                joystick: read_joystick(r)?,
+                axis: read_gamepad_axis(r)?,
+                value: read_f32(r)?
+            },
+            31 => JoystickButtonDown {
+                joystick: read_joystick(r)?,
+                button: read_u32(r)? as usize
+            },
+            32 => JoystickButtonUp {
+                joystick: read_joystick(r)?,
+                button: read_u32(r)? as usize
+            },
+            33 => JoystickAxisMoved {
+                joystick: read_joystick(r)?,
+                axis: read_u32(r)? as usize,
+                value: read_f32(r)?
+            },
+            _ => JoystickHatChanged {
+                joystick: read_joystick(r)?,
+                hat: read_u32(r)? as usize,
+                state: read_hat_state(r)?
+            }
+        };
+        return Ok((None, event));
+    }
+
+    let index = read_u32(r)?;
+    let win = WindowId::placeholder(index);
+    let event = match tag {
+        4 => WindowPosition { win, x: read_i32(r)?, y: read_i32(r)? },
+        5 => WindowResize { win, width: read_i32(r)?, height: read_i32(r)? },
+        6 => WindowCloseRequested(win),
+        7 => WindowRefresh(win),
+        8 => WindowFocused(win),
+        9 => WindowDefocused(win),
+        10 => WindowIconified(win),
+        11 => WindowDeiconified(win),
+        12 => WindowMaximized(win),
+        13 => WindowDemaximized(win),
+        14 => FramebufferSizeChanged { win, width: read_i32(r)?, height: read_i32(r)? },
+        15 => ContentScaleChanged { win, x_scale: read_f32(r)?, y_scale: read_f32(r)? },
+        16 => MouseButtonDown {
+            win,
+            button: read_mouse_button(r)?,
+            modifiers: read_modifiers(r)?
+        },
+        17 => MouseButtonUp {
+            win,
+            button: read_mouse_button(r)?,
+            modifiers: read_modifiers(r)?
+        },
+        18 => CursorMoved { win, x: read_f64(r)?, y: read_f64(r)? },
+        19 => CursorEntered(win),
+        20 => CursorExited(win),
+        21 => Scroll { win, x_offset: read_f64(r)?, y_offset: read_f64(r)? },
+        22..=24 => {
+            let (key, scancode, modifiers) = read_key(r)?;
+            match tag {
+                22 => KeyDown { win, key, scancode, modifiers },
+                23 => KeyUp { win, key, scancode, modifiers },
+                _ => KeyRepeat { win, key, scancode, modifiers }
+            }
+        }
+        25 => CharTyped(win, read_char(r)?),
+        26 => CharTypedWithModifiers {
+            win,
+            character: read_char(r)?,
+            modifiers: read_modifiers(r)?
+        },
+        _ => {
+            let count = read_u32(r)?;
+            let mut paths = vec![];
+            for _ in 0..count {
+                paths.push(PathBuf::from(read_string(r)?));
+            }
+            FileDrop(win, paths)
+        }
+    };
+    Ok((Some(index), event))
+}
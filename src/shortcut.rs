@@ -0,0 +1,121 @@
+use std::fmt;
+use std::str::FromStr;
+
+use Event;
+use Key;
+use Modifiers;
+use ParseNameError;
+
+/// Modifiers that are written differently in the Mac style, by the name `Modifiers` writes.
+const MAC_NAMES: [(&str, &str); 2] = [("Alt", "Option"), ("Super", "Cmd")];
+
+/// How a [`Shortcut`] is written.
+///
+/// [`Shortcut`]: struct.Shortcut.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ShortcutStyle {
+    /// `Ctrl`, `Alt`, `Shift` and `Super`.
+    Standard,
+    /// `Ctrl`, `Option`, `Shift` and `Cmd`.
+    Mac
+}
+
+impl ShortcutStyle {
+    /// Returns the style of the platform the program was compiled for.
+    pub fn native() -> ShortcutStyle {
+        if cfg!(target_os = "macos") {
+            ShortcutStyle::Mac
+        } else {
+            ShortcutStyle::Standard
+        }
+    }
+}
+
+/// A keyboard shortcut, like `Ctrl+Shift+S`.
+///
+/// Shortcuts are written as modifiers followed by a key, separated by `+`, with the modifiers and
+/// key written as [`Modifiers`] and [`Key`] write them, like `Ctrl+1` or `Ctrl+Shift+Space`.
+/// `Display` uses the [native style], and [`label()`] any style. Parsing accepts both styles as
+/// well as anything `Modifiers` and `Key` parse.
+///
+/// [`Modifiers`]: struct.Modifiers.html
+/// [`Key`]: enum.Key.html
+/// [native style]: enum.ShortcutStyle.html#method.native
+/// [`label()`]: #method.label
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Shortcut {
+    /// Only `CONTROL`, `ALT`, `SHIFT` and `SUPER` are meaningful in a shortcut.
+    pub modifiers: Modifiers,
+    pub key: Key
+}
+
+impl Shortcut {
+    pub fn new(modifiers: Modifiers, key: Key) -> Shortcut {
+        Shortcut { modifiers, key }
+    }
+
+    /// Returns whether the event is a press of this shortcut. Key repeats do not match.
+    ///
+    /// The modifiers must match exactly, except for `CAPSLOCK` and `NUMLOCK`, which are ignored
+    /// as they are only reported with [`LockKeyMods`] enabled.
+    ///
+    /// [`LockKeyMods`]: enum.InputMode.html#variant.LockKeyMods
+    pub fn matches(&self, event: &Event) -> bool {
+        match *event {
+            Event::KeyDown { key, modifiers, .. } => self.matches_key(key, modifiers),
+            _ => false
+        }
+    }
+
+    /// Returns whether a key and the modifiers held with it make up this shortcut, ignoring
+    /// `CAPSLOCK` and `NUMLOCK`.
+    pub fn matches_key(&self, key: Key, modifiers: Modifiers) -> bool {
+        self.key == key && self.modifiers == modifiers - (Modifiers::CAPSLOCK | Modifiers::NUMLOCK)
+    }
+
+    /// Returns the shortcut written in the given style, for display in menus.
+    pub fn label(&self, style: ShortcutStyle) -> String {
+        let modifiers = (self.modifiers - (Modifiers::CAPSLOCK | Modifiers::NUMLOCK)).to_string();
+        let mut label = String::new();
+        for name in modifiers.split('+').filter(|n| !n.is_empty()) {
+            let name = match style {
+                ShortcutStyle::Mac => MAC_NAMES.iter()
+                        .find(|&&(standard, _)| standard == name)
+                        .map_or(name, |&(_, mac)| mac),
+                ShortcutStyle::Standard => name
+            };
+            label.push_str(name);
+            label.push('+');
+        }
+        label.push_str(&self.key.to_string());
+        label
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.label(ShortcutStyle::native()))
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Shortcut, ParseNameError> {
+        let s = s.trim();
+        let (modifier_names, key_name) = match s.rfind('+') {
+            Some(i) => (&s[..i], s[i + 1..].trim()),
+            None => ("", s)
+        };
+
+        let mut modifiers = Modifiers::empty();
+        for name in modifier_names.split('+').map(str::trim) {
+            let modifier: Modifiers = name.parse()?;
+            if modifier.intersects(Modifiers::CAPSLOCK | Modifiers::NUMLOCK) {
+                return Err(ParseNameError::new("shortcut modifier", name));
+            }
+            modifiers |= modifier;
+        }
+        Ok(Shortcut { modifiers, key: key_name.parse()? })
+    }
+}
@@ -0,0 +1,187 @@
+use std::ffi::{ CStr, CString };
+use std::marker::PhantomData;
+use std::mem;
+use std::fmt;
+use std::ptr;
+use std::slice;
+
+use ffi;
+use get_error_in;
+use Error;
+use ErrorKind;
+use Result;
+use SharedGlfw;
+use SharedWindow;
+use util::*;
+
+pub use ffi::vulkan::{ VkInstance, VkPhysicalDevice, VkSurfaceKHR, VkResult };
+pub use ffi::vulkan::GLFWvkproc as VkProc;
+
+impl SharedGlfw {
+    /// [GLFW Reference][glfw]
+    /// 
+    /// [glfw]: http://www.glfw.org/docs/3.3/group__vulkan.html#ga2e7f30931e02464b5bc8d0d4b6f9fe2b
+    pub fn vulkan_supported(&self) -> bool {
+        cint_to_bool(unsafe { ffi::vulkan::glfwVulkanSupported() })
+    }
+
+    /// [GLFW Reference][glfw]
+    /// 
+    /// Returns an `ApiUnavailable` error if no Vulkan loader or ICD was found, or if the loader
+    /// does not support the surface extensions required by this platform.
+    /// 
+    /// [glfw]: http://www.glfw.org/docs/3.3/group__vulkan.html#ga1abcbe61033958f22f63ef82008874b1
+    pub fn get_required_instance_extensions(&self) -> Result<Vec<String>> {
+        let mut count = 0;
+        let ptr = unsafe { ffi::vulkan::glfwGetRequiredInstanceExtensions(&mut count) };
+        get_error_in("SharedGlfw::get_required_instance_extensions").map(|_| unsafe {
+            assert!(!ptr.is_null());
+            slice::from_raw_parts(ptr, count as usize).iter()
+                    .map(|p| CStr::from_ptr(*p).to_string_lossy().into_owned())
+                    .collect()
+        })
+    }
+
+    /// [GLFW Reference][glfw]
+    /// 
+    /// `instance` may be null when loading global functions such as `vkCreateInstance`.
+    /// 
+    /// # Safety
+    /// 
+    /// `instance` must be null or a valid Vulkan instance.
+    /// 
+    /// [glfw]: http://www.glfw.org/docs/3.3/group__vulkan.html#gadf228fac94c5fd8f12423ec9af9ff1e9
+    pub unsafe fn get_instance_proc_address(
+        &self,
+        instance: VkInstance,
+        proc_name: &str
+    ) -> Result<VkProc> {
+        let cstr = CString::new(proc_name).unwrap();
+        let proc = ffi::vulkan::glfwGetInstanceProcAddress(instance, cstr.as_ptr());
+        get_error_in("SharedGlfw::get_instance_proc_address").map(|_| proc)
+    }
+
+    /// [GLFW Reference][glfw]
+    /// 
+    /// # Safety
+    /// 
+    /// `instance` must be a valid Vulkan instance and `device` one of its physical devices.
+    /// 
+    /// [glfw]: http://www.glfw.org/docs/3.3/group__vulkan.html#gaff3823355cdd7e2f3f9f4d9ea9518d92
+    pub unsafe fn get_physical_device_presentation_support(
+        &self,
+        instance: VkInstance,
+        device: VkPhysicalDevice,
+        queue_family: u32
+    ) -> Result<bool> {
+        let v = ffi::vulkan::glfwGetPhysicalDevicePresentationSupport(
+            instance, device, queue_family
+        );
+        get_error_in("SharedGlfw::get_physical_device_presentation_support").map(|_| cint_to_bool(v))
+    }
+}
+
+impl SharedWindow {
+    /// [GLFW Reference][glfw]
+    /// 
+    /// The window should have been created with [`ClientApi::NoApi`]. The returned surface
+    /// borrows the window, so it is statically guaranteed to be destroyed before the window is.
+    /// 
+    /// Returns an `ApiUnavailable` error without creating a surface if `vkDestroySurfaceKHR`
+    /// cannot be loaded for `instance`.
+    /// 
+    /// # Safety
+    /// 
+    /// `instance` must be a valid Vulkan instance created with the extensions returned by
+    /// [`get_required_instance_extensions()`], and it must outlive the returned surface.
+    /// 
+    /// [glfw]: http://www.glfw.org/docs/3.3/group__vulkan.html#ga1a24536bec3f80b08ead18e28e6ae965
+    /// [`ClientApi::NoApi`]: enum.ClientApi.html#variant.NoApi
+    /// [`get_required_instance_extensions()`]: struct.SharedGlfw.html#method.get_required_instance_extensions
+    pub unsafe fn create_window_surface(&self, instance: VkInstance) -> Result<WindowSurface<'_>> {
+        // Resolved up front so that a surface is never created that could not be destroyed
+        let name = CString::new("vkDestroySurfaceKHR").unwrap();
+        let proc = ffi::vulkan::glfwGetInstanceProcAddress(instance, name.as_ptr());
+        get_error_in("SharedWindow::create_window_surface")?;
+        if proc.is_null() {
+            return Err(Error {
+                kind: ErrorKind::ApiUnavailable,
+                description: "vkDestroySurfaceKHR could not be loaded for the instance".to_owned(),
+                function: "SharedWindow::create_window_surface"
+            });
+        }
+        let destroy: ffi::vulkan::PFN_vkDestroySurfaceKHR = mem::transmute(proc);
+        let mut surface = 0;
+        let r = ffi::vulkan::glfwCreateWindowSurface(
+            instance, self.0, ptr::null(), &mut surface
+        );
+        get_error_in("SharedWindow::create_window_surface")?;
+        if r != ffi::vulkan::VK_SUCCESS {
+            return Err(vulkan_error(r, "SharedWindow::create_window_surface"));
+        }
+        Ok(WindowSurface {
+            handle: surface,
+            instance,
+            destroy,
+            _window: PhantomData
+        })
+    }
+}
+
+/// Converts the result of a failed Vulkan call that GLFW did not report an error for.
+fn vulkan_error(result: VkResult, function: &'static str) -> Error {
+    let kind = match result {
+        ffi::vulkan::VK_ERROR_OUT_OF_HOST_MEMORY | ffi::vulkan::VK_ERROR_OUT_OF_DEVICE_MEMORY =>
+                ErrorKind::OutOfMemory,
+        ffi::vulkan::VK_ERROR_EXTENSION_NOT_PRESENT => ErrorKind::ApiUnavailable,
+        _ => ErrorKind::PlatformError
+    };
+    Error {
+        kind,
+        description: format!("Vulkan call failed with VkResult {}", result),
+        function
+    }
+}
+
+/// A Vulkan surface for a window, created with [`create_window_surface()`].
+/// 
+/// The surface is destroyed with `vkDestroySurfaceKHR` when dropped. Use [`into_raw()`] to take
+/// over responsibility for destroying it, e.g. when handing it to a Vulkan wrapper library.
+/// 
+/// [`create_window_surface()`]: struct.SharedWindow.html#method.create_window_surface
+/// [`into_raw()`]: #method.into_raw
+pub struct WindowSurface<'a> {
+    handle: VkSurfaceKHR,
+    instance: VkInstance,
+    destroy: ffi::vulkan::PFN_vkDestroySurfaceKHR,
+    _window: PhantomData<&'a SharedWindow>
+}
+
+impl<'a> fmt::Debug for WindowSurface<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WindowSurface({:#x})", self.handle)
+    }
+}
+
+impl<'a> WindowSurface<'a> {
+    pub fn handle(&self) -> VkSurfaceKHR {
+        self.handle
+    }
+
+    pub fn instance(&self) -> VkInstance {
+        self.instance
+    }
+
+    /// Releases ownership of the surface without destroying it.
+    pub fn into_raw(self) -> VkSurfaceKHR {
+        let handle = self.handle;
+        mem::forget(self);
+        handle
+    }
+}
+
+impl<'a> Drop for WindowSurface<'a> {
+    fn drop(&mut self) {
+        (self.destroy)(self.instance, self.handle, ptr::null());
+    }
+}
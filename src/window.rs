@@ -3,6 +3,9 @@ use std::ptr;
 use std::ops::Deref;
 use std::fmt;
 use std::hash::{ Hash, Hasher };
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::cell::{ Cell, UnsafeCell };
+use std::any::Any;
 
 use enum_primitive::FromPrimitive;
 
@@ -23,9 +26,60 @@ use CursorMode;
 use KeyCode;
 use MouseButton;
 use Cursor;
+use fullscreen::WindowedState;
 use events::*;
 use util::*;
-use get_error;
+use get_error_in;
+
+/// Identifies a window for as long as the program runs.
+/// 
+/// Unlike the address of the underlying `GLFWwindow`, IDs are never reused, so an ID outliving its
+/// window can never be mistaken for a newer window.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct WindowId(u64);
+
+impl WindowId {
+    /// Creates an ID that does not belong to any window, for values that are filled in later.
+    pub(crate) fn placeholder(index: u32) -> WindowId {
+        // Real IDs count up from 1, so this range is never reached
+        WindowId(u64::MAX - index as u64)
+    }
+}
+
+static NEXT_WINDOW_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// State the wrapper keeps for each window, stored behind the GLFW window user pointer.
+/// 
+/// Allocated when the window is created and freed right before `glfwDestroyWindow`, so it is
+/// available from every callback the window can produce.
+pub(crate) struct WindowData {
+    id: WindowId,
+    /// Only ever replaced through the owning `Window`, which takes `&mut self` to do so. Other
+    /// `Window`s for the same `GLFWwindow` only exist while destruction is deferred, so the old
+    /// value is kept alive until they are gone.
+    user_data: UnsafeCell<Option<Box<dyn Any>>>,
+    /// Saved by `Window::set_fullscreen()` while the window is fullscreen.
+    pub(crate) windowed: Cell<Option<WindowedState>>
+}
+
+pub(crate) fn attach_window_data(ptr: *mut ffi::GLFWwindow) {
+    let data = Box::new(WindowData {
+        id: WindowId(NEXT_WINDOW_ID.fetch_add(1, Ordering::Relaxed) as u64),
+        user_data: UnsafeCell::new(None),
+        windowed: Cell::new(None)
+    });
+    unsafe { ffi::glfwSetWindowUserPointer(ptr, Box::into_raw(data) as *mut _) };
+}
+
+pub(crate) fn free_window_data(ptr: *mut ffi::GLFWwindow) {
+    unsafe {
+        let data = ffi::glfwGetWindowUserPointer(ptr) as *mut WindowData;
+        if !data.is_null() {
+            ffi::glfwSetWindowUserPointer(ptr, ptr::null_mut());
+            drop(Box::from_raw(data));
+        }
+    }
+}
 
 pub struct Window<'a> {
     pub(crate) ptr: *mut ffi::GLFWwindow,
@@ -74,13 +128,49 @@ impl<'a> Window<'a> {
         &self.shared
     }
 
+    /// Attaches a value to this window, replacing any previous value.
+    /// 
+    /// The value can be retrieved with [`user_data()`] from any `Window` referring to this window,
+    /// including the ones passed to event handlers. It is dropped when the window is destroyed.
+    /// 
+    /// This is backed by the GLFW window user pointer.
+    /// 
+    /// [`user_data()`]: #method.user_data
+    pub fn set_user_data<T: 'static>(&mut self, data: T) {
+        self.replace_user_data(Some(Box::new(data)));
+    }
+
+    /// Removes the value attached with [`set_user_data()`], dropping it.
+    /// 
+    /// [`set_user_data()`]: #method.set_user_data
+    pub fn clear_user_data(&mut self) {
+        self.replace_user_data(None);
+    }
+
+    fn replace_user_data(&mut self, data: Option<Box<dyn Any>>) {
+        let old = unsafe { ::std::mem::replace(&mut *self.data().user_data.get(), data) };
+        if let Some(old) = old {
+            match self.glfw {
+                Some(glfw) => glfw.drop_user_data(old),
+                None => drop(old)
+            }
+        }
+    }
+
+    /// Retrieves the value attached with [`set_user_data()`], if it is of type `T`.
+    /// 
+    /// [`set_user_data()`]: #method.set_user_data
+    pub fn user_data<T: 'static>(&self) -> Option<&T> {
+        unsafe { &*self.data().user_data.get() }.as_ref().and_then(|d| d.downcast_ref())
+    }
+
     /// [GLFW Reference][glfw]
     /// 
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga5d877f09e968cef7a360b513306f17ff
     pub fn set_title(&self, title: &str) -> Result<()> {
         let title = CString::new(title).unwrap();
         unsafe { ffi::glfwSetWindowTitle(self.ptr, title.as_ptr()) };
-        get_error()
+        get_error_in("Window::set_title")
     }
 
     /// [GLFW Reference][glfw]
@@ -89,7 +179,7 @@ impl<'a> Window<'a> {
     pub fn set_icon(&self, icons: &[Image]) -> Result<()> {
         let icons: Vec<_> = icons.iter().map(|i| i.as_glfw_image()).collect();
         unsafe { ffi::glfwSetWindowIcon(self.ptr, icons.len() as i32, icons.as_ptr()) };
-        get_error()
+        get_error_in("Window::set_icon")
     }
 
     /// [GLFW Reference][glfw]
@@ -98,7 +188,7 @@ impl<'a> Window<'a> {
     pub fn get_pos(&self) -> Result<(i32, i32)> {
         let mut p = (0, 0);
         unsafe { ffi::glfwGetWindowPos(self.ptr, &mut p.0, &mut p.1) };
-        get_error().map(|_| p)
+        get_error_in("Window::get_pos").map(|_| p)
     }
 
     /// [GLFW Reference][glfw]
@@ -106,7 +196,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga1abb6d690e8c88e0c8cd1751356dbca8
     pub fn set_pos(&self, x: i32, y: i32) -> Result<()> {
         unsafe { ffi::glfwSetWindowPos(self.ptr, x, y) };
-        get_error()
+        get_error_in("Window::set_pos")
     }
 
     /// [GLFW Reference][glfw]
@@ -115,7 +205,7 @@ impl<'a> Window<'a> {
     pub fn get_window_size(&self) -> Result<(i32, i32)> {
         let mut p = (0, 0);
         unsafe { ffi::glfwGetWindowSize(self.ptr, &mut p.0, &mut p.1) };
-        get_error().map(|_| p)
+        get_error_in("Window::get_window_size").map(|_| p)
     }
 
     /// [GLFW Reference][glfw]
@@ -135,7 +225,7 @@ impl<'a> Window<'a> {
             max_width.or_dont_care(),
             max_height.or_dont_care()
         )}
-        get_error()
+        get_error_in("Window::set_size_limits")
     }
 
     /// [GLFW Reference][glfw]
@@ -151,7 +241,7 @@ impl<'a> Window<'a> {
             numerator.or_dont_care(),
             denominator.or_dont_care()
         )}
-        get_error()
+        get_error_in("Window::set_aspect_ratio")
     }
 
     /// [GLFW Reference][glfw]
@@ -159,7 +249,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga371911f12c74c504dd8d47d832d095cb
     pub fn set_size(&self, width: i32, height: i32) -> Result<()> {
         unsafe { ffi::glfwSetWindowSize(self.ptr, width, height) };
-        get_error()
+        get_error_in("Window::set_size")
     }
 
     /// [GLFW Reference][glfw]
@@ -168,7 +258,7 @@ impl<'a> Window<'a> {
     pub fn get_framebuffer_size(&self) -> Result<(i32, i32)> {
         let mut s = (0, 0);
         unsafe { ffi::glfwGetFramebufferSize(self.ptr, &mut s.0, &mut s.1) };
-        get_error().map(|_| s)
+        get_error_in("Window::get_framebuffer_size").map(|_| s)
     }
 
     /// [GLFW Reference][glfw]
@@ -192,7 +282,7 @@ impl<'a> Window<'a> {
             right.ptr(),
             bottom.ptr()
         )}
-        get_error()
+        get_error_in("Window::get_frame_size")
     }
 
     /// [GLFW Reference][glfw]
@@ -201,7 +291,7 @@ impl<'a> Window<'a> {
     pub fn get_content_scale(&self) -> Result<(f32, f32)> {
         let mut s = (0.0, 0.0);
         unsafe { ffi::glfwGetWindowContentScale(self.ptr, &mut s.0, &mut s.1) };
-        get_error().map(|_| s)
+        get_error_in("Window::get_content_scale").map(|_| s)
     }
 
     /// [GLFW Reference][glfw]
@@ -209,7 +299,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#gad09f0bd7a6307c4533b7061828480a84
     pub fn get_opacity(&self) -> Result<f32> {
         let v = unsafe { ffi::glfwGetWindowOpacity(self.ptr) };
-        get_error().map(|_| v)
+        get_error_in("Window::get_opacity").map(|_| v)
     }
 
     /// [GLFW Reference][glfw]
@@ -217,7 +307,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#gac31caeb3d1088831b13d2c8a156802e9
     pub fn set_opacity(&self, opacity: f32) -> Result<()> {
         unsafe { ffi::glfwSetWindowOpacity(self.ptr, opacity) };
-        get_error()
+        get_error_in("Window::set_opacity")
     }
 
     /// [GLFW Reference][glfw]
@@ -225,7 +315,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga1bb559c0ebaad63c5c05ad2a066779c4
     pub fn iconify(&self) -> Result<()> {
         unsafe { ffi::glfwIconifyWindow(self.ptr) };
-        get_error()
+        get_error_in("Window::iconify")
     }
 
     /// [GLFW Reference][glfw]
@@ -233,7 +323,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga52527a5904b47d802b6b4bb519cdebc7
     pub fn restore(&self) -> Result<()> {
         unsafe { ffi::glfwRestoreWindow(self.ptr) };
-        get_error()
+        get_error_in("Window::restore")
     }
 
     /// [GLFW Reference][glfw]
@@ -241,7 +331,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga3f541387449d911274324ae7f17ec56b
     pub fn maximize(&self) -> Result<()> {
         unsafe { ffi::glfwMaximizeWindow(self.ptr) };
-        get_error()
+        get_error_in("Window::maximize")
     }
 
     /// [GLFW Reference][glfw]
@@ -249,7 +339,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga61be47917b72536a148300f46494fc66
     pub fn show(&self) -> Result<()> {
         unsafe { ffi::glfwShowWindow(self.ptr) };
-        get_error()
+        get_error_in("Window::show")
     }
 
     /// [GLFW Reference][glfw]
@@ -257,7 +347,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga49401f82a1ba5f15db5590728314d47c
     pub fn hide(&self) -> Result<()> {
         unsafe { ffi::glfwHideWindow(self.ptr) };
-        get_error()
+        get_error_in("Window::hide")
     }
 
     /// [GLFW Reference][glfw]
@@ -265,7 +355,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga873780357abd3f3a081d71a40aae45a1
     pub fn focus(&self) -> Result<()> {
         unsafe { ffi::glfwFocusWindow(self.ptr) };
-        get_error()
+        get_error_in("Window::focus")
     }
 
     /// [GLFW Reference][glfw]
@@ -273,7 +363,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga2f8d59323fc4692c1d54ba08c863a703
     pub fn request_attention(&self) -> Result<()> {
         unsafe { ffi::glfwRequestWindowAttention(self.ptr) };
-        get_error()
+        get_error_in("Window::request_attention")
     }
 
     /// [GLFW Reference][glfw]
@@ -306,7 +396,7 @@ impl<'a> Window<'a> {
             height,
             refresh_rate.or_dont_care()
         )}
-        get_error()
+        get_error_in("Window::set_monitor")
     }
 
     /// [GLFW Reference][glfw]
@@ -314,7 +404,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#gacccb29947ea4b16860ebef42c2cb9337
     pub fn get_attribute(&self, attrib: WindowAttribute) -> Result<bool> {
         let r = unsafe { ffi::glfwGetWindowAttrib(self.ptr, attrib as i32) };
-        get_error().map(|_| cint_to_bool(r))
+        get_error_in("Window::get_attribute").map(|_| cint_to_bool(r))
     }
 
     /// [GLFW Reference][glfw]
@@ -322,7 +412,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#gacccb29947ea4b16860ebef42c2cb9337
     pub fn get_client_api_attribute(&self) -> Result<ClientApi> {
         let r = unsafe { ffi::glfwGetWindowAttrib(self.ptr, ffi::GLFW_CLIENT_API) };
-        get_error().map(|_| ClientApi::from_i32(r).unwrap())
+        get_error_in("Window::get_client_api_attribute").map(|_| ClientApi::from_i32(r).unwrap())
     }
 
     /// [GLFW Reference][glfw]
@@ -330,7 +420,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#gacccb29947ea4b16860ebef42c2cb9337
     pub fn get_context_creation_api_attribute(&self) -> Result<ContextCreationApi> {
         let r = unsafe { ffi::glfwGetWindowAttrib(self.ptr, ffi::GLFW_CONTEXT_CREATION_API) };
-        get_error().map(|_| ContextCreationApi::from_i32(r).unwrap())
+        get_error_in("Window::get_context_creation_api_attribute").map(|_| ContextCreationApi::from_i32(r).unwrap())
     }
 
     /// [GLFW Reference][glfw]
@@ -342,7 +432,7 @@ impl<'a> Window<'a> {
             ffi::glfwGetWindowAttrib(self.ptr, ffi::GLFW_CONTEXT_VERSION_MINOR),
             ffi::glfwGetWindowAttrib(self.ptr, ffi::GLFW_CONTEXT_REVISION),
         )};
-        get_error().map(|_| t)
+        get_error_in("Window::get_context_version_attribute").map(|_| t)
     }
 
     /// [GLFW Reference][glfw]
@@ -350,7 +440,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#gacccb29947ea4b16860ebef42c2cb9337
     pub fn get_opengl_profile_attribute(&self) -> Result<OpenGlProfile> {
         let r = unsafe { ffi::glfwGetWindowAttrib(self.ptr, ffi::GLFW_OPENGL_PROFILE) };
-        get_error().map(|_| OpenGlProfile::from_i32(r).unwrap())
+        get_error_in("Window::get_opengl_profile_attribute").map(|_| OpenGlProfile::from_i32(r).unwrap())
     }
 
     /// [GLFW Reference][glfw]
@@ -358,7 +448,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#gacccb29947ea4b16860ebef42c2cb9337
     pub fn get_context_robustness_attribute(&self) -> Result<ContextRobustness> {
         let r = unsafe { ffi::glfwGetWindowAttrib(self.ptr, ffi::GLFW_CONTEXT_ROBUSTNESS) };
-        get_error().map(|_| ContextRobustness::from_i32(r).unwrap())
+        get_error_in("Window::get_context_robustness_attribute").map(|_| ContextRobustness::from_i32(r).unwrap())
     }
 
     /// [GLFW Reference][glfw]
@@ -376,7 +466,7 @@ impl<'a> Window<'a> {
             AutoIconify(v) =>
                     ffi::glfwSetWindowAttrib(self.ptr, ffi::GLFW_AUTO_ICONIFY, bool_to_cint(v)),
         } }
-        get_error()
+        get_error_in("Window::set_attribute")
     }
 
     /// [GLFW Reference][glfw]
@@ -413,7 +503,7 @@ impl<'a> Window<'a> {
                 self.ptr, ffi::GLFW_RAW_MOUSE_MOTION, bool_to_cint(v)
             )
         } }
-        get_error()
+        get_error_in("Window::set_input_mode")
     }
 
     /// [GLFW Reference][glfw]
@@ -444,7 +534,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__input.html#ga04b03af936d906ca123c8f4ee08b39e7
     pub fn set_cursor_pos(&self, x: f64, y: f64) -> Result<()> {
         unsafe { ffi::glfwSetCursorPos(self.ptr, x, y) };
-        get_error()
+        get_error_in("Window::set_cursor_pos")
     }
 
     /// [GLFW Reference][glfw]
@@ -452,7 +542,7 @@ impl<'a> Window<'a> {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__input.html#gad3b4f38c8d5dae036bc8fa959e18343e
     pub fn set_cursor(&self, cursor: Option<&Cursor>) -> Result<()> {
         unsafe { ffi::glfwSetCursor(self.ptr, cursor.map_or(ptr::null_mut(), |c| c.ptr)) };
-        get_error()
+        get_error_in("Window::set_cursor")
     }
 }
 
@@ -465,10 +555,23 @@ impl<'a> Deref for Window<'a> {
 }
 
 #[derive(PartialEq, Eq, Hash)]
-pub struct SharedWindow(*mut ffi::GLFWwindow);
+pub struct SharedWindow(pub(crate) *mut ffi::GLFWwindow);
 unsafe impl Sync for SharedWindow {}
 
 impl SharedWindow {
+    pub(crate) fn data(&self) -> &WindowData {
+        unsafe {
+            let data = ffi::glfwGetWindowUserPointer(self.0) as *const WindowData;
+            assert!(!data.is_null());
+            &*data
+        }
+    }
+
+    /// Returns the ID of this window.
+    pub fn id(&self) -> WindowId {
+        self.data().id
+    }
+
     /// [GLFW Reference][glfw]
     /// 
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga24e02fbfefbb81fc45320989f8140ab5
@@ -491,7 +594,7 @@ impl SharedWindow {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__window.html#ga15a5a1ee5b3c2ca6b15ca209a12efd14
     pub fn swap_buffers(&self) -> Result<()> {
         unsafe { ffi::glfwSwapBuffers(self.0) };
-        get_error()
+        get_error_in("SharedWindow::swap_buffers")
     }
 
     /// [GLFW Reference][glfw]
@@ -499,7 +602,7 @@ impl SharedWindow {
     /// [glfw]: http://www.glfw.org/docs/3.3/group__context.html#ga1c04dc242268f827290fe40aa1c91157
     pub unsafe fn make_context_current(&self) -> Result<()> {
         ffi::glfwMakeContextCurrent(self.0);
-        get_error()
+        get_error_in("SharedWindow::make_context_current")
     }
 }
 
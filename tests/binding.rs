@@ -0,0 +1,137 @@
+extern crate glfw_wrapper;
+
+use glfw_wrapper::*;
+
+const CONFIG: &str = "
+# Movement
+jump = Space | Gamepad:A
+walk_left = a | Scancode:38 | Axis:LeftX<-0.25
+save = Ctrl+Shift+S
+fire = Mouse:Left | Axis:RightTrigger>0.5
+unbound =
+";
+
+#[test]
+fn names_round_trip() {
+    assert_eq!("space".parse(), Ok(KeyCode::Space));
+    assert_eq!(KeyCode::LeftShift.to_string(), "LeftShift");
+    assert_eq!("Middle".parse(), Ok(MouseButton::Middle));
+    assert_eq!("dpadup".parse(), Ok(GamepadButton::DpadUp));
+    assert_eq!(GamepadAxis::RightTrigger.to_string().parse(), Ok(GamepadAxis::RightTrigger));
+    let error = "Spacebar".parse::<KeyCode>().unwrap_err();
+    assert_eq!(error.to_string(), "unknown key name `Spacebar`");
+}
+
+#[test]
+fn config_round_trips() {
+    let bindings: Bindings = CONFIG.parse().unwrap();
+    assert_eq!(bindings.actions().collect::<Vec<_>>(),
+            vec!["fire", "jump", "save", "unbound", "walk_left"]);
+    assert_eq!(bindings.bindings("save"), &[Binding {
+        modifiers: Modifiers::CONTROL | Modifiers::SHIFT,
+        inputs: vec![BindingInput::Key(Key::Named(KeyCode::S))]
+    }]);
+    assert_eq!(bindings.bindings("walk_left")[2], Binding::new(BindingInput::GamepadAxis {
+        axis: GamepadAxis::LeftX,
+        threshold: -0.25
+    }));
+    assert!(bindings.bindings("unbound").is_empty());
+    assert_eq!(bindings.bindings("save")[0].to_string(), "Ctrl+Shift+S");
+    assert_eq!("control+shift+s".parse(), Ok(bindings.bindings("save")[0].clone()));
+    assert_eq!(bindings.to_string().parse(), Ok(bindings));
+}
+
+#[test]
+fn config_errors_report_the_line() {
+    let error = "jump = Space\nfire = Mouse:Thumb\n".parse::<Bindings>().unwrap_err();
+    assert_eq!(error.line(), Some(2));
+    assert!("jump Space".parse::<Bindings>().is_err());
+    assert!("jump = Shift".parse::<Bindings>().is_err());
+    assert!("fire = Axis:LeftTrigger<0.5".parse::<Bindings>().is_err());
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn actions_follow_input() {
+    use glfw_wrapper::mock;
+
+    let _lock = mock::lock();
+    let glfw = init(InitHints::default()).unwrap();
+    let window = glfw.create_window(&WindowHints::default(), 640, 480, "Test", None, None)
+            .unwrap();
+    let id = window.id();
+    let bindings: Bindings = CONFIG.parse().unwrap();
+    let mut input = InputState::new();
+    let update = |input: &mut InputState| {
+        input.end_frame();
+        glfw.poll_events(&mut |e| input.handle_event(0.0, &e)).unwrap();
+    };
+
+    mock::press_key(&window, KeyCode::S, Modifiers::empty());
+    update(&mut input);
+    assert!(!bindings.is_active("save", &input, id, None));
+    mock::press_key(&window, KeyCode::LeftControl, Modifiers::empty());
+    mock::press_key(&window, KeyCode::RightShift, Modifiers::CONTROL);
+    update(&mut input);
+    assert!(bindings.is_active("save", &input, id, None));
+    assert!(bindings.was_triggered("save", &input, id, None, None));
+    update(&mut input);
+    assert!(!bindings.was_triggered("save", &input, id, None, None));
+
+    // A tap between frames still triggers the action
+    mock::press_key(&window, KeyCode::Space, Modifiers::empty());
+    mock::release_key(&window, KeyCode::Space, Modifiers::empty());
+    update(&mut input);
+    assert!(!bindings.is_active("jump", &input, id, None));
+    assert!(bindings.was_triggered("jump", &input, id, None, None));
+
+    let mut gamepad = GamepadState {
+        a_cross: false, b_circle: false, x_square: false, y_triangle: false,
+        left_bumper: false, right_bumper: false, back: false, start: false, guide: false,
+        left_stick: false, right_stick: false,
+        dpad_up: false, dpad_right: false, dpad_down: false, dpad_left: false,
+        left_stick_x: 0.0, left_stick_y: 0.0, right_stick_x: 0.0, right_stick_y: 0.0,
+        left_trigger: -1.0, right_trigger: -1.0
+    };
+    let previous = gamepad;
+    gamepad.left_stick_x = -0.2;
+    assert_eq!(bindings.value("walk_left", &input, id, Some(&gamepad)), 0.0);
+    gamepad.left_stick_x = -0.75;
+    assert_eq!(bindings.value("walk_left", &input, id, Some(&gamepad)), 0.75);
+    mock::press_key(&window, KeyCode::A, Modifiers::empty());
+    update(&mut input);
+    assert_eq!(bindings.value("walk_left", &input, id, Some(&gamepad)), 1.0);
+
+    gamepad.a_cross = true;
+    assert!(bindings.was_triggered("jump", &input, id, Some(&gamepad), Some(&previous)));
+    assert!(!bindings.was_triggered("jump", &input, id, Some(&gamepad), Some(&gamepad)));
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn bindings_allow_extra_modifiers() {
+    use glfw_wrapper::mock;
+
+    let _lock = mock::lock();
+    let glfw = init(InitHints::default()).unwrap();
+    let window = glfw.create_window(&WindowHints::default(), 640, 480, "Test", None, None)
+            .unwrap();
+    let id = window.id();
+    let bindings: Bindings = "save = Ctrl+S\nforward = W".parse().unwrap();
+    let mut input = InputState::new();
+    glfw.poll_events(&mut |e| input.handle_event(0.0, &e)).unwrap();
+
+    mock::press_key(&window, KeyCode::LeftShift, Modifiers::empty());
+    mock::press_key(&window, KeyCode::W, Modifiers::SHIFT);
+    glfw.poll_events(&mut |e| input.handle_event(0.0, &e)).unwrap();
+    assert!(bindings.is_active("forward", &input, id, None));
+
+    // Unlike shortcuts, Ctrl+S is also active with Ctrl+Shift+S
+    mock::press_key(&window, KeyCode::LeftControl, Modifiers::SHIFT);
+    mock::press_key(&window, KeyCode::S, Modifiers::SHIFT | Modifiers::CONTROL);
+    glfw.poll_events(&mut |e| input.handle_event(0.0, &e)).unwrap();
+    assert!(bindings.is_active("save", &input, id, None));
+    let shortcut: Shortcut = "Ctrl+S".parse().unwrap();
+    let modifiers = Modifiers::SHIFT | Modifiers::CONTROL;
+    assert!(!shortcut.matches_key(Key::Named(KeyCode::S), modifiers));
+}
@@ -0,0 +1,64 @@
+extern crate glfw_wrapper;
+
+use glfw_wrapper::*;
+
+fn validation_error(builder: WindowBuilder) -> String {
+    let error = builder.validate().unwrap_err();
+    assert_eq!(error.kind, ErrorKind::InvalidValue);
+    assert_eq!(error.function, "WindowBuilder::validate");
+    error.description
+}
+
+#[test]
+fn invalid_settings_are_rejected() {
+    assert!(WindowBuilder::new(640, 480, "Test").validate().is_ok());
+    assert_eq!(validation_error(WindowBuilder::new(0, 480, "Test")), "Invalid window size 0x480");
+    assert_eq!(validation_error(WindowBuilder::new(640, 480, "Te\0st")),
+            "Window title contains a nul character");
+    assert_eq!(validation_error(WindowBuilder::new(640, 480, "Test")
+            .client_api(ClientApi::NoApi)
+            .context_version(3, 3)),
+            "Context version 3.3 requested without a client API");
+    assert_eq!(validation_error(WindowBuilder::new(640, 480, "Test")
+            .context_version(3, 1)
+            .opengl_profile(OpenGlProfile::Core)),
+            "OpenGL profiles require version 3.2, not 3.1");
+    assert_eq!(validation_error(WindowBuilder::new(640, 480, "Test")
+            .size_limits(Some(800), None, Some(640), None)),
+            "Invalid window size limits");
+    assert_eq!(validation_error(WindowBuilder::new(640, 480, "Test").aspect_ratio(16, 0)),
+            "Invalid aspect ratio 16:0");
+    assert_eq!(validation_error(WindowBuilder::new(640, 480, "Test").opacity(1.5)),
+            "Invalid opacity 1.5");
+    assert!(WindowBuilder::new(640, 480, "Test")
+            .client_api(ClientApi::NoApi)
+            .size_limits(Some(320), None, Some(640), None)
+            .validate()
+            .is_ok());
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn settings_are_applied_before_showing() {
+    let _lock = glfw_wrapper::mock::lock();
+    let glfw = init(InitHints::default()).unwrap();
+    let window = WindowBuilder::new(640, 480, "Test")
+            .resizable(false)
+            .position(120, 80)
+            .opacity(0.5)
+            .cursor_mode(CursorMode::Hidden)
+            .build(&glfw)
+            .unwrap();
+    assert_eq!(window.get_pos().unwrap(), (120, 80));
+    assert_eq!(window.get_opacity().unwrap(), 0.5);
+    assert_eq!(window.get_cursor_mode(), CursorMode::Hidden);
+    assert!(!window.get_attribute(WindowAttribute::Resizable).unwrap());
+    assert!(window.get_attribute(WindowAttribute::Visible).unwrap());
+
+    let hidden = WindowBuilder::new(640, 480, "Hidden")
+            .visible(false)
+            .position(0, 0)
+            .build(&glfw)
+            .unwrap();
+    assert!(!hidden.get_attribute(WindowAttribute::Visible).unwrap());
+}
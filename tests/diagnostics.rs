@@ -0,0 +1,86 @@
+extern crate glfw_wrapper;
+
+use glfw_wrapper::*;
+
+#[test]
+fn diagnostics_describe_the_problem() {
+    let payload: Box<dyn std::any::Any + Send> = Box::new(String::from("boom"));
+    let panic = Diagnostic::CallbackPanic { callback: "key", payload: &*payload };
+    assert_eq!(panic.to_string(), "panic in GLFW key callback: boom");
+    let error = Diagnostic::UnexpectedError { code: 0x10003, description: "Invalid key" };
+    assert_eq!(error.to_string(), "unexpected GLFW error 0x10003: Invalid key");
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn callback_panics_can_be_propagated() {
+    use std::panic::{ self, AssertUnwindSafe };
+    use glfw_wrapper::mock;
+
+    let _lock = mock::lock();
+    let glfw = init(InitHints::default()).unwrap();
+    let window = glfw.create_window(&WindowHints::default(), 640, 480, "Test", None, None)
+            .unwrap();
+    glfw.set_panic_behavior(PanicBehavior::Propagate);
+    glfw.poll_events(&mut |_| {}).unwrap();
+
+    mock::press_key(&window, KeyCode::A, Modifiers::empty());
+    mock::press_key(&window, KeyCode::B, Modifiers::empty());
+    let mut handled = 0;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        glfw.poll_events(&mut |_| {
+            handled += 1;
+            panic!("handler failed");
+        })
+    }));
+    assert_eq!(*result.unwrap_err().downcast::<&str>().unwrap(), "handler failed");
+    // The rest of the events are dropped
+    assert_eq!(handled, 1);
+
+    mock::press_key(&window, KeyCode::C, Modifiers::empty());
+    let mut events = vec![];
+    glfw.poll_events_into(&mut events).unwrap();
+    assert_eq!(events.len(), 1);
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn disconnections_are_still_handled_after_a_callback_panics() {
+    use std::cell::Cell;
+    use std::panic::{ self, AssertUnwindSafe };
+    use std::rc::Rc;
+    use glfw_wrapper::mock;
+
+    struct DropFlag(Rc<Cell<bool>>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    let _lock = mock::lock();
+    let glfw = init(InitHints::default()).unwrap();
+    let monitor = glfw.get_primary_monitor().unwrap();
+    let dropped = Rc::new(Cell::new(false));
+    monitor.set_user_data(DropFlag(dropped.clone()));
+    glfw.set_panic_behavior(PanicBehavior::Propagate);
+
+    mock::connect_monitor(mock::MonitorConfig {
+        name: "Second".to_owned(),
+        ..Default::default()
+    });
+    assert!(mock::disconnect_monitor("Mock Monitor"));
+    let mut handled = 0;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        glfw.poll_events(&mut |_| {
+            handled += 1;
+            panic!("handler failed");
+        })
+    }));
+    assert!(result.is_err());
+    // The handler does not see the disconnection, but the monitor is still released
+    assert_eq!(handled, 1);
+    assert!(!monitor.is_valid());
+    assert!(dropped.get());
+}
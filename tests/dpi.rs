@@ -0,0 +1,20 @@
+extern crate glfw_wrapper;
+
+use glfw_wrapper::*;
+
+#[test]
+fn conversions_use_the_content_scale() {
+    let scale = (1.5, 2.0);
+    assert_eq!(LogicalSize::new(101.0, 50.0).to_physical(scale), PhysicalSize::new(152, 100));
+    assert_eq!(PhysicalSize::new(150, 100).to_logical(scale), LogicalSize::new(100.0, 50.0));
+    assert_eq!(LogicalPosition::new(10.0, -4.0).to_physical(scale),
+            PhysicalPosition::new(15.0, -8.0));
+    assert_eq!(PhysicalPosition::new(15.0, -8.0).to_logical(scale),
+            LogicalPosition::new(10.0, -4.0));
+    assert_eq!(<(i32, i32)>::from(PhysicalSize::from((640, 480))), (640, 480));
+
+    // A zero scale falls back to 1.0 instead of producing infinities
+    assert_eq!(PhysicalSize::new(150, 100).to_logical((0.0, 2.0)), LogicalSize::new(150.0, 50.0));
+    assert_eq!(PhysicalPosition::new(15.0, -8.0).to_logical((1.5, 0.0)),
+            LogicalPosition::new(10.0, -8.0));
+}
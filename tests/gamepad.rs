@@ -0,0 +1,120 @@
+extern crate glfw_wrapper;
+
+use glfw_wrapper::*;
+
+fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+    assert!((actual.0 - expected.0).abs() < 1e-5 && (actual.1 - expected.1).abs() < 1e-5,
+            "{:?} != {:?}", actual, expected);
+}
+
+fn stick(deadzone: Deadzone) -> StickSettings {
+    StickSettings { deadzone, ..Default::default() }
+}
+
+#[test]
+fn deadzones_filter_small_movements() {
+    assert_close(stick(Deadzone::None).apply(0.05, -0.9), (0.05, -0.9));
+
+    let axial = stick(Deadzone::Axial(0.2));
+    assert_close(axial.apply(0.1, 0.8), (0.0, 0.8));
+    assert_close(axial.apply(0.15, 0.15), (0.0, 0.0));
+
+    let radial = stick(Deadzone::Radial(0.2));
+    assert_close(radial.apply(0.1, 0.1), (0.0, 0.0));
+    assert_close(radial.apply(0.1, 0.8), (0.1, 0.8));
+    assert_close(radial.apply(0.0, -0.25), (0.0, -0.25));
+
+    let scaled = stick(Deadzone::ScaledRadial(0.2));
+    assert_close(scaled.apply(0.0, -0.25), (0.0, -0.0625));
+    assert_close(scaled.apply(0.6, 0.0), (0.5, 0.0));
+    assert_close(scaled.apply(0.0, 1.0), (0.0, 1.0));
+}
+
+#[test]
+fn outer_deadzones_and_curves_change_the_distance() {
+    let settings = StickSettings {
+        deadzone: Deadzone::ScaledRadial(0.1),
+        outer_deadzone: 0.1,
+        exponent: 2.0,
+        invert_y: true
+    };
+    assert_close(settings.apply(0.0, 0.5), (0.0, -0.25));
+    assert_close(settings.apply(-0.95, 0.0), (-1.0, 0.0));
+    // The direction is kept
+    assert_close(settings.apply(0.6, 0.8), (0.6, -0.8));
+}
+
+#[test]
+fn triggers_keep_the_glfw_range() {
+    let default = TriggerSettings::default();
+    assert_eq!(default.apply(-1.0), -1.0);
+    assert!((default.apply(0.3) - 0.3).abs() < 1e-5);
+
+    let trigger = TriggerSettings { threshold: 0.2, outer_deadzone: 0.2, ..Default::default() };
+    assert_eq!(trigger.apply(-0.7), -1.0);
+    assert!(trigger.apply(0.0).abs() < 1e-5);
+    assert_eq!(trigger.apply(0.7), 1.0);
+}
+
+#[test]
+fn settings_apply_to_the_whole_state() {
+    let state = GamepadState {
+        a_cross: true, b_circle: false, x_square: false, y_triangle: false,
+        left_bumper: false, right_bumper: false, back: false, start: false, guide: false,
+        left_stick: false, right_stick: false,
+        dpad_up: false, dpad_right: false, dpad_down: false, dpad_left: false,
+        left_stick_x: 0.05, left_stick_y: -0.05, right_stick_x: 0.0, right_stick_y: 0.7,
+        left_trigger: -0.95, right_trigger: 1.0
+    };
+    assert_eq!(GamepadSettings::default().apply(&state), state);
+
+    let settings = GamepadSettings {
+        left_stick: stick(Deadzone::Radial(0.1)),
+        right_stick: StickSettings { invert_y: true, ..Default::default() },
+        left_trigger: TriggerSettings { threshold: 0.1, ..Default::default() },
+        ..Default::default()
+    };
+    let processed = settings.apply(&state);
+    assert!(processed.a_cross);
+    assert_eq!((processed.left_stick_x, processed.left_stick_y), (0.0, 0.0));
+    assert_close((processed.right_stick_x, processed.right_stick_y), (0.0, -0.7));
+    assert_eq!(processed.left_trigger, -1.0);
+    assert_eq!(processed.right_trigger, 1.0);
+}
+
+#[test]
+fn buttons_and_axes_can_be_indexed() {
+    let mut state = GamepadState {
+        a_cross: false, b_circle: false, x_square: false, y_triangle: false,
+        left_bumper: false, right_bumper: false, back: false, start: false, guide: false,
+        left_stick: false, right_stick: false,
+        dpad_up: false, dpad_right: false, dpad_down: false, dpad_left: false,
+        left_stick_x: 0.0, left_stick_y: 0.0, right_stick_x: 0.0, right_stick_y: 0.0,
+        left_trigger: -1.0, right_trigger: -1.0
+    };
+    state[GamepadButton::DpadLeft] = true;
+    state[GamepadButton::A] = true;
+    state[GamepadAxis::RightTrigger] = 0.5;
+    assert!(state.dpad_left && state.button(GamepadButton::A));
+    assert_eq!(state.right_trigger, 0.5);
+    assert_eq!(state.pressed_buttons().collect::<Vec<_>>(),
+            vec![GamepadButton::A, GamepadButton::DpadLeft]);
+    assert_eq!(state.axes().nth(5), Some((GamepadAxis::RightTrigger, 0.5)));
+
+    for (i, &button) in GamepadButton::ALL.iter().enumerate() {
+        assert_eq!(button as usize, i);
+    }
+    for (i, &axis) in GamepadAxis::ALL.iter().enumerate() {
+        assert_eq!(axis as usize, i);
+    }
+}
+
+#[test]
+fn labels_follow_the_layout() {
+    assert_eq!(GamepadButton::A.label(GamepadLayout::Xbox), "A");
+    assert_eq!(GamepadButton::A.label(GamepadLayout::PlayStation), "Cross");
+    assert_eq!(GamepadButton::RightBumper.label(GamepadLayout::PlayStation), "R1");
+    assert_eq!(GamepadButton::DpadUp.label(GamepadLayout::PlayStation), "D-pad Up");
+    assert_eq!(GamepadAxis::LeftTrigger.label(GamepadLayout::Xbox), "LT");
+    assert_eq!(GamepadAxis::LeftTrigger.label(GamepadLayout::PlayStation), "L2");
+}
@@ -0,0 +1,111 @@
+//! Checks that injected events reach event handlers like real ones, using the mock backend.
+
+#![cfg(all(feature = "event-injection", feature = "mock"))]
+
+extern crate glfw_wrapper;
+
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use glfw_wrapper::*;
+use glfw_wrapper::mock;
+
+fn init() -> Glfw {
+    glfw_wrapper::init(InitHints::default()).unwrap()
+}
+
+fn inject(glfw: &Glfw, event: &OwnedEvent) -> Vec<OwnedEvent> {
+    let mut events = vec![];
+    glfw.inject_event(event, &mut |e| events.push(OwnedEvent::from(&e))).unwrap();
+    events
+}
+
+#[test]
+fn injected_events_round_trip() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let hints = WindowHints { visible: false, ..Default::default() };
+    let window = glfw.create_window(&hints, 640, 480, "Test", None, None).unwrap();
+    let win = window.id();
+
+    let events = vec![
+        OwnedEvent::KeyDown {
+            win,
+            key: Key::Named(KeyCode::Space),
+            scancode: 65,
+            modifiers: Modifiers::CONTROL
+        },
+        OwnedEvent::KeyRepeat { win, key: Key::Unnamed(300), scancode: 300, modifiers: Modifiers::empty() },
+        OwnedEvent::CharTypedWithModifiers { win, character: 'é', modifiers: Modifiers::SHIFT },
+        OwnedEvent::CharTyped(win, 'é'),
+        OwnedEvent::MouseButtonUp { win, button: MouseButton::Right, modifiers: Modifiers::ALT },
+        OwnedEvent::CursorMoved { win, x: 10.5, y: -3.0 },
+        OwnedEvent::CursorExited(win),
+        OwnedEvent::Scroll { win, x_offset: 0.0, y_offset: -1.0 },
+        OwnedEvent::WindowResize { win, width: 800, height: 600 },
+        OwnedEvent::WindowDefocused(win),
+        OwnedEvent::FileDrop(win, vec![PathBuf::from("/tmp/a b"), PathBuf::from("c")]),
+        OwnedEvent::JoystickConnected(Joystick::Three)
+    ];
+    for event in &events {
+        assert_eq!(inject(&glfw, event), vec![event.clone()]);
+    }
+}
+
+#[test]
+fn windows_destroyed_by_the_handler_are_destroyed_afterwards() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let hints = WindowHints { visible: false, ..Default::default() };
+    let mut window = Some(glfw.create_window(&hints, 640, 480, "Test", None, None).unwrap());
+    let id = window.as_ref().unwrap().id();
+
+    let mut lent = false;
+    glfw.inject_event(&OwnedEvent::WindowCloseRequested(id), &mut |e| {
+        window.take();
+        // Still alive while events are being processed
+        lent = e.window().is_some_and(|w| w.id() == id);
+    }).unwrap();
+    assert!(lent);
+    assert!(!glfw.window_exists(id));
+
+    let error = glfw.inject_event(&OwnedEvent::WindowRefresh(id), &mut |_| {
+        panic!("event for a destroyed window delivered")
+    }).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::InvalidValue);
+}
+
+#[test]
+fn injected_joystick_disconnections_keep_user_data() {
+    let _lock = mock::lock();
+    let glfw = init();
+    mock::connect_joystick(Joystick::One, mock::JoystickConfig::default());
+    glfw.poll_events(&mut |_| {}).unwrap();
+    assert!(glfw.set_joystick_user_data(Joystick::One, 42));
+
+    let event = OwnedEvent::JoystickDisconnected(Joystick::One);
+    assert_eq!(inject(&glfw, &event), vec![event.clone()]);
+    assert_eq!(glfw.joystick_user_data::<i32>(Joystick::One), Some(Rc::new(42)));
+}
+
+#[test]
+fn monitor_events_are_rejected() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let event = OwnedEvent::MonitorDisconnected("Monitor".to_owned());
+    let error = glfw.inject_event(&event, &mut |_| {}).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::InvalidValue);
+}
+
+#[test]
+fn dropped_paths_with_nul_bytes_are_rejected() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let hints = WindowHints { visible: false, ..Default::default() };
+    let window = glfw.create_window(&hints, 640, 480, "Test", None, None).unwrap();
+    let event = OwnedEvent::FileDrop(window.id(), vec![PathBuf::from("a\0b")]);
+    let mut called = false;
+    let error = glfw.inject_event(&event, &mut |_| called = true).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::InvalidValue);
+    assert!(!called);
+}
@@ -0,0 +1,87 @@
+//! Tests of `InputState` fed with input from the mock backend.
+
+#![cfg(feature = "mock")]
+
+extern crate glfw_wrapper;
+
+use glfw_wrapper::*;
+use glfw_wrapper::mock;
+
+fn update(glfw: &Glfw, input: &mut InputState) {
+    input.end_frame();
+    glfw.poll_events(&mut |e| input.handle_event(glfw.get_time(), &e)).unwrap();
+}
+
+#[test]
+fn presses_and_releases_last_one_frame() {
+    let _lock = mock::lock();
+    let glfw = glfw_wrapper::init(InitHints::default()).unwrap();
+    let window = glfw.create_window(&WindowHints::default(), 640, 480, "Test", None, None)
+            .unwrap();
+    let id = window.id();
+    let mut input = InputState::new();
+    update(&glfw, &mut input);
+
+    mock::press_key(&window, KeyCode::W, Modifiers::empty());
+    mock::press_mouse_button(&window, MouseButton::Left, Modifiers::empty());
+    update(&glfw, &mut input);
+    assert!(input.is_down(id, KeyCode::W));
+    assert!(input.was_pressed_this_frame(id, KeyCode::W));
+    assert!(input.was_pressed_this_frame(id, MouseButton::Left));
+    assert!(!input.is_down(id, KeyCode::S));
+
+    mock::advance_time(0.5);
+    mock::repeat_key(&window, KeyCode::W, Modifiers::empty());
+    update(&glfw, &mut input);
+    assert!(input.is_down(id, KeyCode::W));
+    assert!(!input.was_pressed_this_frame(id, KeyCode::W));
+    assert_eq!(input.held_duration(id, KeyCode::W, glfw.get_time()), Some(0.5));
+
+    // A tap between two frames is still seen
+    mock::release_mouse_button(&window, MouseButton::Left, Modifiers::empty());
+    mock::press_mouse_button(&window, MouseButton::Left, Modifiers::empty());
+    mock::release_key(&window, KeyCode::W, Modifiers::empty());
+    mock::press_key(&window, KeyCode::Space, Modifiers::empty());
+    mock::release_key(&window, KeyCode::Space, Modifiers::empty());
+    update(&glfw, &mut input);
+    assert!(!input.is_down(id, KeyCode::W));
+    assert!(input.was_released_this_frame(id, KeyCode::W));
+    assert_eq!(input.held_duration(id, KeyCode::W, glfw.get_time()), None);
+    assert!(input.was_pressed_this_frame(id, KeyCode::Space));
+    assert!(input.was_released_this_frame(id, KeyCode::Space));
+    assert!(input.is_down(id, MouseButton::Left));
+    assert!(input.was_released_this_frame(id, MouseButton::Left));
+
+    update(&glfw, &mut input);
+    assert!(!input.was_released_this_frame(id, KeyCode::W));
+    assert!(!input.was_pressed_this_frame(id, KeyCode::Space));
+}
+
+#[test]
+fn scrolling_and_cursor_movement_accumulate() {
+    let _lock = mock::lock();
+    let glfw = glfw_wrapper::init(InitHints::default()).unwrap();
+    let window = glfw.create_window(&WindowHints::default(), 640, 480, "Test", None, None)
+            .unwrap();
+    let id = window.id();
+    let mut input = InputState::new();
+    update(&glfw, &mut input);
+
+    mock::move_cursor(&window, 10.0, 10.0);
+    mock::move_cursor(&window, 15.0, 8.0);
+    mock::move_cursor(&window, 20.0, 12.0);
+    mock::scroll(&window, 0.0, 1.0);
+    mock::scroll(&window, 0.5, 1.0);
+    update(&glfw, &mut input);
+    assert_eq!(input.cursor_delta(id), (10.0, 2.0));
+    assert_eq!(input.cursor_position(id), Some((20.0, 12.0)));
+    assert_eq!(input.scroll(id), (0.5, 2.0));
+
+    mock::enter_cursor(&window, false);
+    mock::enter_cursor(&window, true);
+    mock::move_cursor(&window, 300.0, 200.0);
+    update(&glfw, &mut input);
+    assert_eq!(input.cursor_delta(id), (0.0, 0.0));
+    assert_eq!(input.scroll(id), (0.0, 0.0));
+    assert_eq!(input.cursor_position(id), Some((300.0, 200.0)));
+}
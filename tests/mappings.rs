@@ -0,0 +1,97 @@
+extern crate glfw_wrapper;
+
+use glfw_wrapper::*;
+
+const PAD: &str = "030000005e0400008e02000010010000";
+const OTHER: &str = "03000000de280000ff11000001000000";
+
+fn database() -> String {
+    format!("
+# Test database
+{pad},Pad,a:b0,b:b1,leftx:a0,lefty:a1,righttrigger:+a5,dpup:h0.1,platform:Linux,
+{pad},Windows Pad,a:b1,b:b0,platform:Windows,
+{pad},Mac Pad,a:b1,b:b0,platform:Mac OS X,
+{other},Other,a:b0,dpdown:-a7~,
+{other},Broken,a:c0,
+12345,Short GUID,a:b0,
+", pad = PAD, other = OTHER)
+}
+
+fn native_pad_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "Windows Pad"
+    } else if cfg!(target_os = "macos") {
+        "Mac Pad"
+    } else {
+        "Pad"
+    }
+}
+
+#[test]
+fn loading_reports_invalid_lines() {
+    let mut mappings = GamepadMappings::new();
+    let errors = mappings.load_str(&database());
+    assert_eq!(errors.iter().map(|e| e.line()).collect::<Vec<_>>(), vec![Some(7), Some(8)]);
+    assert_eq!(errors[0].to_string(), "line 7: invalid input `c0` for `a`");
+
+    assert_eq!(mappings.len(), 2);
+    assert_eq!(mappings.get(&PAD.to_uppercase()).unwrap().name(), native_pad_name());
+    assert_eq!(mappings.get(OTHER).unwrap().platform(), None);
+
+    assert!("0300000000000000000000000000000".parse::<GamepadMapping>().is_err());
+    assert!(format!("{},,a:b0", PAD).parse::<GamepadMapping>().is_err());
+    assert!(format!("{},Pad,a", PAD).parse::<GamepadMapping>().is_err());
+    let errors = mappings.load_str(&format!("{},Pad,a:éb0,\n", PAD));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].to_string(), "line 1: invalid input `éb0` for `a`");
+    // Keys GLFW does not use are kept as they are
+    let mapping: GamepadMapping = format!("{},Pad,misc1:b15,crc:1234,", PAD).parse().unwrap();
+    assert_eq!(mapping.to_string(), format!("{},Pad,misc1:b15,crc:1234,", PAD));
+}
+
+#[test]
+fn overrides_take_precedence() {
+    let mut mappings = GamepadMappings::new();
+    mappings.load_str(&database());
+    let custom: GamepadMapping = format!("{},Custom,a:b3", OTHER).parse().unwrap();
+    assert_eq!(mappings.set_override(custom.clone()), None);
+    assert_eq!(mappings.get(OTHER), Some(&custom));
+    assert_eq!(mappings.len(), 2);
+    assert_eq!(mappings.iter().map(GamepadMapping::name).collect::<Vec<_>>(),
+            vec![native_pad_name(), "Custom"]);
+
+    assert_eq!(mappings.remove_override(&OTHER.to_uppercase()), Some(custom));
+    assert_eq!(mappings.get(OTHER).unwrap().name(), "Other");
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn joysticks_are_matched_by_guid() {
+    use glfw_wrapper::mock;
+
+    let _lock = mock::lock();
+    let glfw = init(InitHints::default()).unwrap();
+    mock::connect_joystick(Joystick::One, mock::JoystickConfig {
+        guid: PAD.to_owned(),
+        ..Default::default()
+    });
+    mock::connect_joystick(Joystick::Three, mock::JoystickConfig::default());
+    glfw.poll_events(&mut |_| {}).unwrap();
+
+    let mut mappings = GamepadMappings::new();
+    mappings.load_str(&database());
+    mappings.apply(&glfw).unwrap();
+    assert!(glfw.is_joystick_gamepad(Joystick::One));
+    assert_eq!(glfw.get_gamepad_name(Joystick::One).unwrap(), native_pad_name());
+    assert_eq!(mappings.joystick_mapping(&glfw, Joystick::One).unwrap(), mappings.get(PAD));
+    assert_eq!(mappings.joystick_mapping(&glfw, Joystick::Two).unwrap(), None);
+
+    mappings.set_override(format!("{},Custom,a:b3", PAD).parse().unwrap());
+    mappings.apply(&glfw).unwrap();
+    assert_eq!(glfw.get_gamepad_name(Joystick::One).unwrap(), "Custom");
+    let assignments = mappings.assignments(&glfw).unwrap();
+    assert_eq!(assignments.len(), 2);
+    assert_eq!(assignments[0].0, Joystick::One);
+    assert_eq!(assignments[0].1.unwrap().name(), "Custom");
+    assert_eq!(assignments[1], (Joystick::Three, None));
+}
@@ -0,0 +1,615 @@
+//! Tests of the wrapper against the mock GLFW backend.
+//!
+//! Run with `cargo test --no-default-features --features mock`.
+
+#![cfg(feature = "mock")]
+
+extern crate glfw_wrapper;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use glfw_wrapper::*;
+use glfw_wrapper::mock;
+
+fn init() -> Glfw {
+    glfw_wrapper::init(InitHints::default()).unwrap()
+}
+
+fn create_window(glfw: &Glfw) -> Window<'_> {
+    glfw.create_window(&WindowHints::default(), 640, 480, "Test", None, None).unwrap()
+}
+
+fn poll(glfw: &Glfw) -> Vec<OwnedEvent> {
+    let mut events = vec![];
+    glfw.poll_events_into(&mut events).unwrap();
+    events
+}
+
+/// Sets a flag when dropped.
+struct DropFlag(Rc<Cell<bool>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+#[test]
+fn init_is_exclusive() {
+    let _lock = mock::lock();
+    let glfw = init();
+    match glfw_wrapper::init(InitHints::default()) {
+        Err(InitError::AlreadyInitialized) => {}
+        _ => panic!("second init should fail")
+    }
+    drop(glfw);
+    init();
+}
+
+#[test]
+fn init_failure_is_reported() {
+    let _lock = mock::lock();
+    mock::fail_next_init(ErrorKind::PlatformError, "no display");
+    match glfw_wrapper::init(InitHints::default()) {
+        Err(error @ InitError::Failed(_)) => {
+            assert_eq!(error.to_string(),
+                    "GLFW initialization failed: init failed: no display (PlatformError)");
+            let boxed: Box<dyn std::error::Error> = Box::new(error);
+            let e = boxed.source().unwrap().downcast_ref::<Error>().unwrap();
+            assert_eq!(e.kind, ErrorKind::PlatformError);
+            assert_eq!(e.description, "no display");
+            assert_eq!(e.function, "init");
+        }
+        _ => panic!("init should fail")
+    }
+    init();
+}
+
+#[test]
+fn errors_are_taken_once() {
+    let _lock = mock::lock();
+    let _glfw = init();
+    mock::raise_error(ErrorKind::PlatformError, "oops");
+    assert_eq!(get_error().unwrap_err().kind, ErrorKind::PlatformError);
+    assert!(get_error().is_ok());
+}
+
+#[test]
+fn errors_name_the_failing_function() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let window = create_window(&glfw);
+    mock::raise_error(ErrorKind::PlatformError, "oops");
+    let error = window.set_title("Title").unwrap_err();
+    assert_eq!(error.function, "Window::set_title");
+    assert_eq!(error.to_string(), "Window::set_title failed: oops (PlatformError)");
+}
+
+#[test]
+fn create_window_applies_hints() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let hints = WindowHints { decorated: false, ..Default::default() };
+    let window = glfw.create_window(&hints, 320, 200, "Test", None, None).unwrap();
+    assert_eq!(window.get_window_size().unwrap(), (320, 200));
+    assert!(!window.get_attribute(WindowAttribute::Decorated).unwrap());
+    assert!(window.get_attribute(WindowAttribute::Visible).unwrap());
+    assert!(glfw.window_exists(window.id()));
+    assert_eq!(mock::window_count(), 1);
+}
+
+#[test]
+fn create_window_reports_errors() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let error = glfw.create_window(&WindowHints::default(), 0, 480, "Test", None, None)
+            .unwrap_err();
+    assert_eq!(error.kind, ErrorKind::InvalidValue);
+
+    let hints = WindowHints { context_version: (5, 0), ..Default::default() };
+    let error = glfw.create_window(&hints, 640, 480, "Test", None, None).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::VersionUnavailable);
+    assert_eq!(mock::window_count(), 0);
+}
+
+#[test]
+fn poll_events_delivers_input_in_order() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let window = create_window(&glfw);
+    poll(&glfw);
+
+    mock::press_key(&window, KeyCode::A, Modifiers::SHIFT);
+    mock::type_char(&window, 'A', Modifiers::SHIFT);
+    mock::release_key(&window, KeyCode::A, Modifiers::empty());
+    assert_eq!(poll(&glfw), vec![
+        OwnedEvent::KeyDown {
+            win: window.id(),
+            key: Key::Named(KeyCode::A),
+            scancode: mock::scancode(KeyCode::A),
+            modifiers: Modifiers::SHIFT
+        },
+        OwnedEvent::CharTypedWithModifiers {
+            win: window.id(),
+            character: 'A',
+            modifiers: Modifiers::SHIFT
+        },
+        OwnedEvent::CharTyped(window.id(), 'A'),
+        OwnedEvent::KeyUp {
+            win: window.id(),
+            key: Key::Named(KeyCode::A),
+            scancode: mock::scancode(KeyCode::A),
+            modifiers: Modifiers::empty()
+        }
+    ]);
+    assert!(poll(&glfw).is_empty());
+}
+
+#[test]
+fn close_requests_set_the_flag() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let window = create_window(&glfw);
+    mock::request_close(&window);
+    assert!(!window.should_close());
+    assert!(poll(&glfw).contains(&OwnedEvent::WindowCloseRequested(window.id())));
+    assert!(window.should_close());
+}
+
+#[test]
+fn window_destruction_is_deferred_during_event_processing() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let mut window = Some(create_window(&glfw));
+    let id = window.as_ref().unwrap().id();
+    mock::request_close(window.as_ref().unwrap());
+    mock::request_refresh(window.as_ref().unwrap());
+
+    let mut refreshed = false;
+    glfw.poll_events(&mut |event| match event {
+        Event::WindowCloseRequested(_) => {
+            window.take();
+            assert_eq!(mock::window_count(), 1);
+        }
+        Event::WindowRefresh(_) => refreshed = true,
+        _ => {}
+    }).unwrap();
+
+    // GLFW still delivers events for the window until it is really destroyed
+    assert!(refreshed);
+    assert_eq!(mock::window_count(), 0);
+    assert!(!glfw.window_exists(id));
+}
+
+#[test]
+fn monitor_disconnection_invalidates_handles() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let monitor = glfw.get_primary_monitor().unwrap();
+    let dropped = Rc::new(Cell::new(false));
+    monitor.set_user_data(DropFlag(dropped.clone()));
+
+    mock::connect_monitor(mock::MonitorConfig {
+        name: "Second".to_owned(),
+        ..Default::default()
+    });
+    assert!(mock::disconnect_monitor("Mock Monitor"));
+    assert_eq!(poll(&glfw), vec![
+        OwnedEvent::MonitorConnected("Second".to_owned()),
+        OwnedEvent::MonitorDisconnected("Mock Monitor".to_owned())
+    ]);
+
+    assert!(!monitor.is_valid());
+    assert!(dropped.get());
+    assert_eq!(glfw.get_primary_monitor().unwrap().get_name(), "Second");
+}
+
+#[test]
+fn joystick_user_data_is_dropped_on_disconnect() {
+    let _lock = mock::lock();
+    let glfw = init();
+    mock::connect_joystick(Joystick::One, mock::JoystickConfig {
+        axes: vec![0.5],
+        ..Default::default()
+    });
+    assert_eq!(poll(&glfw), vec![OwnedEvent::JoystickConnected(Joystick::One)]);
+    assert_eq!(glfw.get_joystick_axes(Joystick::One).unwrap(), Some(vec![0.5]));
+
+    let dropped = Rc::new(Cell::new(false));
+    assert!(glfw.set_joystick_user_data(Joystick::One, DropFlag(dropped.clone())));
+    mock::disconnect_joystick(Joystick::One);
+    let mut dropped_before_handler = false;
+    glfw.poll_events(&mut |event| if let Event::JoystickDisconnected(_) = event {
+        dropped_before_handler = dropped.get();
+    }).unwrap();
+    assert!(dropped_before_handler);
+    assert!(!glfw.is_joystick_present(Joystick::One).unwrap());
+
+    // Disconnections found while polling a joystick happen outside event processing
+    mock::connect_joystick(Joystick::One, mock::JoystickConfig::default());
+    poll(&glfw);
+    let dropped = Rc::new(Cell::new(false));
+    assert!(glfw.set_joystick_user_data(Joystick::One, DropFlag(dropped.clone())));
+    mock::unplug_joystick(Joystick::One);
+    assert!(!glfw.is_joystick_present(Joystick::One).unwrap());
+    assert!(dropped.get());
+    assert_eq!(poll(&glfw), vec![]);
+}
+
+#[test]
+fn joystick_snapshots_reuse_their_buffers() {
+    let _lock = mock::lock();
+    let glfw = init();
+    mock::connect_joystick(Joystick::Two, mock::JoystickConfig {
+        name: "Stick".to_owned(),
+        axes: vec![0.25, -1.0],
+        buttons: vec![true],
+        hats: vec![JoystickHatState::DOWN],
+        ..Default::default()
+    });
+    mock::connect_joystick(Joystick::Four, mock::JoystickConfig::default());
+    poll(&glfw);
+    assert_eq!(glfw.present_joysticks().collect::<Result<Vec<_>>>().unwrap(),
+            vec![Joystick::Two, Joystick::Four]);
+    // Each joystick is checked for errors on its own
+    mock::raise_error(ErrorKind::PlatformError, "oops");
+    let mut present = glfw.present_joysticks();
+    assert_eq!(present.next().unwrap().unwrap_err().kind, ErrorKind::PlatformError);
+    assert_eq!(present.next().unwrap().unwrap(), Joystick::Two);
+    assert_eq!(present.next().unwrap().unwrap(), Joystick::Four);
+    assert!(present.next().is_none());
+
+    let mut snapshot = JoystickSnapshot::new();
+    assert!(glfw.capture_joystick(Joystick::Two, &mut snapshot).unwrap());
+    assert_eq!(snapshot, JoystickSnapshot {
+        name: "Stick".to_owned(),
+        guid: "03000000000000000000000000000000".to_owned(),
+        axes: vec![0.25, -1.0],
+        // The hat is also reported as four buttons
+        buttons: vec![true, false, false, true, false],
+        hats: vec![JoystickHatState::DOWN]
+    });
+    let axes = snapshot.axes.as_ptr();
+    mock::update_joystick(Joystick::Two, |c| c.axes[1] = 1.0);
+    assert!(glfw.capture_joystick(Joystick::Two, &mut snapshot).unwrap());
+    assert_eq!(snapshot.axes, vec![0.25, 1.0]);
+    assert_eq!(snapshot.axes.as_ptr(), axes);
+
+    assert!(!glfw.capture_joystick(Joystick::One, &mut snapshot).unwrap());
+    assert_eq!(snapshot, JoystickSnapshot::new());
+    mock::disconnect_joystick(Joystick::Two);
+    mock::disconnect_joystick(Joystick::Four);
+    poll(&glfw);
+    assert_eq!(glfw.present_joysticks().count(), 0);
+}
+
+#[test]
+fn gamepad_events_report_state_changes() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let released = GamepadState {
+        a_cross: false, b_circle: false, x_square: false, y_triangle: false,
+        left_bumper: false, right_bumper: false, back: false, start: false, guide: false,
+        left_stick: false, right_stick: false,
+        dpad_up: false, dpad_right: false, dpad_down: false, dpad_left: false,
+        left_stick_x: 0.0, left_stick_y: 0.0, right_stick_x: 0.0, right_stick_y: 0.0,
+        left_trigger: -1.0, right_trigger: -1.0
+    };
+    mock::connect_joystick(Joystick::One, mock::JoystickConfig {
+        gamepad: Some(GamepadState { b_circle: true, ..released }),
+        ..Default::default()
+    });
+    mock::connect_joystick(Joystick::Two, mock::JoystickConfig {
+        axes: vec![0.0],
+        buttons: vec![false, false],
+        hats: vec![JoystickHatState::CENTERED],
+        ..Default::default()
+    });
+    poll(&glfw);
+    glfw.enable_gamepad_events(0.1);
+    assert_eq!(poll(&glfw), vec![]);
+
+    mock::update_joystick(Joystick::One, |c| c.gamepad = Some(GamepadState {
+        a_cross: true,
+        left_stick_y: 0.05,
+        right_trigger: 1.0,
+        ..released
+    }));
+    mock::update_joystick(Joystick::Two, |c| {
+        c.buttons[1] = true;
+        c.hats[0] = JoystickHatState::UP | JoystickHatState::LEFT;
+    });
+    assert_eq!(poll(&glfw), vec![
+        OwnedEvent::GamepadButtonDown { joystick: Joystick::One, button: GamepadButton::A },
+        OwnedEvent::GamepadButtonUp { joystick: Joystick::One, button: GamepadButton::B },
+        OwnedEvent::GamepadAxisMoved {
+            joystick: Joystick::One,
+            axis: GamepadAxis::RightTrigger,
+            value: 1.0
+        },
+        OwnedEvent::JoystickButtonDown { joystick: Joystick::Two, button: 1 },
+        // Hats are also buttons after the real ones unless `joystick_hat_buttons` is unset
+        OwnedEvent::JoystickButtonDown { joystick: Joystick::Two, button: 2 },
+        OwnedEvent::JoystickButtonDown { joystick: Joystick::Two, button: 5 },
+        OwnedEvent::JoystickHatChanged {
+            joystick: Joystick::Two,
+            hat: 0,
+            state: JoystickHatState::UP | JoystickHatState::LEFT
+        }
+    ]);
+
+    // Small movements add up until they pass the epsilon
+    mock::update_joystick(Joystick::Two, |c| c.axes[0] = 0.08);
+    assert_eq!(poll(&glfw), vec![]);
+    mock::update_joystick(Joystick::Two, |c| c.axes[0] = 0.16);
+    assert_eq!(poll(&glfw), vec![
+        OwnedEvent::JoystickAxisMoved { joystick: Joystick::Two, axis: 0, value: 0.16 }
+    ]);
+
+    glfw.disable_gamepad_events();
+    mock::update_joystick(Joystick::Two, |c| c.buttons[1] = false);
+    assert_eq!(poll(&glfw), vec![]);
+    mock::disconnect_joystick(Joystick::One);
+    mock::disconnect_joystick(Joystick::Two);
+    poll(&glfw);
+}
+
+#[test]
+fn gamepad_events_can_be_reset_from_disconnections_found_while_polling() {
+    let _lock = mock::lock();
+    let glfw = init();
+    mock::connect_joystick(Joystick::One, mock::JoystickConfig {
+        buttons: vec![false],
+        ..Default::default()
+    });
+    poll(&glfw);
+    glfw.enable_gamepad_events(0.1);
+
+    mock::unplug_joystick(Joystick::One);
+    let mut events = vec![];
+    glfw.poll_events(&mut |event| {
+        if let Event::JoystickDisconnected(_) = event {
+            glfw.enable_gamepad_events(0.5);
+        }
+        events.push(OwnedEvent::from(&event));
+    }).unwrap();
+    assert_eq!(events, vec![OwnedEvent::JoystickDisconnected(Joystick::One)]);
+    glfw.disable_gamepad_events();
+}
+
+#[test]
+fn deferred_drops_happen_when_a_gamepad_event_handler_panics() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let _lock = mock::lock();
+    let glfw = init();
+    let mut window = create_window(&glfw);
+    let dropped = Rc::new(Cell::new(false));
+    window.set_user_data(DropFlag(dropped.clone()));
+    mock::connect_joystick(Joystick::One, mock::JoystickConfig {
+        buttons: vec![false],
+        ..Default::default()
+    });
+    poll(&glfw);
+    glfw.enable_gamepad_events(0.1);
+
+    mock::update_joystick(Joystick::One, |c| c.buttons[0] = true);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        glfw.poll_events(&mut |event| if let Event::JoystickButtonDown { .. } = event {
+            window.clear_user_data();
+            panic!("handler failed");
+        })
+    }));
+    assert!(result.is_err());
+    assert!(dropped.get());
+
+    glfw.disable_gamepad_events();
+    mock::disconnect_joystick(Joystick::One);
+    poll(&glfw);
+}
+
+#[test]
+fn fullscreen_modes_restore_the_windowed_state() {
+    let _lock = mock::lock();
+    let glfw = init();
+    mock::connect_monitor(mock::MonitorConfig {
+        name: "Second".to_owned(),
+        position: (1920, 0),
+        ..Default::default()
+    });
+    let window = create_window(&glfw);
+    window.set_pos(2000, 100).unwrap();
+    poll(&glfw);
+    assert_eq!(window.current_monitor().unwrap().unwrap().get_name(), "Second");
+
+    window.toggle_fullscreen().unwrap();
+    assert!(window.is_fullscreen());
+    assert_eq!(window.get_pos().unwrap(), (1920, 0));
+    assert_eq!(window.get_window_size().unwrap(), (1920, 1080));
+    assert!(!window.get_attribute(WindowAttribute::Decorated).unwrap());
+    assert!(window.get_monitor().is_none());
+
+    let primary = glfw.get_primary_monitor().unwrap();
+    let mode = primary.get_video_modes().unwrap()[1];
+    window.set_fullscreen(FullscreenMode::Exclusive(primary, mode)).unwrap();
+    assert_eq!(window.get_monitor().unwrap().get_name(), "Mock Monitor");
+    assert_eq!(window.get_window_size().unwrap(), (1280, 720));
+
+    window.set_fullscreen(FullscreenMode::Windowed).unwrap();
+    assert!(!window.is_fullscreen());
+    assert_eq!(window.get_pos().unwrap(), (2000, 100));
+    assert_eq!(window.get_window_size().unwrap(), (640, 480));
+    assert!(window.get_attribute(WindowAttribute::Decorated).unwrap());
+
+    window.maximize().unwrap();
+    window.toggle_fullscreen().unwrap();
+    window.toggle_fullscreen().unwrap();
+    assert!(window.get_attribute(WindowAttribute::Maximized).unwrap());
+    window.restore().unwrap();
+    assert_eq!(window.get_pos().unwrap(), (2000, 100));
+}
+
+#[test]
+fn windows_created_fullscreen_are_centered_when_made_windowed() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let monitor = glfw.get_primary_monitor().unwrap();
+    let window = glfw.create_window(&WindowHints::default(), 1280, 720, "Test", Some(monitor),
+            None).unwrap();
+    assert!(window.is_fullscreen());
+
+    window.toggle_fullscreen().unwrap();
+    assert!(!window.is_fullscreen());
+    assert!(window.get_monitor().is_none());
+    // The work area leaves room for a taskbar below
+    assert_eq!(window.get_pos().unwrap(), (320, 165));
+    assert_eq!(window.get_window_size().unwrap(), (1280, 720));
+}
+
+#[test]
+fn placements_are_clamped_onto_connected_monitors() {
+    let _lock = mock::lock();
+    let glfw = init();
+    mock::connect_monitor(mock::MonitorConfig {
+        name: "Second".to_owned(),
+        position: (1920, 0),
+        workarea: (1920, 0, 1920, 1050),
+        ..Default::default()
+    });
+    let window = create_window(&glfw);
+    window.set_pos(2000, 100).unwrap();
+    poll(&glfw);
+    window.toggle_fullscreen().unwrap();
+    let placement = window.get_placement().unwrap();
+    assert_eq!(placement.position, (2000, 100));
+    assert_eq!(placement.size, (640, 480));
+    assert_eq!(placement.fullscreen, Some(FullscreenPlacement {
+        monitor_name: "Second".to_owned(),
+        monitor_position: (1920, 0),
+        exclusive_mode: None
+    }));
+
+    window.set_fullscreen(FullscreenMode::Windowed).unwrap();
+    window.set_pos(100, 100).unwrap();
+    window.set_placement(&placement).unwrap();
+    assert!(window.is_fullscreen());
+    assert_eq!(window.get_pos().unwrap(), (1920, 0));
+    window.set_fullscreen(FullscreenMode::Windowed).unwrap();
+    assert_eq!(window.get_pos().unwrap(), (2000, 100));
+
+    // Without the monitor, the frame is moved into the primary work area
+    assert!(mock::disconnect_monitor("Second"));
+    poll(&glfw);
+    window.set_placement(&placement).unwrap();
+    assert!(!window.is_fullscreen());
+    assert_eq!(window.get_pos().unwrap(), (1276, 100));
+    assert_eq!(window.get_window_size().unwrap(), (640, 480));
+
+    // Values from a corrupt file do not overflow
+    window.set_placement(&WindowPlacement {
+        position: (i32::MAX, i32::MIN),
+        size: (i32::MAX, -5),
+        ..placement.clone()
+    }).unwrap();
+    assert_eq!(window.get_pos().unwrap(), (4, 24));
+    assert_eq!(window.get_window_size().unwrap(), (1912, 1));
+
+    window.set_placement(&WindowPlacement {
+        position: (-50, -50),
+        size: (4000, 3000),
+        ..placement
+    }).unwrap();
+    assert_eq!(window.get_pos().unwrap(), (4, 24));
+    assert_eq!(window.get_window_size().unwrap(), (1912, 1022));
+}
+
+#[test]
+fn placement_helpers_position_the_frame() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let (left, top, _, _) = mock::FRAME_SIZE;
+    let parent = glfw.create_window(&WindowHints::default(), 800, 600, "Parent", None, None)
+            .unwrap();
+    parent.set_pos(100, 100).unwrap();
+    let window = create_window(&glfw);
+
+    window.center_on_monitor(&glfw.get_primary_monitor().unwrap()).unwrap();
+    assert_eq!(window.get_pos().unwrap(), (636 + left, 271 + top));
+    window.center_over(&parent).unwrap();
+    assert_eq!(window.get_pos().unwrap(), (180, 160));
+    window.cascade_from(&parent).unwrap();
+    assert_eq!(window.get_pos().unwrap(), (100 + top, 100 + top));
+
+    // Cascading past the bottom of the work area starts over at the top
+    parent.set_pos(1500, 900).unwrap();
+    window.cascade_from(&parent).unwrap();
+    assert_eq!(window.get_pos().unwrap(), (left, top));
+
+    window.set_pos(10, 400).unwrap();
+    assert!(window.snap_to_edges(8).unwrap());
+    assert_eq!(window.get_pos().unwrap(), (left, 400));
+    assert!(!window.snap_to_edges(8).unwrap());
+    poll(&glfw);
+}
+
+#[test]
+fn typed_accessors_convert_to_logical_pixels() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let window = create_window(&glfw);
+    poll(&glfw);
+    mock::set_content_scale(&window, 2.0, 2.0);
+    poll(&glfw);
+    assert_eq!(window.get_physical_size().unwrap(), PhysicalSize::new(640, 480));
+    assert_eq!(window.get_logical_size().unwrap(), LogicalSize::new(320.0, 240.0));
+    window.set_logical_size(LogicalSize::new(400.0, 300.0)).unwrap();
+    assert_eq!(window.get_window_size().unwrap(), (800, 600));
+    poll(&glfw);
+
+    mock::move_cursor(&window, 100.0, 50.0);
+    let mut positions = vec![];
+    glfw.poll_events(&mut |event| {
+        positions.push(event.logical_cursor_pos().unwrap());
+    }).unwrap();
+    assert_eq!(positions, vec![Some(LogicalPosition::new(50.0, 25.0))]);
+    assert_eq!(window.get_logical_cursor_pos().unwrap(), LogicalPosition::new(50.0, 25.0));
+
+    let monitor = glfw.get_primary_monitor().unwrap();
+    assert_eq!(monitor.get_physical_resolution().unwrap(), PhysicalSize::new(1920, 1080));
+    assert_eq!(monitor.get_logical_resolution().unwrap(), LogicalSize::new(1920.0, 1080.0));
+}
+
+#[test]
+fn typed_accessors_use_the_framebuffer_ratio() {
+    let _lock = mock::lock();
+    let glfw = init();
+    let window = create_window(&glfw);
+    // Screen coordinates are logical pixels, as on macOS
+    mock::set_framebuffer_scale(&window, 2);
+    mock::set_content_scale(&window, 2.0, 2.0);
+    window.set_pos(100, 60).unwrap();
+    poll(&glfw);
+    assert_eq!(window.get_physical_size().unwrap(), PhysicalSize::new(1280, 960));
+    assert_eq!(window.get_logical_size().unwrap(), LogicalSize::new(640.0, 480.0));
+    assert_eq!(window.get_physical_pos().unwrap(), PhysicalPosition::new(200.0, 120.0));
+    assert_eq!(window.get_logical_pos().unwrap(), LogicalPosition::new(100.0, 60.0));
+
+    window.set_logical_size(LogicalSize::new(400.0, 300.0)).unwrap();
+    assert_eq!(window.get_window_size().unwrap(), (400, 300));
+    window.set_logical_pos(LogicalPosition::new(50.0, 25.0)).unwrap();
+    assert_eq!(window.get_pos().unwrap(), (50, 25));
+    mock::move_cursor(&window, 100.0, 50.0);
+    let mut sizes = vec![];
+    let mut positions = vec![];
+    let mut cursor_positions = vec![];
+    glfw.poll_events(&mut |event| {
+        sizes.extend(event.physical_size().unwrap());
+        positions.extend(event.logical_pos().unwrap());
+        cursor_positions.extend(event.physical_cursor_pos().unwrap());
+    }).unwrap();
+    assert_eq!(sizes, vec![PhysicalSize::new(800, 600), PhysicalSize::new(800, 600)]);
+    assert_eq!(positions, vec![LogicalPosition::new(50.0, 25.0)]);
+    assert_eq!(cursor_positions, vec![PhysicalPosition::new(200.0, 100.0)]);
+    assert_eq!(window.get_logical_cursor_pos().unwrap(), LogicalPosition::new(100.0, 50.0));
+}
@@ -0,0 +1,93 @@
+//! Round trips of event recordings, using the mock backend to produce input.
+
+#![cfg(feature = "mock")]
+
+extern crate glfw_wrapper;
+
+use std::io;
+
+use glfw_wrapper::*;
+use glfw_wrapper::mock;
+
+#[test]
+fn recordings_replay_in_order_and_on_time() {
+    let _lock = mock::lock();
+    let glfw = glfw_wrapper::init(InitHints::default()).unwrap();
+    let window = glfw.create_window(&WindowHints::default(), 640, 480, "Test", None, None)
+            .unwrap();
+    glfw.poll_events(&mut |_| {}).unwrap();
+
+    let mut recorder = EventRecorder::new(&glfw, vec![]).unwrap();
+    let mut recorded = vec![];
+    mock::press_key(&window, KeyCode::W, Modifiers::empty());
+    mock::drop_paths(&window, &["/tmp/level.map"]);
+    glfw.poll_events(&mut recorder.wrap(&glfw, &mut |e| recorded.push(OwnedEvent::from(&e))))
+            .unwrap();
+    mock::advance_time(1.0);
+    mock::move_cursor(&window, 12.5, 40.0);
+    mock::connect_joystick(Joystick::Two, Default::default());
+    glfw.poll_events(&mut recorder.wrap(&glfw, &mut |e| recorded.push(OwnedEvent::from(&e))))
+            .unwrap();
+    let data = recorder.finish().unwrap();
+    assert_eq!(recorded.len(), 4);
+
+    // Replay into a different window, as a new run of the program would have
+    let replay_window = glfw.create_window(&WindowHints::default(), 640, 480, "Test", None, None)
+            .unwrap();
+    let mut player = EventPlayer::new(&data[..]).unwrap();
+    player.map_window(0, replay_window.id());
+    assert_eq!(player.duration(), 1.0);
+
+    let mut played = vec![];
+    player.play_until(0.5, &mut |e| played.push(e));
+    assert_eq!(played.len(), 2);
+    player.play_until(1.0, &mut |e| played.push(e));
+    assert!(player.is_finished());
+
+    let expected: Vec<_> = recorded.iter().map(|e| match *e {
+        OwnedEvent::KeyDown { key, scancode, modifiers, .. } =>
+                OwnedEvent::KeyDown { win: replay_window.id(), key, scancode, modifiers },
+        OwnedEvent::FileDrop(_, ref paths) => OwnedEvent::FileDrop(replay_window.id(), paths.clone()),
+        OwnedEvent::CursorMoved { x, y, .. } =>
+                OwnedEvent::CursorMoved { win: replay_window.id(), x, y },
+        ref e => e.clone()
+    }).collect();
+    assert_eq!(played, expected);
+}
+
+#[test]
+fn events_for_unmapped_windows_are_skipped() {
+    let _lock = mock::lock();
+    let glfw = glfw_wrapper::init(InitHints::default()).unwrap();
+    let window = glfw.create_window(&WindowHints::default(), 640, 480, "Test", None, None)
+            .unwrap();
+    glfw.poll_events(&mut |_| {}).unwrap();
+
+    let mut recorder = EventRecorder::new(&glfw, vec![]).unwrap();
+    recorder.record(glfw.get_time(), &OwnedEvent::WindowRefresh(window.id()));
+    recorder.record(glfw.get_time(), &OwnedEvent::JoystickDisconnected(Joystick::One));
+    let data = recorder.finish().unwrap();
+
+    let mut played = vec![];
+    EventPlayer::new(&data[..]).unwrap().play_until(0.0, &mut |e| played.push(e));
+    assert_eq!(played, vec![OwnedEvent::JoystickDisconnected(Joystick::One)]);
+}
+
+#[test]
+fn invalid_recordings_are_rejected() {
+    let error = EventPlayer::new(&b"not a recording"[..]).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    let mut data = b"GLFWREC\0\x01\x00".to_vec();
+    assert!(EventPlayer::new(&data[..]).unwrap().is_finished());
+    data.extend_from_slice(&0f64.to_le_bytes());
+    data.push(200);
+    let error = EventPlayer::new(&data[..]).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    // Truncated record
+    data.pop();
+    data.push(4);
+    let error = EventPlayer::new(&data[..]).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+}
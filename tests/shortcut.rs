@@ -0,0 +1,107 @@
+extern crate glfw_wrapper;
+extern crate enum_primitive;
+
+use enum_primitive::FromPrimitive;
+use glfw_wrapper::*;
+
+fn all_key_codes() -> Vec<KeyCode> {
+    (0..400).filter_map(KeyCode::from_i32).collect()
+}
+
+#[test]
+fn every_key_code_round_trips() {
+    let codes = all_key_codes();
+    assert_eq!(codes.len(), 120);
+    for code in codes {
+        let key = Key::Named(code);
+        assert_eq!(key.to_string().parse(), Ok(key));
+        for &style in &[ShortcutStyle::Standard, ShortcutStyle::Mac] {
+            let shortcut = Shortcut::new(Modifiers::CONTROL | Modifiers::SHIFT, key);
+            assert_eq!(shortcut.label(style).parse(), Ok(shortcut));
+        }
+    }
+    let unnamed = Shortcut::new(Modifiers::ALT, Key::Unnamed(38));
+    assert_eq!(unnamed.to_string().parse(), Ok(unnamed));
+}
+
+#[test]
+fn shortcuts_use_the_platform_names() {
+    let shortcut: Shortcut = "shift+ctrl+super+s".parse().unwrap();
+    assert_eq!(shortcut.label(ShortcutStyle::Standard), "Ctrl+Shift+Super+S");
+    assert_eq!(shortcut.label(ShortcutStyle::Mac), "Ctrl+Shift+Cmd+S");
+    assert_eq!("Cmd+Option+1".parse::<Shortcut>().unwrap().label(ShortcutStyle::Standard),
+            "Alt+Super+1");
+    assert_eq!("Ctrl+=".parse(), Ok(Shortcut::new(Modifiers::CONTROL, Key::Named(KeyCode::Equal))));
+    assert_eq!("F5".parse(), Ok(Shortcut::new(Modifiers::empty(), Key::Named(KeyCode::F5))));
+
+    assert!("Ctrl+".parse::<Shortcut>().is_err());
+    assert!("Hyper+A".parse::<Shortcut>().is_err());
+    assert!("CapsLock+A".parse::<Shortcut>().is_err());
+}
+
+#[test]
+fn non_ascii_names_are_rejected() {
+    assert!("aaaaaaaa€".parse::<Key>().is_err());
+    assert!("Ctrl+aaaaaaaa€".parse::<Shortcut>().is_err());
+    assert!("€+S".parse::<Shortcut>().is_err());
+    assert!("save = Ctrl+aaaaaaaa€".parse::<Bindings>().is_err());
+}
+
+#[test]
+fn modifiers_round_trip() {
+    let all = Modifiers::all();
+    assert_eq!(all.to_string(), "Ctrl+Alt+Shift+Super+CapsLock+NumLock");
+    assert_eq!(all.to_string().parse(), Ok(all));
+    assert_eq!("".parse(), Ok(Modifiers::empty()));
+    assert_eq!("control + cmd".parse(), Ok(Modifiers::CONTROL | Modifiers::SUPER));
+}
+
+#[test]
+fn shortcuts_write_keys_and_modifiers_like_their_own_display() {
+    let zero = Key::Named(KeyCode::Zero);
+    assert_eq!(KeyCode::Zero.to_string(), "0");
+    assert_eq!(zero.to_string(), "0");
+    assert_eq!("Zero".parse(), Ok(KeyCode::Zero));
+    assert_eq!("0".parse(), Ok(KeyCode::Zero));
+
+    let shortcut = Shortcut::new(Modifiers::CONTROL | Modifiers::ALT, zero);
+    assert_eq!(shortcut.label(ShortcutStyle::Standard),
+            format!("{}+{}", shortcut.modifiers, shortcut.key));
+    assert_eq!(shortcut.label(ShortcutStyle::Standard), "Ctrl+Alt+0");
+    assert_eq!(shortcut.label(ShortcutStyle::Mac), "Ctrl+Option+0");
+}
+
+#[test]
+fn matching_ignores_lock_modifiers() {
+    let shortcut: Shortcut = "Ctrl+S".parse().unwrap();
+    let s = Key::Named(KeyCode::S);
+    assert!(shortcut.matches_key(s, Modifiers::CONTROL));
+    assert!(shortcut.matches_key(s, Modifiers::CONTROL | Modifiers::CAPSLOCK | Modifiers::NUMLOCK));
+    assert!(!shortcut.matches_key(s, Modifiers::CONTROL | Modifiers::SHIFT));
+    assert!(!shortcut.matches_key(s, Modifiers::empty()));
+    assert!(!shortcut.matches_key(Key::Named(KeyCode::A), Modifiers::CONTROL));
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn shortcuts_match_key_down_events() {
+    use glfw_wrapper::mock;
+
+    let _lock = mock::lock();
+    let glfw = init(InitHints::default()).unwrap();
+    let window = glfw.create_window(&WindowHints::default(), 640, 480, "Test", None, None)
+            .unwrap();
+    window.set_input_mode(SetInputMode::LockKeyMods(true)).unwrap();
+    let shortcut: Shortcut = "Ctrl+S".parse().unwrap();
+    glfw.poll_events(&mut |_| {}).unwrap();
+
+    // The mock reports the modifiers it is given, and strips the lock ones unless enabled
+    let mods = Modifiers::CONTROL | Modifiers::CAPSLOCK;
+    mock::press_key(&window, KeyCode::LeftControl, Modifiers::CAPSLOCK);
+    mock::press_key(&window, KeyCode::S, mods);
+    mock::repeat_key(&window, KeyCode::S, mods);
+    mock::release_key(&window, KeyCode::S, mods);
+    let mut matches = vec![];
+    glfw.poll_events(&mut |e| matches.push(shortcut.matches(&e))).unwrap();
+    assert_eq!(matches, vec![false, true, false, false]);
+}
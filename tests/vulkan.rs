@@ -0,0 +1,104 @@
+//! Checks the Vulkan entry points against the mock loader.
+
+#![cfg(all(feature = "vulkan", feature = "mock"))]
+
+extern crate glfw_wrapper;
+
+use std::ptr;
+
+use glfw_wrapper::*;
+use glfw_wrapper::mock;
+
+fn init() -> Glfw {
+    glfw_wrapper::init(InitHints::default()).unwrap()
+}
+
+#[test]
+fn required_extensions_match_support() {
+    let _lock = mock::lock();
+    let glfw = init();
+
+    assert!(glfw.vulkan_supported());
+    let extensions = glfw.get_required_instance_extensions().unwrap();
+    assert!(extensions.iter().any(|e| e == "VK_KHR_surface"));
+
+    mock::set_vulkan_supported(false);
+    assert!(!glfw.vulkan_supported());
+    let error = glfw.get_required_instance_extensions().unwrap_err();
+    assert_eq!(error.kind, ErrorKind::ApiUnavailable);
+    mock::set_vulkan_supported(true);
+}
+
+#[test]
+fn global_commands_are_loadable() {
+    let _lock = mock::lock();
+    let glfw = init();
+
+    let proc = unsafe {
+        glfw.get_instance_proc_address(ptr::null_mut(), "vkCreateInstance").unwrap()
+    };
+    assert!(!proc.is_null());
+    let proc = unsafe {
+        glfw.get_instance_proc_address(ptr::null_mut(), "vkNotACommand").unwrap()
+    };
+    assert!(proc.is_null());
+}
+
+#[test]
+fn instance_commands_require_an_instance() {
+    let _lock = mock::lock();
+    let glfw = init();
+
+    let proc = unsafe {
+        glfw.get_instance_proc_address(ptr::null_mut(), "vkDestroySurfaceKHR").unwrap()
+    };
+    assert!(proc.is_null());
+    let proc = unsafe {
+        glfw.get_instance_proc_address(mock::instance(), "vkDestroySurfaceKHR").unwrap()
+    };
+    assert!(!proc.is_null());
+}
+
+#[test]
+fn presentation_support_requires_a_loader() {
+    let _lock = mock::lock();
+    let glfw = init();
+
+    let supported = unsafe {
+        glfw.get_physical_device_presentation_support(mock::instance(), ptr::null_mut(), 0)
+    };
+    assert!(supported.unwrap());
+
+    mock::set_vulkan_supported(false);
+    let error = unsafe {
+        glfw.get_physical_device_presentation_support(mock::instance(), ptr::null_mut(), 0)
+    }.unwrap_err();
+    assert_eq!(error.kind, ErrorKind::ApiUnavailable);
+    assert_eq!(error.function, "SharedGlfw::get_physical_device_presentation_support");
+    mock::set_vulkan_supported(true);
+}
+
+#[test]
+fn surfaces_require_a_window_without_a_context() {
+    let _lock = mock::lock();
+    let glfw = init();
+
+    let hints = WindowHints { client_api: ClientApi::NoApi, ..Default::default() };
+    let window = glfw.create_window(&hints, 640, 480, "Test", None, None).unwrap();
+    let surface = unsafe { window.create_window_surface(mock::instance()).unwrap() };
+    assert_eq!(mock::surface_count(), 1);
+    assert_eq!(surface.instance(), mock::instance());
+    drop(surface);
+    assert_eq!(mock::surface_count(), 0);
+
+    let error = unsafe { window.create_window_surface(ptr::null_mut()).unwrap_err() };
+    assert_eq!(error.kind, ErrorKind::ApiUnavailable);
+    assert_eq!(mock::surface_count(), 0);
+
+    let window = glfw.create_window(&WindowHints::default(), 640, 480, "Test", None, None)
+            .unwrap();
+    let error = unsafe { window.create_window_surface(mock::instance()).unwrap_err() };
+    assert_eq!(error.kind, ErrorKind::InvalidValue);
+    assert_eq!(error.function, "SharedWindow::create_window_surface");
+    assert_eq!(mock::surface_count(), 0);
+}